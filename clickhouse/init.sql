@@ -3,19 +3,94 @@ USE workouts;
 
 CREATE TABLE workout_sets
 (
-    workout_id    UUID,
-    workout_name  String,
-    timezone      String DEFAULT 'Europe/Berlin',
-    start_date    DateTime64(3) DEFAULT now(),
-    end_date      DateTime64(3) DEFAULT now(),
-    exercise_id   UUID,
-    exercise_nr   UInt32,
-    exercise_name String,
-    set_id        UUID,
-    set_nr        UInt32,
-    weight        Float32 DEFAULT 0.0,
-    reps          UInt32,
-    rpe           Float32 DEFAULT 0.0
+    workout_id         UUID,
+    workout_name       String,
+    timezone           String DEFAULT 'Europe/Berlin',
+    start_date         DateTime64(3) DEFAULT now(),
+    end_date           DateTime64(3) DEFAULT now(),
+    exercise_id        UUID,
+    exercise_nr        UInt32,
+    exercise_name      String,
+    exercise_locale    LowCardinality(String) DEFAULT 'en',
+    canonical_exercise String DEFAULT exercise_name,
+    set_id             UUID,
+    set_nr             UInt32,
+    weight             Float32 DEFAULT 0.0,
+    weight_unit        LowCardinality(String) DEFAULT '',
+    load_type          LowCardinality(String) DEFAULT '',
+    bar_weight         Float32 DEFAULT 0.0,
+    total_load         Float32 DEFAULT 0.0,
+    effective_load     Float32 DEFAULT 0.0,
+    reps               UInt32,
+    rpe                Float32 DEFAULT 0.0,
+    distance           Float32 DEFAULT 0.0,
+    distance_unit      LowCardinality(String) DEFAULT ''
 )
     ENGINE = ReplacingMergeTree()
 ORDER BY (start_date, workout_id, exercise_nr, exercise_id, set_nr);
+
+CREATE TABLE one_rep_maxes
+(
+    workout_id         UUID,
+    start_date         DateTime64(3),
+    exercise_id        UUID,
+    measurement_id     String,
+    canonical_exercise String,
+    formula            LowCardinality(String),
+    set_id             UUID,
+    effective_load     Float32,
+    reps               UInt32,
+    rpe                Float32 DEFAULT 0.0,
+    one_rep_max        Float32
+)
+    ENGINE = ReplacingMergeTree()
+ORDER BY (canonical_exercise, formula, start_date, workout_id);
+
+CREATE TABLE personal_records
+(
+    kind               LowCardinality(String),
+    canonical_exercise String,
+    measurement_id     String,
+    workout_id         UUID,
+    start_date         DateTime64(3),
+    exercise_id        UUID,
+    set_id             Nullable(UUID),
+    effective_load     Float32 DEFAULT 0.0,
+    value              Float32,
+    previous           Nullable(Float32)
+)
+    ENGINE = ReplacingMergeTree()
+ORDER BY (canonical_exercise, kind, start_date, workout_id, effective_load);
+
+CREATE TABLE daily_workload
+(
+    user_id            String,
+    measure            LowCardinality(String),
+    date               Date,
+    load               Float32,
+    acute              Float32,
+    chronic            Float32,
+    acwr               Nullable(Float32),
+    monotony           Nullable(Float32),
+    strain             Nullable(Float32),
+    flag               LowCardinality(String) DEFAULT ''
+)
+    ENGINE = ReplacingMergeTree()
+ORDER BY (user_id, measure, date);
+
+CREATE TABLE cardio
+(
+    workout_id         UUID,
+    start_date         DateTime64(3),
+    exercise_id        UUID,
+    measurement_id     String,
+    canonical_exercise String,
+    set_id             UUID,
+    distance           Float32 DEFAULT 0.0,
+    distance_unit      LowCardinality(String) DEFAULT '',
+    duration           UInt32 DEFAULT 0,
+    pace               Nullable(Float32),
+    speed              Nullable(Float32)
+)
+    ENGINE = ReplacingMergeTree()
+ORDER BY (canonical_exercise, start_date, workout_id, set_id);
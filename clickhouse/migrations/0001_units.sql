@@ -0,0 +1,7 @@
+-- Weights are stored in kilograms and distances in kilometers, the unit the set
+-- was logged in is kept next to them. Rows saved before this migration didn't
+-- record it, so they are left unlabelled until the next fetch replaces them.
+ALTER TABLE workouts.workout_sets
+    ADD COLUMN IF NOT EXISTS weight_unit LowCardinality(String) DEFAULT '' AFTER weight,
+    ADD COLUMN IF NOT EXISTS distance Float32 DEFAULT 0.0 AFTER rpe,
+    ADD COLUMN IF NOT EXISTS distance_unit LowCardinality(String) DEFAULT '' AFTER distance;
@@ -0,0 +1,10 @@
+-- Load actually moved per set: the logged weight for barbells, dumbbells and machines,
+-- body weight plus or minus the logged weight for weighted and assisted bodyweight exercises.
+--
+-- Rows written before this migration can't be classified: weighted bodyweight sets were saved
+-- with only the added weight, indistinguishable from external loads. They are left with an
+-- empty load_type and no effective_load. Run the fetch again afterwards, ReplacingMergeTree
+-- replaces them with the fetched rows.
+ALTER TABLE workouts.workout_sets
+    ADD COLUMN IF NOT EXISTS load_type LowCardinality(String) DEFAULT '' AFTER weight_unit,
+    ADD COLUMN IF NOT EXISTS effective_load Float32 DEFAULT 0.0 AFTER load_type;
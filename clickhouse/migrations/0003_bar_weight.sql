@@ -0,0 +1,8 @@
+-- Bar plus base weight configured for the exercise and the logged weight including them.
+ALTER TABLE workouts.workout_sets
+    ADD COLUMN IF NOT EXISTS bar_weight Float32 DEFAULT 0.0 AFTER load_type,
+    ADD COLUMN IF NOT EXISTS total_load Float32 DEFAULT 0.0 AFTER bar_weight;
+
+-- Strong logs the weight including the bar, so for existing rows the total load is the weight.
+ALTER TABLE workouts.workout_sets
+    UPDATE total_load = weight WHERE total_load = 0 AND weight > 0;
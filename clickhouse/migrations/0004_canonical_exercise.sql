@@ -0,0 +1,4 @@
+-- Name the exercise is reported under after applying the user's exercise aliases.
+-- Rows written before this migration default to their exercise name.
+ALTER TABLE workouts.workout_sets
+    ADD COLUMN IF NOT EXISTS canonical_exercise String DEFAULT exercise_name AFTER exercise_name;
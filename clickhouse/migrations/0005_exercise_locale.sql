@@ -0,0 +1,4 @@
+-- Locale the exercise name was taken from, e.g. 'de', 'en' or 'custom'.
+-- Rows written before this migration used the English name, or the custom one for custom exercises.
+ALTER TABLE workouts.workout_sets
+    ADD COLUMN IF NOT EXISTS exercise_locale LowCardinality(String) DEFAULT 'en' AFTER exercise_name;
@@ -0,0 +1,17 @@
+-- Best estimated one-rep max per exercise, workout and formula.
+CREATE TABLE IF NOT EXISTS workouts.one_rep_maxes
+(
+    workout_id         UUID,
+    start_date         DateTime64(3),
+    exercise_id        UUID,
+    measurement_id     String,
+    canonical_exercise String,
+    formula            LowCardinality(String),
+    set_id             UUID,
+    effective_load     Float32,
+    reps               UInt32,
+    rpe                Float32 DEFAULT 0.0,
+    one_rep_max        Float32
+)
+    ENGINE = ReplacingMergeTree()
+ORDER BY (canonical_exercise, formula, start_date, workout_id);
@@ -0,0 +1,17 @@
+-- Personal records per exercise: heaviest weight, most reps at a weight, best e1RM and best
+-- set and session volume. `previous` is the record that was beaten, NULL for the first one.
+CREATE TABLE IF NOT EXISTS workouts.personal_records
+(
+    kind               LowCardinality(String),
+    canonical_exercise String,
+    measurement_id     String,
+    workout_id         UUID,
+    start_date         DateTime64(3),
+    exercise_id        UUID,
+    set_id             Nullable(UUID),
+    effective_load     Float32 DEFAULT 0.0,
+    value              Float32,
+    previous           Nullable(Float32)
+)
+    ENGINE = ReplacingMergeTree()
+ORDER BY (canonical_exercise, kind, start_date, workout_id, effective_load);
@@ -0,0 +1,17 @@
+-- Daily acute and chronic workload, ACWR, monotony and strain per user. `flag` is
+-- `below_band` or `above_band` when the ACWR is outside ACWR_LOW to ACWR_HIGH.
+CREATE TABLE IF NOT EXISTS workouts.daily_workload
+(
+    user_id            String,
+    measure            LowCardinality(String),
+    date               Date,
+    load               Float32,
+    acute              Float32,
+    chronic            Float32,
+    acwr               Nullable(Float32),
+    monotony           Nullable(Float32),
+    strain             Nullable(Float32),
+    flag               LowCardinality(String) DEFAULT ''
+)
+    ENGINE = ReplacingMergeTree()
+ORDER BY (user_id, measure, date);
@@ -0,0 +1,18 @@
+-- Completed sets with a distance or a duration. Distances are in kilometers, durations in
+-- seconds, `pace` in seconds per kilometer and `speed` in kilometers per hour.
+CREATE TABLE IF NOT EXISTS workouts.cardio
+(
+    workout_id         UUID,
+    start_date         DateTime64(3),
+    exercise_id        UUID,
+    measurement_id     String,
+    canonical_exercise String,
+    set_id             UUID,
+    distance           Float32 DEFAULT 0.0,
+    distance_unit      LowCardinality(String) DEFAULT 'km',
+    duration           UInt32 DEFAULT 0,
+    pace               Nullable(Float32),
+    speed              Nullable(Float32)
+)
+    ENGINE = ReplacingMergeTree()
+ORDER BY (canonical_exercise, start_date, workout_id, set_id);
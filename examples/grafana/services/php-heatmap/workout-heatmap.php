@@ -2,7 +2,7 @@
 /**
  * Workout Heatmap (PNG)
  *
- * Renders a GitHub-style contribution heatmap of training tonnage (weight × reps)
+ * Renders a GitHub-style contribution heatmap of training tonnage (effective load × reps)
  * from a ClickHouse database. Output is a PNG image with transparent or solid background.
  *
  * -----------------------------------------------------------------------------
@@ -376,7 +376,7 @@ class ClickHouseClient {
         WITH '" . $tzName . "' AS tz
         SELECT
           toDate(toTimeZone(start_date, tz)) AS day,
-          sumIf(weight*reps, weight>0)       AS tonnage
+          sumIf(effective_load*reps, effective_load>0) AS tonnage
         FROM workouts.workout_sets
         WHERE start_date >= parseDateTimeBestEffort('$fromSql')
           AND start_date <  parseDateTimeBestEffort('$toSql')
@@ -2,9 +2,13 @@ use clickhouse::Row;
 use clickhouse::insert::Insert;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
+use strong_api_lib::analytics::cardio::cardio_sets;
+use strong_api_lib::analytics::one_rep_max::best_one_rep_maxes;
+use strong_api_lib::analytics::personal_records::PersonalRecord;
+use strong_api_lib::analytics::workload::DailyWorkload;
 use strong_api_lib::data_transformer::Workout;
-use time::OffsetDateTime;
 use time::format_description::well_known::Rfc3339;
+use time::{Date, OffsetDateTime};
 use uuid::Uuid;
 
 /// This flattened struct represents one set with its workout and exercise context.
@@ -22,17 +26,105 @@ pub struct WorkoutSet {
     pub exercise_id: Uuid,
     pub exercise_nr: u32,
     pub exercise_name: String,
+    pub exercise_locale: String,
+    pub canonical_exercise: String,
     #[serde(with = "clickhouse::serde::uuid")]
     pub set_id: Uuid,
     pub set_nr: u32,
     pub weight: f32,
+    pub weight_unit: String,
+    pub load_type: String,
+    pub bar_weight: f32,
+    pub total_load: f32,
+    pub effective_load: f32,
     pub reps: u32,
     pub rpe: f32,
+    pub distance: f32,
+    pub distance_unit: String,
+}
+
+/// The best estimated one-rep max of an exercise in a workout with one formula.
+#[derive(Row, Serialize, Deserialize, Debug)]
+pub struct OneRepMaxRow {
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub workout_id: Uuid,
+    #[serde(with = "clickhouse::serde::time::datetime64::millis")]
+    pub start_date: OffsetDateTime,
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub exercise_id: Uuid,
+    pub measurement_id: String,
+    pub canonical_exercise: String,
+    pub formula: String,
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub set_id: Uuid,
+    pub effective_load: f32,
+    pub reps: u32,
+    pub rpe: f32,
+    pub one_rep_max: f32,
+}
+
+/// A personal record and the workout and set it was set in.
+#[derive(Row, Serialize, Deserialize, Debug)]
+pub struct PersonalRecordRow {
+    pub kind: String,
+    pub canonical_exercise: String,
+    pub measurement_id: String,
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub workout_id: Uuid,
+    #[serde(with = "clickhouse::serde::time::datetime64::millis")]
+    pub start_date: OffsetDateTime,
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub exercise_id: Uuid,
+    #[serde(with = "clickhouse::serde::uuid::option")]
+    pub set_id: Option<Uuid>,
+    pub effective_load: f32,
+    pub value: f32,
+    pub previous: Option<f32>,
+}
+
+/// The workload, ACWR, monotony and strain of one day.
+#[derive(Row, Serialize, Deserialize, Debug)]
+pub struct DailyWorkloadRow {
+    pub user_id: String,
+    pub measure: String,
+    #[serde(with = "clickhouse::serde::time::date")]
+    pub date: Date,
+    pub load: f32,
+    pub acute: f32,
+    pub chronic: f32,
+    pub acwr: Option<f32>,
+    pub monotony: Option<f32>,
+    pub strain: Option<f32>,
+    pub flag: String,
+}
+
+/// A completed set with a distance or a duration, with its pace and speed.
+#[derive(Row, Serialize, Deserialize, Debug)]
+pub struct CardioRow {
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub workout_id: Uuid,
+    #[serde(with = "clickhouse::serde::time::datetime64::millis")]
+    pub start_date: OffsetDateTime,
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub exercise_id: Uuid,
+    pub measurement_id: String,
+    pub canonical_exercise: String,
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub set_id: Uuid,
+    pub distance: f32,
+    pub distance_unit: String,
+    pub duration: u32,
+    pub pace: Option<f32>,
+    pub speed: Option<f32>,
 }
 
 pub struct ClickHouseSaver {
     client: clickhouse::Client,
     table_name: String,
+    one_rep_max_table: String,
+    personal_record_table: String,
+    workload_table: String,
+    cardio_table: String,
 }
 
 impl ClickHouseSaver {
@@ -50,9 +142,37 @@ impl ClickHouseSaver {
                 .with_password(password)
                 .with_database(database),
             table_name: table_name.to_string(),
+            one_rep_max_table: "one_rep_maxes".to_string(),
+            personal_record_table: "personal_records".to_string(),
+            workload_table: "daily_workload".to_string(),
+            cardio_table: "cardio".to_string(),
         }
     }
 
+    /// Sets the table the estimated one-rep maxes are saved to, `one_rep_maxes` by default.
+    pub fn with_one_rep_max_table(mut self, table_name: &str) -> Self {
+        self.one_rep_max_table = table_name.to_string();
+        self
+    }
+
+    /// Sets the table personal records are saved to, `personal_records` by default.
+    pub fn with_personal_record_table(mut self, table_name: &str) -> Self {
+        self.personal_record_table = table_name.to_string();
+        self
+    }
+
+    /// Sets the table the daily workload is saved to, `daily_workload` by default.
+    pub fn with_workload_table(mut self, table_name: &str) -> Self {
+        self.workload_table = table_name.to_string();
+        self
+    }
+
+    /// Sets the table cardio sets are saved to, `cardio` by default.
+    pub fn with_cardio_table(mut self, table_name: &str) -> Self {
+        self.cardio_table = table_name.to_string();
+        self
+    }
+
     /// Saves a given workout into ClickHouse by flattening its nested data into rows.
     ///
     /// # Arguments
@@ -94,11 +214,20 @@ impl ClickHouseSaver {
                     exercise_id: Uuid::parse_str(&exercise.id).expect("exercise UUID parse failed"),
                     exercise_nr,
                     exercise_name: exercise.name.clone(),
+                    exercise_locale: exercise.name_locale.clone(),
+                    canonical_exercise: exercise.canonical_name.clone(),
                     set_id: Uuid::parse_str(&set.id).expect("set UUID parse failed"),
                     set_nr,
                     weight: set.weight.unwrap_or(0.0),
+                    weight_unit: set.weight_unit.to_string(),
+                    load_type: set.load_type.to_string(),
+                    bar_weight: set.bar_weight.unwrap_or(0.0),
+                    total_load: set.total_load.unwrap_or(0.0),
+                    effective_load: set.effective_load.unwrap_or(0.0),
                     reps: set.reps,
                     rpe: set.rpe.unwrap_or(0.0),
+                    distance: set.distance.unwrap_or(0.0),
+                    distance_unit: set.distance_unit.to_string(),
                 };
                 // debug print set.rpe.unwrap_or(0.0)
                 println!("Inserting row: {:?}", row);
@@ -112,4 +241,133 @@ impl ClickHouseSaver {
         println!("Workout {} imported successfully", workout.id);
         Ok(())
     }
+
+    /// Saves the best estimated one-rep max per exercise and formula of a workout.
+    pub async fn save_one_rep_maxes(&self, workout: &Workout) -> Result<(), Box<dyn Error>> {
+        let best = best_one_rep_maxes(workout);
+        if best.is_empty() {
+            return Ok(());
+        }
+
+        let start_date =
+            OffsetDateTime::parse(&workout.start_date.clone().unwrap_or_default(), &Rfc3339)?;
+        let mut insert: Insert<OneRepMaxRow> = self.client.insert(&self.one_rep_max_table)?;
+        for one_rep_max in best {
+            let row = OneRepMaxRow {
+                workout_id: Uuid::parse_str(&one_rep_max.workout_id)?,
+                start_date,
+                exercise_id: Uuid::parse_str(&one_rep_max.exercise_id)?,
+                measurement_id: one_rep_max.measurement_id,
+                canonical_exercise: one_rep_max.exercise,
+                formula: one_rep_max.formula.to_string(),
+                set_id: Uuid::parse_str(&one_rep_max.set_id)?,
+                effective_load: one_rep_max.effective_load,
+                reps: one_rep_max.reps,
+                rpe: one_rep_max.rpe.unwrap_or(0.0),
+                one_rep_max: one_rep_max.one_rep_max,
+            };
+            insert.write(&row).await?;
+        }
+        insert.end().await?;
+
+        Ok(())
+    }
+
+    /// Saves the sets of a workout with a distance or a duration.
+    pub async fn save_cardio(&self, workout: &Workout) -> Result<(), Box<dyn Error>> {
+        let sets = cardio_sets(std::slice::from_ref(workout));
+        if sets.is_empty() {
+            return Ok(());
+        }
+
+        let start_date =
+            OffsetDateTime::parse(&workout.start_date.clone().unwrap_or_default(), &Rfc3339)?;
+        let mut insert: Insert<CardioRow> = self.client.insert(&self.cardio_table)?;
+        for set in &sets {
+            let row = CardioRow {
+                workout_id: Uuid::parse_str(&set.workout_id)?,
+                start_date,
+                exercise_id: Uuid::parse_str(&set.exercise_id)?,
+                measurement_id: set.measurement_id.clone(),
+                canonical_exercise: set.exercise.clone(),
+                set_id: Uuid::parse_str(&set.set_id)?,
+                distance: set.distance.unwrap_or(0.0),
+                distance_unit: set.distance_unit.to_string(),
+                duration: set.duration.unwrap_or(0),
+                pace: set.pace(),
+                speed: set.speed(),
+            };
+            insert.write(&row).await?;
+        }
+        insert.end().await?;
+
+        Ok(())
+    }
+
+    /// Saves the personal records of the timeline.
+    pub async fn save_personal_records(
+        &self,
+        records: &[PersonalRecord],
+    ) -> Result<(), Box<dyn Error>> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        let mut insert: Insert<PersonalRecordRow> =
+            self.client.insert(&self.personal_record_table)?;
+        for record in records {
+            let row = PersonalRecordRow {
+                kind: record.kind.to_string(),
+                canonical_exercise: record.exercise.clone(),
+                measurement_id: record.measurement_id.clone(),
+                workout_id: Uuid::parse_str(&record.workout_id)?,
+                start_date: OffsetDateTime::parse(
+                    &record.start_date.clone().unwrap_or_default(),
+                    &Rfc3339,
+                )?,
+                exercise_id: Uuid::parse_str(&record.exercise_id)?,
+                set_id: record.set_id.as_deref().map(Uuid::parse_str).transpose()?,
+                effective_load: record.effective_load.unwrap_or(0.0),
+                value: record.value,
+                previous: record.previous,
+            };
+            insert.write(&row).await?;
+        }
+        insert.end().await?;
+
+        println!("{} personal records imported successfully", records.len());
+        Ok(())
+    }
+
+    /// Saves the daily workload series of a user.
+    pub async fn save_daily_workload(
+        &self,
+        user_id: &str,
+        series: &[DailyWorkload],
+    ) -> Result<(), Box<dyn Error>> {
+        if series.is_empty() {
+            return Ok(());
+        }
+
+        let mut insert: Insert<DailyWorkloadRow> = self.client.insert(&self.workload_table)?;
+        for day in series {
+            let row = DailyWorkloadRow {
+                user_id: user_id.to_string(),
+                measure: day.measure.to_string(),
+                date: day.date,
+                load: day.load,
+                acute: day.acute,
+                chronic: day.chronic,
+                acwr: day.acwr,
+                monotony: day.monotony,
+                strain: day.strain,
+                flag: day.flag.map(|flag| flag.to_string()).unwrap_or_default(),
+            };
+            insert.write(&row).await?;
+        }
+        insert.end().await?;
+
+        println!("{} days of workload imported successfully", series.len());
+        Ok(())
+    }
 }
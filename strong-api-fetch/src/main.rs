@@ -1,12 +1,28 @@
 mod clickhouse_saver;
+mod report;
 
 use dotenvy::dotenv;
+use report::{ReportFormat, render_comparison, render_year_in_review_html};
 use reqwest::Url;
 use std::env;
 use std::fs;
 use std::path::Path;
+use strong_api_lib::analytics::calendar;
+use strong_api_lib::analytics::comparison::{Period, compare_periods};
+use strong_api_lib::analytics::frequency::training_frequency;
+use strong_api_lib::analytics::one_rep_max::OneRepMaxFormula;
+use strong_api_lib::analytics::personal_records::personal_record_timeline;
+use strong_api_lib::analytics::progression::{ProgressionOptions, detect_progression};
+use strong_api_lib::analytics::scores::{BigThree, ScoreOptions, Sex, strength_scores};
+use strong_api_lib::analytics::workload::{WorkloadMeasure, WorkloadOptions, daily_workload};
+use strong_api_lib::analytics::year_in_review::year_in_review;
+use strong_api_lib::data_transformer::aliases::ExerciseAliases;
+use strong_api_lib::data_transformer::body_weight::BodyWeight;
+use strong_api_lib::data_transformer::csv_export::workouts_to_csv;
 use strong_api_lib::data_transformer::{DataTransformer, Workout};
+use strong_api_lib::models::measured_value::MeasuredValue;
 use strong_api_lib::models::measurement::MeasurementsResponse;
+use strong_api_lib::models::workout::UserResponse;
 use strong_api_lib::strong_api::{Includes, StrongApi};
 
 #[tokio::main]
@@ -21,22 +37,45 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Load configuration from environment variables.
     let config = load_config()?;
-    let url = Url::parse(&config.strong_backend).expect("STRONG_BACKEND is not a valid URL");
 
-    // Initialize the API and ClickHouse saver.
-    let mut strong_api = StrongApi::new(url);
+    // Initialize the ClickHouse saver.
     let clickhouse_saver = create_clickhouse_saver(&config);
 
-    // Log in to the API.
-    strong_api
-        .login(config.username.as_str(), config.password.as_str())
-        .await?;
+    // Get the measurements and the user, from the API unless workouts are imported from a CSV.
+    let (measurements_response, user) = match &config.csv_import {
+        Some(path) => {
+            println!("Importing workouts from {path}");
+            let user = UserResponse {
+                id: config.csv_user_id.clone().unwrap_or_default(),
+                ..Default::default()
+            };
+            (read_measurements_file()?.unwrap_or_default(), user)
+        }
+        None => {
+            let (Some(backend), Some(username), Some(password)) =
+                (&config.strong_backend, &config.username, &config.password)
+            else {
+                return Err(
+                    "STRONG_BACKEND, STRONG_USER and STRONG_PASS must be set unless CSV_IMPORT is set"
+                        .into(),
+                );
+            };
+            let url = Url::parse(backend).expect("STRONG_BACKEND is not a valid URL");
+            let mut strong_api = StrongApi::new(url);
+
+            // Log in to the API.
+            strong_api.login(username, password).await?;
 
-    // Get the measurements (either from file or API).
-    let measurements_response = get_measurements_response(&mut strong_api).await?;
+            // Get the measurements (either from file or API).
+            let measurements_response = get_measurements_response(&mut strong_api).await?;
 
-    // Fetch user data with logs.
-    let user = strong_api.get_user("", 500, vec![Includes::Log]).await?;
+            // Fetch user data with logs.
+            let user = strong_api
+                .get_user("", 500, vec![Includes::Log, Includes::MeasuredValue])
+                .await?;
+            (measurements_response, user)
+        }
+    };
 
     println!(
         "Measurements count: {}/{}",
@@ -45,75 +84,326 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     );
 
     // Transform the measurements into workouts.
-    let data_transformer = DataTransformer::new().with_measurements_response(measurements_response);
-    let workouts = data_transformer
-        .get_measurements_from_logs(&user.embedded.log)
-        .expect("Couldn't read workouts");
+    let mut data_transformer = DataTransformer::new()
+        .with_measurements_response(measurements_response.clone())
+        .with_preferences(&user.id, user.preferences.clone())
+        .with_body_weights(get_body_weights(&config, &user))
+        .with_custom_bar_types(user.legacy_custom_bar_types.clone())
+        .with_logged_weight_includes_bar(!config.weight_excludes_bar)
+        .with_locales(config.locales.clone());
+    if let Some(path) = &config.exercise_aliases {
+        data_transformer =
+            data_transformer.with_exercise_aliases(ExerciseAliases::from_file(path)?);
+    }
+    if let Some(default_body_weight) = config.default_body_weight {
+        data_transformer = data_transformer.with_default_body_weight(default_body_weight);
+    }
+    let (workouts, diagnostics) = match &config.csv_import {
+        Some(path) => data_transformer.workouts_from_csv_with_diagnostics(
+            fs::File::open(path)?,
+            config.csv_timezone.as_deref(),
+        )?,
+        None => data_transformer
+            .get_workouts_with_diagnostics(&user.embedded.log)
+            .expect("Couldn't read workouts"),
+    };
 
     println!("Workout count: {}", workouts.len());
+    println!("{diagnostics}");
+
+    if config.fail_on_diagnostics && !diagnostics.is_empty() {
+        return Err(format!(
+            "Aborting, transformation reported {} issue(s)",
+            diagnostics.len()
+        )
+        .into());
+    }
+
+    // Summarize the training frequency in the timezone of the latest workout.
+    let timezone = workouts
+        .iter()
+        .max_by_key(|workout| workout.start_time())
+        .and_then(|workout| workout.timezone.as_deref());
+    let today = calendar::today(timezone);
+    let frequency = training_frequency(
+        &workouts,
+        user.first_weekday(),
+        user.workouts_per_week_goal(),
+        today,
+    );
+    println!("{frequency}");
+
+    let workload_options = WorkloadOptions::new()
+        .with_measure(config.workload_measure)
+        .with_acwr_band(config.acwr_low, config.acwr_high);
+    let workload = daily_workload(&workouts, &workload_options, today);
+    if let Some(day) = workload.last()
+        && let (Some(acwr), Some(flag)) = (day.acwr, day.flag)
+    {
+        println!(
+            "Warning: ACWR of {acwr:.2} is {flag} ({} to {})",
+            config.acwr_low, config.acwr_high
+        );
+    }
+
+    let mut progression_options = ProgressionOptions::new();
+    if let Some(weeks) = config.progression_weeks {
+        progression_options = progression_options.with_window_weeks(weeks);
+    }
+    let progression = detect_progression(&workouts, &progression_options);
+    println!("{progression}");
+    if let Some(path) = &config.progression_report {
+        fs::write(path, serde_json::to_string_pretty(&progression)?)?;
+    }
+
+    if let Some(compare_period) = &config.compare_period {
+        let current = match compare_period.as_str() {
+            "week" => Period::week_of(today, user.first_weekday()),
+            "month" => Period::month_of(today),
+            days => Period::days_ending(
+                today,
+                days.parse()
+                    .expect("COMPARE_PERIOD must be week, month or a number of days"),
+            ),
+        };
+        let previous = match compare_period.as_str() {
+            "month" => current.previous_month(),
+            _ => current.preceding(),
+        };
+        let comparison = compare_periods(
+            &workouts,
+            &measurements_response,
+            current,
+            previous,
+            OneRepMaxFormula::Epley,
+        );
+        let report = render_comparison(&comparison, config.report_format)?;
+        match &config.comparison_report {
+            Some(path) => fs::write(path, report)?,
+            None => println!("{report}"),
+        }
+    }
+
+    if let Some(year) = config.year_in_review {
+        let path = config
+            .year_in_review_report
+            .clone()
+            .unwrap_or_else(|| format!("year-in-review-{year}.html"));
+        let review = year_in_review(&workouts, year)
+            .ok_or_else(|| format!("YEAR_IN_REVIEW {year} is out of range"))?;
+        fs::write(&path, render_year_in_review_html(&review))?;
+        println!("Wrote the year in review of {year} to {path}");
+    }
+
+    if let Some(path) = &config.csv_export {
+        let written = workouts_to_csv(&workouts, fs::File::create(path)?)?;
+        println!("Wrote {written} workouts to {path}");
+    }
+
+    if let Some(path) = &config.big_three {
+        let score_options = ScoreOptions::new()
+            .with_sex(config.athlete_sex)
+            .with_big_three(BigThree::from_file(path)?);
+        let athlete = user.name.as_deref().unwrap_or(&user.username);
+        println!("{}", strength_scores(athlete, &workouts, &score_options));
+    }
 
     // Save each workout using the ClickHouse saver.
     save_workouts(&workouts, &clickhouse_saver).await?;
 
+    // Save the personal records of the whole history.
+    let personal_records = personal_record_timeline(&workouts, OneRepMaxFormula::Epley);
+    clickhouse_saver
+        .save_personal_records(&personal_records)
+        .await?;
+    clickhouse_saver
+        .save_daily_workload(&user.id, &workload)
+        .await?;
+
     Ok(())
 }
 
 /// Holds all configuration values loaded from the environment.
 struct Config {
-    username: String,
-    password: String,
-    strong_backend: String,
+    username: Option<String>,
+    password: Option<String>,
+    strong_backend: Option<String>,
+    csv_import: Option<String>,
+    csv_timezone: Option<String>,
+    csv_user_id: Option<String>,
     clickhouse_url: String,
     clickhouse_user: String,
     clickhouse_pass: String,
     clickhouse_database: String,
     clickhouse_table: String,
+    clickhouse_one_rep_max_table: Option<String>,
+    clickhouse_personal_record_table: Option<String>,
+    clickhouse_workload_table: Option<String>,
+    clickhouse_cardio_table: Option<String>,
+    fail_on_diagnostics: bool,
+    body_weight_measurement_id: Option<String>,
+    default_body_weight: Option<f32>,
+    weight_excludes_bar: bool,
+    exercise_aliases: Option<String>,
+    locales: Vec<String>,
+    workload_measure: WorkloadMeasure,
+    acwr_low: f32,
+    acwr_high: f32,
+    progression_weeks: Option<u32>,
+    progression_report: Option<String>,
+    big_three: Option<String>,
+    athlete_sex: Sex,
+    compare_period: Option<String>,
+    report_format: ReportFormat,
+    comparison_report: Option<String>,
+    year_in_review: Option<i32>,
+    year_in_review_report: Option<String>,
+    csv_export: Option<String>,
 }
 
 /// Load configuration values from environment variables.
 fn load_config() -> Result<Config, Box<dyn std::error::Error>> {
     Ok(Config {
-        username: env::var("STRONG_USER").expect("STRONG_USER must be set"),
-        password: env::var("STRONG_PASS").expect("STRONG_PASS must be set"),
-        strong_backend: env::var("STRONG_BACKEND").expect("STRONG_BACKEND must be set"),
+        username: env::var("STRONG_USER").ok(),
+        password: env::var("STRONG_PASS").ok(),
+        strong_backend: env::var("STRONG_BACKEND").ok(),
+        csv_import: env::var("CSV_IMPORT").ok(),
+        csv_timezone: env::var("CSV_TIMEZONE").ok(),
+        csv_user_id: env::var("CSV_USER_ID").ok(),
         clickhouse_url: env::var("CLICKHOUSE_URL").expect("CLICKHOUSE_URL must be set"),
         clickhouse_user: env::var("CLICKHOUSE_USER").expect("CLICKHOUSE_USER must be set"),
         clickhouse_pass: env::var("CLICKHOUSE_PASS").expect("CLICKHOUSE_PASS must be set"),
         clickhouse_database: env::var("CLICKHOUSE_DATABASE")
             .expect("CLICKHOUSE_DATABASE must be set"),
         clickhouse_table: env::var("CLICKHOUSE_TABLE").expect("CLICKHOUSE_TABLE must be set"),
+        clickhouse_one_rep_max_table: env::var("CLICKHOUSE_ONE_REP_MAX_TABLE").ok(),
+        clickhouse_personal_record_table: env::var("CLICKHOUSE_PERSONAL_RECORD_TABLE").ok(),
+        clickhouse_workload_table: env::var("CLICKHOUSE_WORKLOAD_TABLE").ok(),
+        clickhouse_cardio_table: env::var("CLICKHOUSE_CARDIO_TABLE").ok(),
+        fail_on_diagnostics: env_flag("FAIL_ON_DIAGNOSTICS"),
+        body_weight_measurement_id: env::var("BODY_WEIGHT_MEASUREMENT_ID").ok(),
+        default_body_weight: env::var("DEFAULT_BODY_WEIGHT")
+            .ok()
+            .map(|value| value.parse().expect("DEFAULT_BODY_WEIGHT must be a number")),
+        weight_excludes_bar: env_flag("WEIGHT_EXCLUDES_BAR"),
+        exercise_aliases: env::var("EXERCISE_ALIASES").ok(),
+        locales: env::var("LOCALES")
+            .map(|value| {
+                value
+                    .split(',')
+                    .map(|locale| locale.trim().to_string())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        workload_measure: match env::var("WORKLOAD_MEASURE").as_deref() {
+            Ok("session_rpe") => WorkloadMeasure::SessionRpe,
+            Ok("tonnage") | Err(_) => WorkloadMeasure::Tonnage,
+            Ok(_) => panic!("WORKLOAD_MEASURE must be tonnage or session_rpe"),
+        },
+        acwr_low: env::var("ACWR_LOW")
+            .map(|value| value.parse().expect("ACWR_LOW must be a number"))
+            .unwrap_or(0.8),
+        acwr_high: env::var("ACWR_HIGH")
+            .map(|value| value.parse().expect("ACWR_HIGH must be a number"))
+            .unwrap_or(1.3),
+        progression_weeks: env::var("PROGRESSION_WEEKS")
+            .ok()
+            .map(|value| value.parse().expect("PROGRESSION_WEEKS must be a number")),
+        progression_report: env::var("PROGRESSION_REPORT").ok(),
+        big_three: env::var("BIG_THREE").ok(),
+        athlete_sex: match env::var("ATHLETE_SEX").as_deref() {
+            Ok("male") | Err(_) => Sex::Male,
+            Ok("female") => Sex::Female,
+            Ok(_) => panic!("ATHLETE_SEX must be male or female"),
+        },
+        compare_period: env::var("COMPARE_PERIOD").ok(),
+        report_format: env::var("REPORT_FORMAT")
+            .map(|value| {
+                ReportFormat::parse(&value).expect("REPORT_FORMAT must be text, markdown or json")
+            })
+            .unwrap_or(ReportFormat::Text),
+        comparison_report: env::var("COMPARISON_REPORT").ok(),
+        year_in_review: env::var("YEAR_IN_REVIEW")
+            .ok()
+            .map(|value| value.parse().expect("YEAR_IN_REVIEW must be a year")),
+        year_in_review_report: env::var("YEAR_IN_REVIEW_REPORT").ok(),
+        csv_export: env::var("CSV_EXPORT").ok(),
     })
 }
 
+/// Reads an optional boolean flag from the environment, `1` and `true` enable it.
+fn env_flag(name: &str) -> bool {
+    env::var(name)
+        .map(|value| matches!(value.to_lowercase().as_str(), "1" | "true"))
+        .unwrap_or(false)
+}
+
 /// Create a new ClickHouseSaver instance using the provided configuration.
 fn create_clickhouse_saver(config: &Config) -> clickhouse_saver::ClickHouseSaver {
-    clickhouse_saver::ClickHouseSaver::new(
+    let mut saver = clickhouse_saver::ClickHouseSaver::new(
         config.clickhouse_url.as_str(),
         config.clickhouse_user.as_str(),
         config.clickhouse_pass.as_str(),
         config.clickhouse_database.as_str(),
         config.clickhouse_table.as_str(),
-    )
+    );
+    if let Some(table_name) = &config.clickhouse_one_rep_max_table {
+        saver = saver.with_one_rep_max_table(table_name);
+    }
+    if let Some(table_name) = &config.clickhouse_personal_record_table {
+        saver = saver.with_personal_record_table(table_name);
+    }
+    if let Some(table_name) = &config.clickhouse_workload_table {
+        saver = saver.with_workload_table(table_name);
+    }
+    if let Some(table_name) = &config.clickhouse_cardio_table {
+        saver = saver.with_cardio_table(table_name);
+    }
+    saver
 }
 
 /// Retrieve the measurements response either by reading from a file or fetching from the API.
 async fn get_measurements_response(
     strong_api: &mut StrongApi,
 ) -> Result<MeasurementsResponse, Box<dyn std::error::Error>> {
+    if let Some(measurements_response) = read_measurements_file()? {
+        return Ok(measurements_response);
+    }
+    println!("Fetching measurements from API");
+    let measurements_response_page1 = strong_api.get_measurements(1).await?;
+    let measurements_response_page2 = strong_api.get_measurements(2).await?;
+    let measurements_response = measurements_response_page1.merge(measurements_response_page2);
+    let measurements_json = serde_json::to_string(&measurements_response)?;
+    fs::write("measurements.json", measurements_json)?;
+    Ok(measurements_response)
+}
+
+/// Read the measurements saved by an earlier fetch, `None` if there is no `measurements.json`.
+fn read_measurements_file() -> Result<Option<MeasurementsResponse>, Box<dyn std::error::Error>> {
     if !Path::new("measurements.json").exists() {
-        println!("Fetching measurements from API");
-        let measurements_response_page1 = strong_api.get_measurements(1).await?;
-        let measurements_response_page2 = strong_api.get_measurements(2).await?;
-        let measurements_response = measurements_response_page1.merge(measurements_response_page2);
-        let measurements_json = serde_json::to_string(&measurements_response)?;
-        fs::write("measurements.json", measurements_json)?;
-        Ok(measurements_response)
-    } else {
-        println!("Reading measurements from file");
-        let measurements_json = fs::read_to_string("measurements.json")?;
-        let measurements_response = serde_json::from_str(&measurements_json)?;
-        Ok(measurements_response)
+        return Ok(None);
     }
+    println!("Reading measurements from file");
+    let measurements_json = fs::read_to_string("measurements.json")?;
+    Ok(Some(serde_json::from_str(&measurements_json)?))
+}
+
+/// Extract the body weights from the user's measured values, if a body weight measurement is configured.
+fn get_body_weights(config: &Config, user: &UserResponse) -> Vec<BodyWeight> {
+    let Some(measurement_id) = &config.body_weight_measurement_id else {
+        return Vec::new();
+    };
+
+    let measured_values: Vec<MeasuredValue> = user
+        .embedded
+        .measured_value
+        .iter()
+        .flatten()
+        .filter_map(|value| serde_json::from_value(value.clone()).ok())
+        .collect();
+    let unit = user.preferences.weight_unit(&user.id, measurement_id);
+
+    BodyWeight::from_measured_values(&measured_values, measurement_id, unit)
 }
 
 /// Save all workouts to ClickHouse.
@@ -126,6 +416,14 @@ async fn save_workouts(
             .save_workout(workout)
             .await
             .expect("Couldn't save workout");
+        clickhouse_saver
+            .save_one_rep_maxes(workout)
+            .await
+            .expect("Couldn't save one-rep maxes");
+        clickhouse_saver
+            .save_cardio(workout)
+            .await
+            .expect("Couldn't save cardio sets");
     }
     Ok(())
 }
@@ -0,0 +1,271 @@
+use std::fmt::Write;
+use strong_api_lib::analytics::comparison::{Delta, PeriodComparison};
+use strong_api_lib::analytics::frequency::Streak;
+use strong_api_lib::analytics::year_in_review::YearInReview;
+
+/// How reports are written, set with `REPORT_FORMAT`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Text,
+    Markdown,
+    Json,
+}
+
+impl ReportFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "text" => Some(ReportFormat::Text),
+            "markdown" | "md" => Some(ReportFormat::Markdown),
+            "json" => Some(ReportFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Renders the comparison of two periods in the given format.
+pub fn render_comparison(
+    comparison: &PeriodComparison,
+    format: ReportFormat,
+) -> Result<String, serde_json::Error> {
+    match format {
+        ReportFormat::Text => Ok(comparison.to_string()),
+        ReportFormat::Markdown => Ok(comparison_markdown(comparison)),
+        ReportFormat::Json => serde_json::to_string_pretty(comparison),
+    }
+}
+
+fn comparison_markdown(comparison: &PeriodComparison) -> String {
+    let mut markdown = format!(
+        "# {} compared to {}\n\n| | Previous | Current | Change |\n|---|---:|---:|---:|\n",
+        comparison.current, comparison.previous
+    );
+    for (name, delta) in [
+        ("Workouts", &comparison.workouts),
+        ("Sets", &comparison.sets),
+        ("Reps", &comparison.reps),
+        ("Tonnage (kg)", &comparison.tonnage),
+        ("Hard sets", &comparison.hard_sets),
+        ("Personal records", &comparison.personal_record_count),
+    ] {
+        markdown_row(&mut markdown, name, delta);
+    }
+
+    if !comparison.sets_by_tag.is_empty() {
+        markdown.push_str(
+            "\n## Sets per muscle group\n\n| | Previous | Current | Change |\n|---|---:|---:|---:|\n",
+        );
+        for tag in &comparison.sets_by_tag {
+            markdown_row(&mut markdown, &tag.tag, &tag.sets);
+        }
+    }
+
+    if !comparison.one_rep_maxes.is_empty() {
+        markdown.push_str(
+            "\n## Estimated one-rep max (kg)\n\n| | Previous | Current | Change |\n|---|---:|---:|---:|\n",
+        );
+        let value = |one_rep_max: Option<f32>| {
+            one_rep_max
+                .map(|one_rep_max| format!("{one_rep_max:.1}"))
+                .unwrap_or_else(|| "-".to_string())
+        };
+        for exercise in &comparison.one_rep_maxes {
+            let _ = writeln!(
+                markdown,
+                "| {} | {} | {} | {} |",
+                exercise.exercise,
+                value(exercise.previous),
+                value(exercise.current),
+                percent(exercise.percent)
+            );
+        }
+    }
+
+    if !comparison.personal_records.is_empty() {
+        markdown.push_str("\n## New personal records\n\n");
+        for record in &comparison.personal_records {
+            let _ = writeln!(
+                markdown,
+                "- {} {}: {:.1} (was {:.1})",
+                record.exercise,
+                record.kind,
+                record.value,
+                record.previous.unwrap_or_default()
+            );
+        }
+    }
+    markdown
+}
+
+fn markdown_row(markdown: &mut String, name: &str, delta: &Delta) {
+    let _ = writeln!(
+        markdown,
+        "| {} | {:.0} | {:.0} | {} |",
+        name,
+        delta.previous,
+        delta.current,
+        percent(delta.percent)
+    );
+}
+
+fn percent(percent: Option<f32>) -> String {
+    percent
+        .map(|percent| format!("{percent:+.1}%"))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+const YEAR_IN_REVIEW_STYLE: &str = "\
+body { font-family: system-ui, sans-serif; margin: 2rem auto; max-width: 60rem; color: #222; }
+h1 { margin-bottom: 0.25rem; }
+.totals { display: flex; flex-wrap: wrap; gap: 1rem; margin: 1.5rem 0; }
+.total { background: #f3f4f6; border-radius: 0.5rem; padding: 0.75rem 1rem; min-width: 8rem; }
+.total strong { display: block; font-size: 1.5rem; }
+.heatmap { display: grid; grid-template-rows: repeat(7, 0.8rem); grid-auto-flow: column; \
+grid-auto-columns: 0.8rem; gap: 0.15rem; overflow-x: auto; }
+.day { border-radius: 0.15rem; background: #ebedf0; }
+.day.empty { background: none; }
+.level-1 { background: #c6e48b; } .level-2 { background: #7bc96f; }
+.level-3 { background: #239a3b; } .level-4 { background: #196127; }
+table { border-collapse: collapse; margin-bottom: 1.5rem; }
+th, td { padding: 0.25rem 0.75rem; text-align: left; border-bottom: 1px solid #e5e7eb; }
+td.number { text-align: right; }
+";
+
+/// Renders the year in review as a self-contained HTML page, styles included.
+pub fn render_year_in_review_html(review: &YearInReview) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{year} in review</title>\n<style>\n{YEAR_IN_REVIEW_STYLE}</style>\n</head>\n<body>\n\
+         <h1>{year} in review</h1>\n<div class=\"totals\">\n",
+        year = review.year
+    );
+    let streak = |streak: Option<Streak>, unit: &str| {
+        streak.map_or_else(
+            || "-".to_string(),
+            |streak| format!("{} {unit}", streak.length),
+        )
+    };
+    for (name, value) in [
+        ("Sessions", review.sessions.to_string()),
+        ("Hours trained", format!("{:.1}", review.hours)),
+        ("Tonnage", format!("{:.0} kg", review.tonnage)),
+        ("Sets", review.sets.to_string()),
+        ("Longest streak", streak(review.longest_streak, "days")),
+        (
+            "Longest weekly streak",
+            streak(review.longest_weekly_streak, "weeks"),
+        ),
+        (
+            "Busiest weekday",
+            review
+                .busiest_weekday
+                .map_or_else(|| "-".to_string(), |weekday| weekday.to_string()),
+        ),
+        (
+            "Busiest month",
+            review
+                .busiest_month
+                .map_or_else(|| "-".to_string(), |month| month.to_string()),
+        ),
+    ] {
+        let _ = writeln!(
+            html,
+            "<div class=\"total\"><strong>{}</strong>{}</div>",
+            value, name
+        );
+    }
+    html.push_str("</div>\n<h2>Heatmap</h2>\n<div class=\"heatmap\">\n");
+
+    // Weeks are columns starting on Monday, so the first week is padded up to January 1st.
+    let max_tonnage = review
+        .heatmap
+        .iter()
+        .map(|day| day.tonnage)
+        .fold(0.0, f32::max);
+    if let Some(first) = review.heatmap.first() {
+        for _ in 0..first.date.weekday().number_days_from_monday() {
+            html.push_str("<div class=\"day empty\"></div>\n");
+        }
+    }
+    for day in &review.heatmap {
+        let level = match (day.workouts, max_tonnage > 0.0) {
+            (0, _) => 0,
+            (_, false) => 1,
+            (_, true) => 1 + (day.tonnage / max_tonnage * 3.0).round() as u32,
+        };
+        let _ = writeln!(
+            html,
+            "<div class=\"day level-{}\" title=\"{}: {} workout(s), {:.0} kg\"></div>",
+            level.min(4),
+            day.date,
+            day.workouts,
+            day.tonnage
+        );
+    }
+    html.push_str("</div>\n");
+
+    if !review.favourite_exercises.is_empty() {
+        html.push_str(
+            "<h2>Favourite exercises</h2>\n<table>\n\
+             <tr><th>Exercise</th><th>Sessions</th><th>Sets</th></tr>\n",
+        );
+        for favourite in &review.favourite_exercises {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td class=\"number\">{}</td><td class=\"number\">{}</td></tr>",
+                escape_html(&favourite.exercise),
+                favourite.sessions,
+                favourite.sets
+            );
+        }
+        html.push_str("</table>\n");
+    }
+
+    if !review.biggest_records.is_empty() {
+        html.push_str(
+            "<h2>Biggest personal records</h2>\n<table>\n\
+             <tr><th>Exercise</th><th>Record</th><th>Before</th><th>After</th><th>Change</th></tr>\n",
+        );
+        for record in &review.biggest_records {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td class=\"number\">{:.1} kg</td>\
+                 <td class=\"number\">{:.1} kg</td><td class=\"number\">{:+.1}%</td></tr>",
+                escape_html(&record.exercise),
+                record.kind,
+                record.before,
+                record.after,
+                record.percent
+            );
+        }
+        html.push_str("</table>\n");
+    }
+
+    html.push_str("<h2>Workouts per weekday and month</h2>\n<table>\n");
+    for (name, workouts) in review
+        .workouts_by_weekday
+        .iter()
+        .map(|(weekday, workouts)| (weekday.to_string(), workouts))
+        .chain(
+            review
+                .workouts_by_month
+                .iter()
+                .map(|(month, workouts)| (month.to_string(), workouts)),
+        )
+    {
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td class=\"number\">{}</td></tr>",
+            name, workouts
+        );
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+    html
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
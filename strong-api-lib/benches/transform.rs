@@ -0,0 +1,183 @@
+//! Compares transforming a synthetic 10-year history in one batch with streaming it.
+//!
+//! Run with `cargo bench -p strong-api-lib --bench transform`. Peak memory is the highest
+//! amount of heap allocated during a run, not counting the JSON input itself.
+
+use serde_json::json;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use strong_api_lib::data_transformer::DataTransformer;
+use strong_api_lib::data_transformer::stream::read_logs;
+use strong_api_lib::models::measurement::MeasurementsResponse;
+use strong_api_lib::models::workout::Log;
+
+const YEARS: u32 = 10;
+const WORKOUTS_PER_WEEK: u32 = 4;
+const EXERCISES_PER_WORKOUT: u32 = 6;
+const SETS_PER_EXERCISE: u32 = 4;
+const RUNS: u32 = 5;
+
+struct CountingAllocator;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+fn track(allocated: usize, freed: usize) {
+    let current = CURRENT.fetch_add(allocated, Ordering::Relaxed) + allocated;
+    PEAK.fetch_max(current, Ordering::Relaxed);
+    CURRENT.fetch_sub(freed, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let pointer = unsafe { System.alloc(layout) };
+        if !pointer.is_null() {
+            track(layout.size(), 0);
+        }
+        pointer
+    }
+
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(pointer, layout) };
+        track(0, layout.size());
+    }
+
+    unsafe fn realloc(&self, pointer: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_pointer = unsafe { System.realloc(pointer, layout, new_size) };
+        if !new_pointer.is_null() {
+            track(new_size, layout.size());
+        }
+        new_pointer
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn measurements() -> MeasurementsResponse {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/measurements_response.json"
+    );
+    serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+/// Builds a JSON array of logs, with ids and values varying like a real history.
+fn synthetic_history(measurements: &MeasurementsResponse) -> Vec<u8> {
+    let measurement_ids: Vec<&str> = measurements
+        .embedded
+        .measurements
+        .iter()
+        .map(|measurement| measurement.id.as_str())
+        .collect();
+    let id = |kind: u32, n: u32| format!("{kind:08x}-0000-4000-8000-{n:012x}");
+
+    let workouts = YEARS * 52 * WORKOUTS_PER_WEEK;
+    let logs: Vec<_> = (0..workouts)
+        .map(|workout| {
+            let start = 1_420_070_400 + i64::from(workout) * 7 * 86_400 / i64::from(WORKOUTS_PER_WEEK);
+            let groups: Vec<_> = (0..EXERCISES_PER_WORKOUT)
+                .map(|exercise| {
+                    let group = workout * EXERCISES_PER_WORKOUT + exercise;
+                    let measurement_id =
+                        measurement_ids[(group as usize * 7) % measurement_ids.len()];
+                    let sets: Vec<_> = (0..SETS_PER_EXERCISE)
+                        .map(|set| {
+                            let n = group * SETS_PER_EXERCISE + set;
+                            json!({
+                                "id": id(3, n),
+                                "isCompleted": true,
+                                "cells": [
+                                    { "id": id(4, n), "cellType": "BARBELL_WEIGHT", "value": format!("{}", 40 + n % 80) },
+                                    { "id": id(5, n), "cellType": "REPS", "value": format!("{}", 3 + n % 10) },
+                                    { "id": id(6, n), "cellType": "RPE", "value": "8" },
+                                ],
+                            })
+                        })
+                        .collect();
+                    json!({
+                        "_links": { "measurement": { "href": format!("/api/users/u/measurements/{measurement_id}") } },
+                        "_embedded": {},
+                        "id": id(2, group),
+                        "cellSets": sets,
+                    })
+                })
+                .collect();
+            let date = time::OffsetDateTime::from_unix_timestamp(start).unwrap();
+            let date = date
+                .format(&time::format_description::well_known::Rfc3339)
+                .unwrap();
+            json!({
+                "_links": {},
+                "_embedded": { "cellSetGroup": groups },
+                "timezoneId": "Europe/Berlin",
+                "id": id(1, workout),
+                "created": date,
+                "lastChanged": date,
+                "name": { "en": format!("Workout {}", workout % 3) },
+                "access": "PRIVATE",
+                "startDate": date,
+                "endDate": date,
+                "logType": "WORKOUT",
+            })
+        })
+        .collect();
+
+    serde_json::to_vec(&logs).unwrap()
+}
+
+/// Runs `scenario` a few times and returns the fastest time and the peak heap above the
+/// memory in use before the run.
+fn measure(scenario: impl Fn() -> usize) -> (Duration, usize, usize) {
+    let mut fastest = Duration::MAX;
+    let mut peak = 0;
+    let mut sets = 0;
+    for _ in 0..RUNS {
+        let before = CURRENT.load(Ordering::Relaxed);
+        PEAK.store(before, Ordering::Relaxed);
+        let started = Instant::now();
+        sets = scenario();
+        fastest = fastest.min(started.elapsed());
+        peak = peak.max(PEAK.load(Ordering::Relaxed) - before);
+    }
+    (fastest, peak, sets)
+}
+
+fn main() {
+    let transformer = DataTransformer::new().with_measurements_response(measurements());
+    let history = synthetic_history(&measurements());
+    println!(
+        "synthetic history: {} workouts, {:.1} MiB of JSON",
+        YEARS * 52 * WORKOUTS_PER_WEEK,
+        history.len() as f64 / 1024.0 / 1024.0
+    );
+
+    let batch = || {
+        let logs: Vec<Log> = serde_json::from_slice(&history).unwrap();
+        let (workouts, _) = transformer
+            .get_workouts_with_diagnostics(&Some(logs))
+            .unwrap();
+        workouts
+            .iter()
+            .flat_map(|workout| &workout.exercises)
+            .map(|exercise| exercise.sets.len())
+            .sum()
+    };
+    let stream = || {
+        transformer
+            .workouts(read_logs(history.as_slice()).map(Result::unwrap))
+            .flat_map(|workout| workout.exercises)
+            .map(|exercise| exercise.sets.len())
+            .sum()
+    };
+
+    for (name, scenario) in [("batch", &batch as &dyn Fn() -> usize), ("stream", &stream)] {
+        let (time, peak, sets) = measure(scenario);
+        println!(
+            "{name:<8} {sets} sets in {:>8.1} ms, peak heap {:>10.1} KiB",
+            time.as_secs_f64() * 1000.0,
+            peak as f64 / 1024.0
+        );
+    }
+}
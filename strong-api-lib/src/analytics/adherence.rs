@@ -0,0 +1,264 @@
+//! How closely workouts followed the template they were started from.
+//!
+//! A workout is matched to the template its log links to. Workouts without a link, or with a
+//! link to a template that isn't known, are matched to the template with the most similar name
+//! and exercise sequence. Exercises are paired by measurement id, or by canonical name if they
+//! have none, and compared by their completed sets against the template's sets.
+
+use crate::data_transformer::{Exercise, Workout};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Score a template needs at least to be matched by similarity.
+pub const DEFAULT_MIN_SCORE: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchMethod {
+    /// The log links to the template.
+    Link,
+    /// The template has the most similar name and exercise sequence.
+    Similarity,
+}
+
+impl fmt::Display for MatchMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match self {
+            MatchMethod::Link => "link",
+            MatchMethod::Similarity => "similarity",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+/// The template a workout was most likely started from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateMatch {
+    pub template_id: String,
+    pub template_name: String,
+    pub method: MatchMethod,
+    /// 1 for a link. For similarity, half from the names being equal and half from the share
+    /// of exercises that appear in the same order in both.
+    pub score: f32,
+}
+
+/// Difference between what the template planned for an exercise and what was done. Loads are
+/// the heaviest logged weight in kilograms, `None` if the template or the workout has none.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExerciseDelta {
+    /// The exercise's `canonical_name`.
+    pub exercise: String,
+    pub measurement_id: String,
+    pub planned_sets: u32,
+    /// Completed sets.
+    pub done_sets: u32,
+    pub planned_reps: u32,
+    pub done_reps: u32,
+    pub planned_load: Option<f32>,
+    pub done_load: Option<f32>,
+}
+
+impl ExerciseDelta {
+    pub fn set_delta(&self) -> i64 {
+        i64::from(self.done_sets) - i64::from(self.planned_sets)
+    }
+
+    pub fn rep_delta(&self) -> i64 {
+        i64::from(self.done_reps) - i64::from(self.planned_reps)
+    }
+
+    pub fn load_delta(&self) -> Option<f32> {
+        Some(self.done_load? - self.planned_load?)
+    }
+}
+
+/// Adherence of one workout to its template.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Adherence {
+    pub workout_id: String,
+    pub workout_name: String,
+    pub start_date: Option<String>,
+    pub template: TemplateMatch,
+    /// Exercises of the template that were done, in template order.
+    pub exercises: Vec<ExerciseDelta>,
+    /// Canonical names of template exercises without a completed set.
+    pub skipped: Vec<String>,
+    /// Canonical names of done exercises the template doesn't have.
+    pub added: Vec<String>,
+}
+
+impl fmt::Display for Adherence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}) from template '{}' by {} ({:.2}): {} exercise(s), {} skipped, {} added",
+            self.workout_name,
+            self.start_date.as_deref().unwrap_or("no date"),
+            self.template.template_name,
+            self.template.method,
+            self.template.score,
+            self.exercises.len(),
+            self.skipped.len(),
+            self.added.len()
+        )?;
+        for exercise in &self.exercises {
+            write!(
+                f,
+                "\n  {}: {:+} set(s), {:+} rep(s)",
+                exercise.exercise,
+                exercise.set_delta(),
+                exercise.rep_delta()
+            )?;
+            if let Some(load_delta) = exercise.load_delta() {
+                write!(f, ", {:+.1} kg", load_delta)?;
+            }
+        }
+        for exercise in &self.skipped {
+            write!(f, "\n  {}: skipped", exercise)?;
+        }
+        for exercise in &self.added {
+            write!(f, "\n  {}: added", exercise)?;
+        }
+        Ok(())
+    }
+}
+
+/// The template `workout` was most likely started from: the linked one if it is among
+/// `templates`, otherwise the most similar one scoring at least `min_score`.
+pub fn match_template(
+    workout: &Workout,
+    templates: &[Workout],
+    min_score: f32,
+) -> Option<TemplateMatch> {
+    let linked = workout
+        .template_id
+        .as_deref()
+        .and_then(|template_id| templates.iter().find(|template| template.id == template_id));
+    if let Some(template) = linked {
+        return Some(TemplateMatch {
+            template_id: template.id.clone(),
+            template_name: template.name.clone(),
+            method: MatchMethod::Link,
+            score: 1.0,
+        });
+    }
+
+    templates
+        .iter()
+        .map(|template| (template, similarity(workout, template)))
+        .filter(|(_, score)| *score >= min_score)
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(template, score)| TemplateMatch {
+            template_id: template.id.clone(),
+            template_name: template.name.clone(),
+            method: MatchMethod::Similarity,
+            score,
+        })
+}
+
+/// Adherence of every workout that matches one of `templates`, in the order of `workouts`.
+pub fn template_adherence(
+    workouts: &[Workout],
+    templates: &[Workout],
+    min_score: f32,
+) -> Vec<Adherence> {
+    workouts
+        .iter()
+        .filter_map(|workout| {
+            let matched = match_template(workout, templates, min_score)?;
+            let template = templates
+                .iter()
+                .find(|template| template.id == matched.template_id)?;
+            Some(compare(workout, template, matched))
+        })
+        .collect()
+}
+
+fn compare(workout: &Workout, template: &Workout, matched: TemplateMatch) -> Adherence {
+    let mut unused: Vec<&Exercise> = workout
+        .exercises
+        .iter()
+        .filter(|exercise| exercise.sets.iter().any(|set| set.is_completed))
+        .collect();
+    let mut exercises = Vec::new();
+    let mut skipped = Vec::new();
+
+    for planned in &template.exercises {
+        let Some(position) = unused.iter().position(|done| key(done) == key(planned)) else {
+            skipped.push(planned.canonical_name.clone());
+            continue;
+        };
+        let done = unused.remove(position);
+        let completed: Vec<_> = done.sets.iter().filter(|set| set.is_completed).collect();
+        exercises.push(ExerciseDelta {
+            exercise: planned.canonical_name.clone(),
+            measurement_id: planned.measurement_id.clone(),
+            planned_sets: planned.sets.len() as u32,
+            done_sets: completed.len() as u32,
+            planned_reps: planned.sets.iter().map(|set| set.reps).sum(),
+            done_reps: completed.iter().map(|set| set.reps).sum(),
+            planned_load: heaviest(planned.sets.iter().filter_map(|set| set.weight)),
+            done_load: heaviest(completed.iter().filter_map(|set| set.weight)),
+        });
+    }
+
+    Adherence {
+        workout_id: workout.id.clone(),
+        workout_name: workout.name.clone(),
+        start_date: workout.start_date.clone(),
+        template: matched,
+        exercises,
+        skipped,
+        added: unused
+            .into_iter()
+            .map(|exercise| exercise.canonical_name.clone())
+            .collect(),
+    }
+}
+
+fn heaviest(weights: impl Iterator<Item = f32>) -> Option<f32> {
+    weights.reduce(f32::max)
+}
+
+/// Exercises are told apart by measurement id, or by canonical name if they have none.
+fn key(exercise: &Exercise) -> &str {
+    if exercise.measurement_id.is_empty() {
+        &exercise.canonical_name
+    } else {
+        &exercise.measurement_id
+    }
+}
+
+/// Half from equal names, half from the longest common subsequence of exercises relative to
+/// the longer of both sequences.
+fn similarity(workout: &Workout, template: &Workout) -> f32 {
+    let same_name = workout
+        .name
+        .trim()
+        .eq_ignore_ascii_case(template.name.trim());
+    let done: Vec<&str> = workout.exercises.iter().map(key).collect();
+    let planned: Vec<&str> = template.exercises.iter().map(key).collect();
+    let longest = done.len().max(planned.len());
+    let sequence = match longest {
+        0 => 0.0,
+        _ => longest_common_subsequence(&done, &planned) as f32 / longest as f32,
+    };
+    let name = if same_name { 0.5 } else { 0.0 };
+    name + 0.5 * sequence
+}
+
+fn longest_common_subsequence(a: &[&str], b: &[&str]) -> usize {
+    let mut previous = vec![0; b.len() + 1];
+    for item in a {
+        let mut current = vec![0; b.len() + 1];
+        for (j, other) in b.iter().enumerate() {
+            current[j + 1] = if item == other {
+                previous[j] + 1
+            } else {
+                current[j].max(previous[j + 1])
+            };
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
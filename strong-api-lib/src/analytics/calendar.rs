@@ -0,0 +1,33 @@
+//! Weeks and months for the analytics, with weeks starting on the user's first week day.
+
+pub use crate::data_transformer::in_timezone;
+use time::{Date, Duration, OffsetDateTime, Weekday};
+
+/// Today's date in the IANA `timezone`, in UTC if the timezone is unknown.
+pub fn today(timezone: Option<&str>) -> Date {
+    in_timezone(OffsetDateTime::now_utc(), timezone).date()
+}
+
+/// First day of the week `date` is in, for weeks starting on `first_weekday`.
+pub fn week_start(date: Date, first_weekday: Weekday) -> Date {
+    let days = (7 + date.weekday().number_days_from_monday()
+        - first_weekday.number_days_from_monday())
+        % 7;
+    date - Duration::days(i64::from(days))
+}
+
+/// ISO year and week number of the week starting at `week_start`. Weeks not starting on a
+/// Monday are numbered like the ISO week of their Thursday.
+pub fn iso_week(week_start: Date) -> (i32, u8) {
+    let days_to_thursday = (7 + Weekday::Thursday.number_days_from_monday()
+        - week_start.weekday().number_days_from_monday())
+        % 7;
+    let (year, week, _) =
+        (week_start + Duration::days(i64::from(days_to_thursday))).to_iso_week_date();
+    (year, week)
+}
+
+/// First day of the month `date` is in.
+pub fn month_start(date: Date) -> Date {
+    date.replace_day(1).expect("every month has a first day")
+}
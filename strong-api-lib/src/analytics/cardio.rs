@@ -0,0 +1,298 @@
+//! Pace, speed, weekly distance and time, and best efforts of cardio sets.
+//!
+//! A cardio set is a completed set with a distance or a duration, like a run, a row or a yoga
+//! session. Distances are in kilometers and durations in seconds, the `*_in()` methods convert
+//! them to a `DistanceUnit`, e.g. the one the set was logged in. Weeks are taken from the start
+//! of each workout in its own timezone, workouts without a start date are left out of them.
+
+use super::calendar::{iso_week, week_start};
+use crate::data_transformer::Workout;
+use crate::models::preferences::DistanceUnit;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use time::{Date, Weekday};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StandardDistance {
+    OneKilometer,
+    OneMile,
+    FiveKilometers,
+    TenKilometers,
+    HalfMarathon,
+    Marathon,
+}
+
+impl StandardDistance {
+    pub const ALL: [StandardDistance; 6] = [
+        StandardDistance::OneKilometer,
+        StandardDistance::OneMile,
+        StandardDistance::FiveKilometers,
+        StandardDistance::TenKilometers,
+        StandardDistance::HalfMarathon,
+        StandardDistance::Marathon,
+    ];
+
+    pub fn kilometers(self) -> f32 {
+        match self {
+            StandardDistance::OneKilometer => 1.0,
+            StandardDistance::OneMile => DistanceUnit::Miles.to_kilometers(1.0),
+            StandardDistance::FiveKilometers => 5.0,
+            StandardDistance::TenKilometers => 10.0,
+            StandardDistance::HalfMarathon => 21.0975,
+            StandardDistance::Marathon => 42.195,
+        }
+    }
+}
+
+impl fmt::Display for StandardDistance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match self {
+            StandardDistance::OneKilometer => "one_kilometer",
+            StandardDistance::OneMile => "one_mile",
+            StandardDistance::FiveKilometers => "five_kilometers",
+            StandardDistance::TenKilometers => "ten_kilometers",
+            StandardDistance::HalfMarathon => "half_marathon",
+            StandardDistance::Marathon => "marathon",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+/// A completed set with a distance or a duration. Displayed in the unit it was logged in, with
+/// the pace of sets logged in meters per kilometer.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CardioSet {
+    pub workout_id: String,
+    pub start_date: Option<String>,
+    pub exercise_id: String,
+    pub measurement_id: String,
+    /// The exercise's `canonical_name`.
+    pub exercise: String,
+    pub set_id: String,
+    /// Kilometers.
+    pub distance: Option<f32>,
+    /// Seconds.
+    pub duration: Option<u32>,
+    /// The unit the distance was logged in.
+    pub distance_unit: DistanceUnit,
+}
+
+impl CardioSet {
+    /// Seconds per kilometer, `None` without both a distance and a duration.
+    pub fn pace(&self) -> Option<f32> {
+        self.pace_in(DistanceUnit::Kilometers)
+    }
+
+    /// Kilometers per hour, `None` without both a distance and a duration.
+    pub fn speed(&self) -> Option<f32> {
+        self.speed_in(DistanceUnit::Kilometers)
+    }
+
+    pub fn distance_in(&self, unit: DistanceUnit) -> Option<f32> {
+        self.distance.map(|distance| unit.from_kilometers(distance))
+    }
+
+    /// Seconds per one `unit`.
+    pub fn pace_in(&self, unit: DistanceUnit) -> Option<f32> {
+        let distance = self.distance_in(unit).filter(|distance| *distance > 0.0)?;
+        let duration = self.duration.filter(|duration| *duration > 0)?;
+        Some(duration as f32 / distance)
+    }
+
+    /// `unit`s per hour.
+    pub fn speed_in(&self, unit: DistanceUnit) -> Option<f32> {
+        self.pace_in(unit).map(|pace| 3600.0 / pace)
+    }
+}
+
+impl fmt::Display for CardioSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.exercise)?;
+        if let Some(distance) = self.distance_in(self.distance_unit) {
+            write!(f, " {:.2} {}", distance, self.distance_unit)?;
+        }
+        if let Some(duration) = self.duration {
+            write!(f, " in {}", format_duration(duration as f32))?;
+        }
+        let pace_unit = match self.distance_unit {
+            DistanceUnit::Meters => DistanceUnit::Kilometers,
+            unit => unit,
+        };
+        if let (Some(pace), Some(speed)) = (self.pace_in(pace_unit), self.speed_in(pace_unit)) {
+            write!(
+                f,
+                " ({} /{}, {:.1} {}/h)",
+                format_duration(pace),
+                pace_unit,
+                speed,
+                pace_unit
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Distance and time of the cardio sets of one week.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CardioWeek {
+    /// First day of the week.
+    pub start: Date,
+    pub iso_year: i32,
+    pub iso_week: u8,
+    /// Workouts with at least one cardio set.
+    pub sessions: u32,
+    pub sets: u32,
+    /// Kilometers.
+    pub distance: f32,
+    /// Seconds.
+    pub duration: u32,
+}
+
+impl CardioWeek {
+    pub fn distance_in(&self, unit: DistanceUnit) -> f32 {
+        unit.from_kilometers(self.distance)
+    }
+}
+
+/// The fastest time over a standard distance of an exercise.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BestEffort {
+    pub distance: StandardDistance,
+    /// The exercise's `canonical_name`.
+    pub exercise: String,
+    pub measurement_id: String,
+    pub workout_id: String,
+    pub start_date: Option<String>,
+    pub set_id: String,
+    /// Seconds for the standard distance at the set's average pace.
+    pub time: f32,
+    /// Seconds per kilometer.
+    pub pace: f32,
+}
+
+impl fmt::Display for BestEffort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}: {} ({} /km)",
+            self.exercise,
+            self.distance,
+            format_duration(self.time),
+            format_duration(self.pace)
+        )
+    }
+}
+
+/// Every cardio set of `workouts`, in the order of `workouts`.
+pub fn cardio_sets(workouts: &[Workout]) -> Vec<CardioSet> {
+    workouts.iter().flat_map(cardio_sets_of_workout).collect()
+}
+
+fn cardio_sets_of_workout(workout: &Workout) -> impl Iterator<Item = CardioSet> + '_ {
+    workout.exercises.iter().flat_map(move |exercise| {
+        exercise
+            .sets
+            .iter()
+            .filter(|set| {
+                set.is_completed
+                    && (set.distance.is_some_and(|distance| distance > 0.0)
+                        || set.duration.is_some_and(|duration| duration > 0))
+            })
+            .map(move |set| CardioSet {
+                workout_id: workout.id.clone(),
+                start_date: workout.start_date.clone(),
+                exercise_id: exercise.id.clone(),
+                measurement_id: exercise.measurement_id.clone(),
+                exercise: exercise.canonical_name.clone(),
+                set_id: set.id.clone(),
+                distance: set.distance,
+                duration: set.duration,
+                distance_unit: set.distance_unit,
+            })
+    })
+}
+
+/// Distance and time of each week with cardio sets, with weeks starting on `first_weekday`,
+/// e.g. from `UserResponse::first_weekday()`. Sorted chronologically.
+pub fn cardio_by_week(workouts: &[Workout], first_weekday: Weekday) -> Vec<CardioWeek> {
+    let mut weeks: BTreeMap<Date, CardioWeek> = BTreeMap::new();
+    for workout in workouts {
+        let Some(date) = workout.local_date() else {
+            continue;
+        };
+        let sets: Vec<CardioSet> = cardio_sets_of_workout(workout).collect();
+        if sets.is_empty() {
+            continue;
+        }
+        let start = week_start(date, first_weekday);
+        let week = weeks.entry(start).or_insert_with(|| {
+            let (iso_year, iso_week) = iso_week(start);
+            CardioWeek {
+                start,
+                iso_year,
+                iso_week,
+                sessions: 0,
+                sets: 0,
+                distance: 0.0,
+                duration: 0,
+            }
+        });
+        week.sessions += 1;
+        for set in sets {
+            week.sets += 1;
+            week.distance += set.distance.unwrap_or(0.0);
+            week.duration += set.duration.unwrap_or(0);
+        }
+    }
+    weeks.into_values().collect()
+}
+
+/// The best effort of each exercise over each standard distance, sorted by exercise and
+/// distance. Only sets with a duration that are at least as long as the standard distance
+/// count, their time is scaled to it by their average pace.
+pub fn best_efforts(workouts: &[Workout]) -> Vec<BestEffort> {
+    let mut best: BTreeMap<(String, StandardDistance), BestEffort> = BTreeMap::new();
+    for set in cardio_sets(workouts) {
+        let (Some(distance), Some(pace)) = (set.distance, set.pace()) else {
+            continue;
+        };
+        for standard in StandardDistance::ALL {
+            // Allow for the rounding of distances logged in another unit.
+            if distance + 0.0005 < standard.kilometers() {
+                continue;
+            }
+            let time = pace * standard.kilometers();
+            let key = (set.exercise.clone(), standard);
+            if best.get(&key).is_some_and(|effort| effort.time <= time) {
+                continue;
+            }
+            best.insert(
+                key,
+                BestEffort {
+                    distance: standard,
+                    exercise: set.exercise.clone(),
+                    measurement_id: set.measurement_id.clone(),
+                    workout_id: set.workout_id.clone(),
+                    start_date: set.start_date.clone(),
+                    set_id: set.set_id.clone(),
+                    time,
+                    pace,
+                },
+            );
+        }
+    }
+    best.into_values().collect()
+}
+
+/// `h:mm:ss`, or `m:ss` below an hour.
+fn format_duration(seconds: f32) -> String {
+    let seconds = seconds.round() as u32;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
@@ -0,0 +1,301 @@
+//! Comparison of two periods, like this month against the last one or a training block
+//! against the previous block.
+//!
+//! Both periods are summarized by their workouts, sets, tonnage, sets per tag (like a muscle
+//! group), best e1RM per exercise and personal records, and every figure of the current period
+//! is compared to the previous one. Like the volume, only completed sets count and workouts
+//! belong to the day they started on in their own timezone. Personal records are taken from
+//! the whole history, so a record is only new if no workout before beat it, and the first
+//! time an exercise is done doesn't count as one.
+
+use super::calendar::{month_start, week_start};
+use super::one_rep_max::{OneRepMaxFormula, best_one_rep_maxes};
+use super::personal_records::{PersonalRecord, personal_record_timeline};
+use super::volume::{Volume, volume_by_tag};
+use crate::data_transformer::Workout;
+use crate::models::measurement::MeasurementsResponse;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use time::{Date, Duration, Weekday};
+
+/// Days from `start` to `end`, both included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Period {
+    pub start: Date,
+    pub end: Date,
+}
+
+impl Period {
+    pub fn new(start: Date, end: Date) -> Self {
+        Self { start, end }
+    }
+
+    /// The week `date` is in, for weeks starting on `first_weekday`.
+    pub fn week_of(date: Date, first_weekday: Weekday) -> Self {
+        let start = week_start(date, first_weekday);
+        Self::new(start, start + Duration::days(6))
+    }
+
+    /// The calendar month `date` is in.
+    pub fn month_of(date: Date) -> Self {
+        let start = month_start(date);
+        let end = start.replace_day(start.month().length(start.year()));
+        Self::new(start, end.expect("every month has a last day"))
+    }
+
+    /// The `days` days ending with `end`.
+    pub fn days_ending(end: Date, days: u32) -> Self {
+        Self::new(end - Duration::days(i64::from(days.max(1)) - 1), end)
+    }
+
+    pub fn days(&self) -> i64 {
+        (self.end - self.start).whole_days() + 1
+    }
+
+    pub fn contains(&self, date: Date) -> bool {
+        self.start <= date && date <= self.end
+    }
+
+    /// The period of the same length right before this one.
+    pub fn preceding(&self) -> Self {
+        let end = self.start - Duration::days(1);
+        Self::new(end - Duration::days(self.days() - 1), end)
+    }
+
+    /// The calendar month before the one this period starts in.
+    pub fn previous_month(&self) -> Self {
+        Self::month_of(month_start(self.start) - Duration::days(1))
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} to {}", self.start, self.end)
+    }
+}
+
+/// A figure of both periods and how it changed.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Delta {
+    pub previous: f32,
+    pub current: f32,
+    pub change: f32,
+    /// Change relative to the previous period, `None` if that was 0.
+    pub percent: Option<f32>,
+}
+
+impl Delta {
+    pub fn new(previous: f32, current: f32) -> Self {
+        Self {
+            previous,
+            current,
+            change: current - previous,
+            percent: (previous != 0.0).then(|| (current - previous) / previous * 100.0),
+        }
+    }
+}
+
+impl fmt::Display for Delta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.0} -> {:.0}", self.previous, self.current)?;
+        match self.percent {
+            Some(percent) => write!(f, " ({:+.1}%)", percent),
+            None if self.current != 0.0 => write!(f, " (new)"),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagDelta {
+    pub tag: String,
+    /// Completed sets.
+    pub sets: Delta,
+}
+
+/// Best e1RM of an exercise in both periods, `None` in a period it wasn't done in.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OneRepMaxDelta {
+    /// The exercise's `canonical_name`.
+    pub exercise: String,
+    pub previous: Option<f32>,
+    pub current: Option<f32>,
+    /// Change relative to the previous period, `None` unless the exercise was done in both.
+    pub percent: Option<f32>,
+}
+
+/// The current period compared to the previous one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeriodComparison {
+    pub current: Period,
+    pub previous: Period,
+    pub formula: OneRepMaxFormula,
+    pub workouts: Delta,
+    pub sets: Delta,
+    pub reps: Delta,
+    /// Kilograms.
+    pub tonnage: Delta,
+    pub hard_sets: Delta,
+    /// Tags done in either period, sorted by tag.
+    pub sets_by_tag: Vec<TagDelta>,
+    /// Exercises done in either period, sorted by exercise.
+    pub one_rep_maxes: Vec<OneRepMaxDelta>,
+    pub personal_record_count: Delta,
+    /// New records of the current period, chronologically.
+    pub personal_records: Vec<PersonalRecord>,
+}
+
+impl fmt::Display for PeriodComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} compared to {}", self.current, self.previous)?;
+        write!(f, "\n  Workouts: {}", self.workouts)?;
+        write!(f, "\n  Sets: {}", self.sets)?;
+        write!(f, "\n  Tonnage (kg): {}", self.tonnage)?;
+        write!(f, "\n  Personal records: {}", self.personal_record_count)?;
+        for tag in &self.sets_by_tag {
+            write!(f, "\n  Sets of {}: {}", tag.tag, tag.sets)?;
+        }
+        for exercise in &self.one_rep_maxes {
+            write!(f, "\n  {} e1RM: ", exercise.exercise)?;
+            match (exercise.previous, exercise.current) {
+                (Some(previous), Some(current)) => write!(
+                    f,
+                    "{:.1} -> {:.1} kg ({:+.1}%)",
+                    previous,
+                    current,
+                    exercise.percent.unwrap_or_default()
+                )?,
+                (None, Some(current)) => write!(f, "{:.1} kg (new)", current)?,
+                (Some(previous), None) => write!(f, "{:.1} kg (not done)", previous)?,
+                (None, None) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Figures of one period.
+struct Summary {
+    workouts: u32,
+    volume: Volume,
+    sets_by_tag: HashMap<String, u32>,
+    one_rep_maxes: HashMap<String, f32>,
+}
+
+fn summarize(
+    workouts: &[Workout],
+    measurements: &MeasurementsResponse,
+    formula: OneRepMaxFormula,
+) -> Summary {
+    let mut summary = Summary {
+        workouts: 0,
+        volume: Volume::default(),
+        sets_by_tag: volume_by_tag(workouts, measurements)
+            .into_iter()
+            .map(|tag| (tag.tag, tag.volume.sets))
+            .collect(),
+        one_rep_maxes: HashMap::new(),
+    };
+    for workout in workouts {
+        let volume = Volume::of_sets(workout.exercises.iter().flat_map(|exercise| &exercise.sets));
+        if volume.sets > 0 {
+            summary.workouts += 1;
+        }
+        summary.volume.add(&volume);
+        for best in best_one_rep_maxes(workout)
+            .into_iter()
+            .filter(|best| best.formula == formula)
+        {
+            let one_rep_max = summary.one_rep_maxes.entry(best.exercise).or_default();
+            *one_rep_max = one_rep_max.max(best.one_rep_max);
+        }
+    }
+    summary
+}
+
+fn in_period(workouts: &[Workout], period: Period) -> Vec<Workout> {
+    workouts
+        .iter()
+        .filter(|workout| {
+            workout
+                .local_date()
+                .is_some_and(|date| period.contains(date))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Compares `current` to `previous`, with sets tagged by the tags of their measurement in
+/// `measurements` and e1RMs estimated with `formula`. Only workouts with a completed set count.
+pub fn compare_periods(
+    workouts: &[Workout],
+    measurements: &MeasurementsResponse,
+    current: Period,
+    previous: Period,
+    formula: OneRepMaxFormula,
+) -> PeriodComparison {
+    let now = summarize(&in_period(workouts, current), measurements, formula);
+    let before = summarize(&in_period(workouts, previous), measurements, formula);
+
+    let mut tags: BTreeMap<&str, Delta> = BTreeMap::new();
+    for tag in now.sets_by_tag.keys().chain(before.sets_by_tag.keys()) {
+        let sets = |summary: &Summary| summary.sets_by_tag.get(tag).copied().unwrap_or(0) as f32;
+        tags.insert(tag, Delta::new(sets(&before), sets(&now)));
+    }
+    let mut exercises: BTreeMap<&str, OneRepMaxDelta> = BTreeMap::new();
+    for exercise in now.one_rep_maxes.keys().chain(before.one_rep_maxes.keys()) {
+        let previous = before.one_rep_maxes.get(exercise).copied();
+        let current = now.one_rep_maxes.get(exercise).copied();
+        exercises.insert(
+            exercise,
+            OneRepMaxDelta {
+                exercise: exercise.clone(),
+                previous,
+                current,
+                percent: previous
+                    .zip(current)
+                    .and_then(|(previous, current)| Delta::new(previous, current).percent),
+            },
+        );
+    }
+
+    // Records of either period, by the local date of the workout they were set in.
+    let dates: HashMap<&str, Date> = workouts
+        .iter()
+        .filter_map(|workout| Some((workout.id.as_str(), workout.local_date()?)))
+        .collect();
+    let (mut records_now, mut records_before) = (Vec::new(), 0);
+    for record in personal_record_timeline(workouts, formula)
+        .into_iter()
+        .filter(|record| record.previous.is_some())
+    {
+        match dates.get(record.workout_id.as_str()) {
+            Some(date) if current.contains(*date) => records_now.push(record),
+            Some(date) if previous.contains(*date) => records_before += 1,
+            _ => {}
+        }
+    }
+
+    let delta = |previous: u32, current: u32| Delta::new(previous as f32, current as f32);
+    PeriodComparison {
+        current,
+        previous,
+        formula,
+        workouts: delta(before.workouts, now.workouts),
+        sets: delta(before.volume.sets, now.volume.sets),
+        reps: delta(before.volume.reps, now.volume.reps),
+        tonnage: Delta::new(before.volume.tonnage, now.volume.tonnage),
+        hard_sets: delta(before.volume.hard_sets, now.volume.hard_sets),
+        sets_by_tag: tags
+            .into_iter()
+            .map(|(tag, sets)| TagDelta {
+                tag: tag.to_string(),
+                sets,
+            })
+            .collect(),
+        one_rep_maxes: exercises.into_values().collect(),
+        personal_record_count: delta(records_before, records_now.len() as u32),
+        personal_records: records_now,
+    }
+}
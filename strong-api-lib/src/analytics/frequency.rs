@@ -0,0 +1,214 @@
+//! Training frequency: streaks, workouts per week against the weekly goal and rest days.
+//!
+//! Days are the local dates the workouts started on, in each workout's own timezone, which is
+//! the user's timezone at the time. Several workouts on one day make one training day.
+
+use super::calendar::{iso_week, week_start};
+use crate::data_transformer::Workout;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use time::{Date, Duration, Weekday};
+
+/// Consecutive days with a workout, or consecutive weeks meeting the goal. For weekly streaks
+/// `start` and `end` are the first days of the first and the last week.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Streak {
+    pub start: Date,
+    pub end: Date,
+    /// Number of days or weeks.
+    pub length: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeekFrequency {
+    /// First day of the week.
+    pub start: Date,
+    pub iso_year: i32,
+    pub iso_week: u8,
+    pub workouts: u32,
+    pub goal_met: bool,
+}
+
+/// How often a number of rest days between two training days occurred.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RestDays {
+    pub days: u32,
+    pub count: u32,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrainingFrequency {
+    /// Workouts per week to meet the goal.
+    pub goal: u32,
+    pub workouts: u32,
+    pub training_days: u32,
+    /// Every week from the first workout's up to the current one, including weeks without
+    /// workouts.
+    pub weeks: Vec<WeekFrequency>,
+    pub weeks_goal_met: u32,
+    pub average_workouts_per_week: f32,
+    /// Daily streak that ends today or yesterday.
+    pub current_daily_streak: Option<Streak>,
+    pub longest_daily_streak: Option<Streak>,
+    /// Weekly streak that ends in the current or the last week, as the current one may not
+    /// be over yet.
+    pub current_weekly_streak: Option<Streak>,
+    pub longest_weekly_streak: Option<Streak>,
+    /// Rest days between consecutive training days, by number of rest days.
+    pub rest_days: Vec<RestDays>,
+}
+
+/// Computes the training frequency of `workouts` as of `today`, e.g. `calendar::today()`.
+/// Weeks start on `first_weekday` and a week meets the goal with `goal` workouts, e.g. from
+/// `UserResponse::workouts_per_week_goal()`, or with a single workout if there is no goal.
+/// Workouts without a start date are left out.
+pub fn training_frequency(
+    workouts: &[Workout],
+    first_weekday: Weekday,
+    goal: Option<u32>,
+    today: Date,
+) -> TrainingFrequency {
+    let goal = goal.unwrap_or(1);
+    let mut workouts_per_day: BTreeMap<Date, u32> = BTreeMap::new();
+    for workout in workouts {
+        if let Some(start) = workout.local_start_time() {
+            *workouts_per_day.entry(start.date()).or_default() += 1;
+        }
+    }
+    let (Some(first_day), Some(last_day)) = (
+        workouts_per_day.keys().next().copied(),
+        workouts_per_day.keys().next_back().copied(),
+    ) else {
+        return TrainingFrequency {
+            goal,
+            ..Default::default()
+        };
+    };
+
+    let current_week = week_start(today.max(last_day), first_weekday);
+    let mut weeks = Vec::new();
+    let mut week = week_start(first_day, first_weekday);
+    while week <= current_week {
+        let workouts = workouts_per_day
+            .range(week..week + Duration::days(7))
+            .map(|(_, workouts)| workouts)
+            .sum();
+        let (iso_year, iso_week) = iso_week(week);
+        weeks.push(WeekFrequency {
+            start: week,
+            iso_year,
+            iso_week,
+            workouts,
+            goal_met: workouts >= goal,
+        });
+        week += Duration::days(7);
+    }
+
+    let daily_streaks = streaks(workouts_per_day.keys().copied(), Duration::days(1));
+    let weekly_streaks = streaks(
+        weeks
+            .iter()
+            .filter(|week| week.goal_met)
+            .map(|week| week.start),
+        Duration::days(7),
+    );
+    let current = |streaks: &[Streak], step: Duration, now: Date| {
+        streaks
+            .last()
+            .filter(|streak| streak.end >= now - step)
+            .copied()
+    };
+
+    let mut rest_days: BTreeMap<u32, u32> = BTreeMap::new();
+    let days: Vec<Date> = workouts_per_day.keys().copied().collect();
+    for pair in days.windows(2) {
+        let rest = (pair[1] - pair[0]).whole_days() - 1;
+        *rest_days.entry(rest as u32).or_default() += 1;
+    }
+
+    let total: u32 = workouts_per_day.values().sum();
+    TrainingFrequency {
+        goal,
+        workouts: total,
+        training_days: days.len() as u32,
+        weeks_goal_met: weeks.iter().filter(|week| week.goal_met).count() as u32,
+        average_workouts_per_week: total as f32 / weeks.len() as f32,
+        current_daily_streak: current(&daily_streaks, Duration::days(1), today),
+        longest_daily_streak: longest(&daily_streaks),
+        current_weekly_streak: current(
+            &weekly_streaks,
+            Duration::days(7),
+            week_start(today, first_weekday),
+        ),
+        longest_weekly_streak: longest(&weekly_streaks),
+        rest_days: rest_days
+            .into_iter()
+            .map(|(days, count)| RestDays { days, count })
+            .collect(),
+        weeks,
+    }
+}
+
+/// Splits sorted `dates` into runs of dates `step` apart.
+fn streaks(dates: impl Iterator<Item = Date>, step: Duration) -> Vec<Streak> {
+    let mut streaks: Vec<Streak> = Vec::new();
+    for date in dates {
+        match streaks.last_mut() {
+            Some(streak) if streak.end + step == date => {
+                streak.end = date;
+                streak.length += 1;
+            }
+            _ => streaks.push(Streak {
+                start: date,
+                end: date,
+                length: 1,
+            }),
+        }
+    }
+    streaks
+}
+
+/// The longest streak, the most recent one on ties.
+fn longest(streaks: &[Streak]) -> Option<Streak> {
+    streaks.iter().max_by_key(|streak| streak.length).copied()
+}
+
+impl fmt::Display for TrainingFrequency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Training frequency: {} workout(s) in {} week(s), {:.1} per week (goal {})",
+            self.workouts,
+            self.weeks.len(),
+            self.average_workouts_per_week,
+            self.goal
+        )?;
+        write!(
+            f,
+            "\n  weeks goal met: {} of {}",
+            self.weeks_goal_met,
+            self.weeks.len()
+        )?;
+        let length = |streak: Option<Streak>| streak.map_or(0, |streak| streak.length);
+        write!(
+            f,
+            "\n  current streak: {} day(s), {} week(s)",
+            length(self.current_daily_streak),
+            length(self.current_weekly_streak)
+        )?;
+        write!(
+            f,
+            "\n  longest streak: {} day(s), {} week(s)",
+            length(self.longest_daily_streak),
+            length(self.longest_weekly_streak)
+        )?;
+        if !self.rest_days.is_empty() {
+            write!(f, "\n  rest days:")?;
+            for rest in &self.rest_days {
+                write!(f, " {}d {}x", rest.days, rest.count)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,14 @@
+//! Analytics computed from transformed `Workout`s.
+
+pub mod adherence;
+pub mod calendar;
+pub mod cardio;
+pub mod comparison;
+pub mod frequency;
+pub mod one_rep_max;
+pub mod personal_records;
+pub mod progression;
+pub mod scores;
+pub mod volume;
+pub mod workload;
+pub mod year_in_review;
@@ -0,0 +1,176 @@
+//! Estimated one-rep max (e1RM) of sets.
+//!
+//! Estimates are based on `Set::effective_load`, the load actually moved, so weighted and
+//! assisted bodyweight sets are estimated like any other set. Like all weights of a `Set`
+//! they are in kilograms.
+
+use crate::data_transformer::{Set, Workout};
+use serde::{Deserialize, Serialize};
+
+/// Share of the one-rep max that can be lifted for 1 to 16 reps to failure, taken from the
+/// RPE chart by Mike Tuchscherer (Reactive Training Systems).
+const SHARE_AT_FAILURE: [f32; 16] = [
+    1.0, 0.955, 0.922, 0.892, 0.863, 0.837, 0.811, 0.786, 0.762, 0.739, 0.707, 0.68, 0.653, 0.626,
+    0.599, 0.574,
+];
+
+/// Highest number of reps the RPE chart covers.
+const RPE_TABLE_MAX_REPS: u32 = 12;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OneRepMaxFormula {
+    /// `load × (1 + reps / 30)`
+    #[default]
+    Epley,
+    /// `load × 36 / (37 - reps)`
+    Brzycki,
+    /// `load × reps ^ 0.1`
+    Lombardi,
+    /// `load / share of the 1RM` for the set's reps and RPE, from the RPE chart.
+    RpeTable,
+}
+
+impl OneRepMaxFormula {
+    pub const ALL: [OneRepMaxFormula; 4] = [
+        OneRepMaxFormula::Epley,
+        OneRepMaxFormula::Brzycki,
+        OneRepMaxFormula::Lombardi,
+        OneRepMaxFormula::RpeTable,
+    ];
+
+    /// Estimates the one-rep max from `load` lifted for `reps`. A single rep is its own
+    /// one-rep max for all formulas but the RPE table, which is the only one using `rpe` and
+    /// has no estimate without it. There is no estimate for sets without load or reps, for 37
+    /// reps or more with Brzycki and outside RPE 6 to 10 or above 12 reps with the RPE table.
+    pub fn estimate(self, load: f32, reps: u32, rpe: Option<f32>) -> Option<f32> {
+        if load <= 0.0 || reps == 0 {
+            return None;
+        }
+        let repetitions = reps as f32;
+        match self {
+            OneRepMaxFormula::RpeTable => Some(load / rpe_table_share(reps, rpe?)?),
+            _ if reps == 1 => Some(load),
+            OneRepMaxFormula::Epley => Some(load * (1.0 + repetitions / 30.0)),
+            OneRepMaxFormula::Brzycki => (reps < 37).then(|| load * 36.0 / (37.0 - repetitions)),
+            OneRepMaxFormula::Lombardi => Some(load * repetitions.powf(0.1)),
+        }
+    }
+
+    /// Estimates the one-rep max of a set. Incomplete sets have no estimate.
+    pub fn estimate_set(self, set: &Set) -> Option<f32> {
+        if !set.is_completed {
+            return None;
+        }
+        self.estimate(set.effective_load?, set.reps, set.rpe)
+    }
+}
+
+impl std::fmt::Display for OneRepMaxFormula {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let value = match self {
+            OneRepMaxFormula::Epley => "epley",
+            OneRepMaxFormula::Brzycki => "brzycki",
+            OneRepMaxFormula::Lombardi => "lombardi",
+            OneRepMaxFormula::RpeTable => "rpe_table",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+/// The load that can be lifted for `reps` at `rpe` with a one-rep max of `one_rep_max`, the
+/// inverse of the RPE table estimate. `None` outside RPE 6 to 10 or above 12 reps.
+pub fn rpe_table_load(one_rep_max: f32, reps: u32, rpe: f32) -> Option<f32> {
+    if reps == 0 {
+        return None;
+    }
+    Some(one_rep_max * rpe_table_share(reps, rpe)?)
+}
+
+/// Share of the one-rep max lifted for `reps` at `rpe`. The set counts as `reps` plus the
+/// reps in reserve (`10 - rpe`) to failure, between whole reps the chart is interpolated.
+fn rpe_table_share(reps: u32, rpe: f32) -> Option<f32> {
+    if !(6.0..=10.0).contains(&rpe) || reps > RPE_TABLE_MAX_REPS {
+        return None;
+    }
+    let reps_to_failure = reps as f32 + 10.0 - rpe;
+    let index = reps_to_failure.floor() as usize - 1;
+    let fraction = reps_to_failure.fract();
+    let lower = SHARE_AT_FAILURE[index];
+    let upper = SHARE_AT_FAILURE.get(index + 1).copied().unwrap_or(lower);
+    Some(lower + (upper - lower) * fraction)
+}
+
+/// The best estimated one-rep max of an exercise in a workout with one formula, and the set
+/// it was estimated from.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BestOneRepMax {
+    pub workout_id: String,
+    pub start_date: Option<String>,
+    /// Id of the exercise the set belongs to.
+    pub exercise_id: String,
+    pub measurement_id: String,
+    /// The exercise's `canonical_name`.
+    pub exercise: String,
+    pub formula: OneRepMaxFormula,
+    pub set_id: String,
+    pub effective_load: f32,
+    pub reps: u32,
+    pub rpe: Option<f32>,
+    pub one_rep_max: f32,
+}
+
+/// Best estimated one-rep max per exercise and formula of a workout. Exercises are grouped
+/// by their canonical name, so an exercise logged twice or under an alias gets one estimate
+/// per formula. Exercises are in workout order, formulas in the order of `OneRepMaxFormula::ALL`.
+pub fn best_one_rep_maxes(workout: &Workout) -> Vec<BestOneRepMax> {
+    let mut best: Vec<BestOneRepMax> = Vec::new();
+    for exercise in &workout.exercises {
+        for set in &exercise.sets {
+            for formula in OneRepMaxFormula::ALL {
+                let Some(one_rep_max) = formula.estimate_set(set) else {
+                    continue;
+                };
+                let candidate = BestOneRepMax {
+                    workout_id: workout.id.clone(),
+                    start_date: workout.start_date.clone(),
+                    exercise_id: exercise.id.clone(),
+                    measurement_id: exercise.measurement_id.clone(),
+                    exercise: exercise.canonical_name.clone(),
+                    formula,
+                    set_id: set.id.clone(),
+                    effective_load: set.effective_load.unwrap_or(0.0),
+                    reps: set.reps,
+                    rpe: set.rpe,
+                    one_rep_max,
+                };
+                match best.iter_mut().find(|current| {
+                    current.exercise == exercise.canonical_name && current.formula == formula
+                }) {
+                    Some(current) if one_rep_max > current.one_rep_max => *current = candidate,
+                    Some(_) => {}
+                    None => best.push(candidate),
+                }
+            }
+        }
+    }
+
+    let exercise_order = |exercise: &str| {
+        workout
+            .exercises
+            .iter()
+            .position(|candidate| candidate.canonical_name == exercise)
+    };
+    let formula_order = |formula: OneRepMaxFormula| {
+        OneRepMaxFormula::ALL
+            .iter()
+            .position(|candidate| *candidate == formula)
+    };
+    best.sort_by_key(|entry| {
+        (
+            exercise_order(&entry.exercise),
+            formula_order(entry.formula),
+        )
+    });
+    best
+}
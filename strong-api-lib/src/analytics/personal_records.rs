@@ -0,0 +1,296 @@
+//! Personal records over a whole history.
+//!
+//! Workouts are walked in chronological order and every workout that beats an exercise's
+//! record so far emits a `PersonalRecord`. Like the one-rep max estimates, records are based
+//! on the `effective_load` of completed sets and exercises are told apart by their
+//! `canonical_name`. The first time an exercise is done sets its records, those events have
+//! no `previous` value.
+
+use super::one_rep_max::OneRepMaxFormula;
+use crate::data_transformer::{Exercise, Set, Workout};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordKind {
+    /// Heaviest load of a single set.
+    #[default]
+    HeaviestWeight,
+    /// More reps than ever done with the set's load or a heavier one.
+    MostReps,
+    /// Best estimated one-rep max.
+    BestOneRepMax,
+    /// Best `load × reps` of a single set.
+    BestSetVolume,
+    /// Best `load × reps` of all sets of the exercise in a workout.
+    BestSessionVolume,
+}
+
+impl std::fmt::Display for RecordKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let value = match self {
+            RecordKind::HeaviestWeight => "heaviest_weight",
+            RecordKind::MostReps => "most_reps",
+            RecordKind::BestOneRepMax => "best_one_rep_max",
+            RecordKind::BestSetVolume => "best_set_volume",
+            RecordKind::BestSessionVolume => "best_session_volume",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+/// A new record of an exercise and where it was set.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersonalRecord {
+    pub kind: RecordKind,
+    /// The exercise's `canonical_name`.
+    pub exercise: String,
+    pub measurement_id: String,
+    pub workout_id: String,
+    pub start_date: Option<String>,
+    /// Id of the exercise the record was set in.
+    pub exercise_id: String,
+    /// Id of the set that set the record, `None` for session volume.
+    pub set_id: Option<String>,
+    /// Load of that set in kilograms, `None` for session volume.
+    pub effective_load: Option<f32>,
+    /// The new record: kilograms for weights, e1RM and volumes, a number of reps for `MostReps`.
+    pub value: f32,
+    /// The record it beats, `None` if there was none. For `MostReps` the most reps done with
+    /// at least `effective_load` before.
+    pub previous: Option<f32>,
+}
+
+/// Records of one exercise so far.
+#[derive(Default, Debug, Clone)]
+struct ExerciseRecords {
+    heaviest_weight: Option<f32>,
+    one_rep_max: Option<f32>,
+    set_volume: Option<f32>,
+    session_volume: Option<f32>,
+    /// `(load, reps)` of all sets no other set beats in both load and reps.
+    rep_records: Vec<(f32, u32)>,
+}
+
+impl ExerciseRecords {
+    /// Most reps done with `load` or more.
+    fn most_reps(&self, load: f32) -> Option<u32> {
+        self.rep_records
+            .iter()
+            .filter(|(record_load, _)| *record_load >= load)
+            .map(|(_, reps)| *reps)
+            .max()
+    }
+
+    /// Finds the sets beating the reps record, leaving out those beaten by another of the
+    /// sets, and updates the record. Returns them with the most reps done at their load before.
+    fn beat_rep_records<'a>(
+        &mut self,
+        sets: &[(&'a Exercise, &'a Set)],
+    ) -> Vec<(&'a Exercise, &'a Set, Option<u32>)> {
+        let mut beaten: Vec<(&Exercise, &Set, f32)> = Vec::new();
+        for &(exercise, set) in sets {
+            let Some(load) = set.effective_load.filter(|_| set.reps > 0) else {
+                continue;
+            };
+            if self.most_reps(load).is_some_and(|reps| reps >= set.reps)
+                || beaten.iter().any(|(_, other, other_load)| {
+                    dominated_by(load, set.reps, *other_load, other.reps)
+                })
+            {
+                continue;
+            }
+            beaten.retain(|(_, other, other_load)| {
+                !dominated_by(*other_load, other.reps, load, set.reps)
+            });
+            beaten.push((exercise, set, load));
+        }
+
+        let records = beaten
+            .iter()
+            .map(|(exercise, set, load)| (*exercise, *set, self.most_reps(*load)))
+            .collect();
+        for (_, set, load) in beaten {
+            self.rep_records.retain(|(record_load, record_reps)| {
+                !dominated_by(*record_load, *record_reps, load, set.reps)
+            });
+            self.rep_records.push((load, set.reps));
+        }
+        records
+    }
+}
+
+/// Whether `reps` at `load` are no better than `other_reps` at `other_load`.
+fn dominated_by(load: f32, reps: u32, other_load: f32, other_reps: u32) -> bool {
+    load <= other_load && reps <= other_reps
+}
+
+/// Replaces `record` if `value` beats it. Returns the beaten record, `Some(None)` if there
+/// was none yet, and `None` if `value` doesn't beat it.
+fn beat(record: &mut Option<f32>, value: f32) -> Option<Option<f32>> {
+    if record.is_some_and(|previous| previous >= value) {
+        return None;
+    }
+    Some(record.replace(value))
+}
+
+/// The set with the highest `value`, the first one on ties.
+fn best_set<'a>(
+    sets: &[(&'a Exercise, &'a Set)],
+    value: impl Fn(&Set) -> Option<f32>,
+) -> Option<(&'a Exercise, &'a Set, f32)> {
+    sets.iter()
+        .filter_map(|&(exercise, set)| Some((exercise, set, value(set)?)))
+        .fold(None, |best, candidate| match best {
+            Some(best) if best.2 >= candidate.2 => Some(best),
+            _ => Some(candidate),
+        })
+}
+
+fn set_volume(set: &Set) -> Option<f32> {
+    Some(set.effective_load? * set.reps as f32).filter(|volume| *volume > 0.0)
+}
+
+/// Completed sets of a workout grouped by the canonical name of their exercise, in workout
+/// order.
+fn sets_by_exercise(workout: &Workout) -> Vec<(&str, Vec<(&Exercise, &Set)>)> {
+    let mut groups: Vec<(&str, Vec<(&Exercise, &Set)>)> = Vec::new();
+    for exercise in &workout.exercises {
+        let index = match groups
+            .iter()
+            .position(|(name, _)| *name == exercise.canonical_name)
+        {
+            Some(index) => index,
+            None => {
+                groups.push((&exercise.canonical_name, Vec::new()));
+                groups.len() - 1
+            }
+        };
+        groups[index].1.extend(
+            exercise
+                .sets
+                .iter()
+                .filter(|set| set.is_completed)
+                .map(|set| (exercise, set)),
+        );
+    }
+    groups
+}
+
+/// Keeps the records of all exercises while workouts are added in chronological order.
+#[derive(Default, Debug, Clone)]
+pub struct PersonalRecords {
+    formula: OneRepMaxFormula,
+    exercises: HashMap<String, ExerciseRecords>,
+}
+
+impl PersonalRecords {
+    /// Creates empty records, estimating one-rep maxes with `formula`.
+    pub fn new(formula: OneRepMaxFormula) -> Self {
+        Self {
+            formula,
+            exercises: HashMap::new(),
+        }
+    }
+
+    /// Adds the next workout and returns the records it set. Each exercise emits at most one
+    /// event per kind and workout, the best one, except for `MostReps`, which is emitted for
+    /// every load the reps record was beaten at.
+    pub fn add_workout(&mut self, workout: &Workout) -> Vec<PersonalRecord> {
+        let formula = self.formula;
+        let mut records = Vec::new();
+        for (name, sets) in sets_by_exercise(workout) {
+            let current = self.exercises.entry(name.to_string()).or_default();
+            let mut push = |kind, exercise: &Exercise, set: Option<&Set>, value, previous| {
+                records.push(PersonalRecord {
+                    kind,
+                    exercise: name.to_string(),
+                    measurement_id: exercise.measurement_id.clone(),
+                    workout_id: workout.id.clone(),
+                    start_date: workout.start_date.clone(),
+                    exercise_id: exercise.id.clone(),
+                    set_id: set.map(|set| set.id.clone()),
+                    effective_load: set.and_then(|set| set.effective_load),
+                    value,
+                    previous,
+                })
+            };
+
+            if let Some((exercise, set, value)) = best_set(&sets, |set| set.effective_load)
+                && let Some(previous) = beat(&mut current.heaviest_weight, value)
+            {
+                push(
+                    RecordKind::HeaviestWeight,
+                    exercise,
+                    Some(set),
+                    value,
+                    previous,
+                );
+            }
+            for (exercise, set, previous) in current.beat_rep_records(&sets) {
+                let previous = previous.map(|reps| reps as f32);
+                push(
+                    RecordKind::MostReps,
+                    exercise,
+                    Some(set),
+                    set.reps as f32,
+                    previous,
+                );
+            }
+            if let Some((exercise, set, value)) = best_set(&sets, |set| formula.estimate_set(set))
+                && let Some(previous) = beat(&mut current.one_rep_max, value)
+            {
+                push(
+                    RecordKind::BestOneRepMax,
+                    exercise,
+                    Some(set),
+                    value,
+                    previous,
+                );
+            }
+            if let Some((exercise, set, value)) = best_set(&sets, set_volume)
+                && let Some(previous) = beat(&mut current.set_volume, value)
+            {
+                push(
+                    RecordKind::BestSetVolume,
+                    exercise,
+                    Some(set),
+                    value,
+                    previous,
+                );
+            }
+            let session_volume: f32 = sets.iter().filter_map(|(_, set)| set_volume(set)).sum();
+            if let Some(&(exercise, _)) = sets.first().filter(|_| session_volume > 0.0)
+                && let Some(previous) = beat(&mut current.session_volume, session_volume)
+            {
+                push(
+                    RecordKind::BestSessionVolume,
+                    exercise,
+                    None,
+                    session_volume,
+                    previous,
+                );
+            }
+        }
+        records
+    }
+}
+
+/// All records of a history. Workouts are walked by start date, those without one come last.
+pub fn personal_record_timeline(
+    workouts: &[Workout],
+    formula: OneRepMaxFormula,
+) -> Vec<PersonalRecord> {
+    let mut chronological: Vec<&Workout> = workouts.iter().collect();
+    chronological.sort_by_key(|workout| {
+        let start = workout.start_time();
+        (start.is_none(), start)
+    });
+
+    let mut records = PersonalRecords::new(formula);
+    chronological
+        .into_iter()
+        .flat_map(|workout| records.add_workout(workout))
+        .collect()
+}
@@ -0,0 +1,282 @@
+//! Progression detection per exercise.
+//!
+//! For every exercise, each session's best estimated one-rep max or top set weight within a
+//! window of weeks is fitted with a least squares regression over time. The slope relative to
+//! the mean value classifies the exercise as progressing, plateaued or regressing. The window
+//! ends with the latest workout, exercises are told apart by their `canonical_name`.
+
+use super::one_rep_max::OneRepMaxFormula;
+use crate::data_transformer::Workout;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use time::{Date, Duration, OffsetDateTime};
+
+const SECONDS_PER_WEEK: f32 = 7.0 * 24.0 * 60.0 * 60.0;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressionMetric {
+    /// Best estimated one-rep max of the session.
+    #[default]
+    OneRepMax,
+    /// Heaviest `effective_load` of the session.
+    TopSetWeight,
+}
+
+impl fmt::Display for ProgressionMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match self {
+            ProgressionMetric::OneRepMax => "one_rep_max",
+            ProgressionMetric::TopSetWeight => "top_set_weight",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Trend {
+    Progressing,
+    Plateaued,
+    Regressing,
+}
+
+impl fmt::Display for Trend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match self {
+            Trend::Progressing => "progressing",
+            Trend::Plateaued => "plateaued",
+            Trend::Regressing => "regressing",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+/// What to fit and when a trend counts as progressing or regressing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProgressionOptions {
+    metric: ProgressionMetric,
+    formula: OneRepMaxFormula,
+    window_weeks: u32,
+    min_sessions: usize,
+    threshold_percent: f32,
+}
+
+impl Default for ProgressionOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProgressionOptions {
+    /// Best Epley e1RM over the last 8 weeks, with at least 3 sessions, and a change of 0.5%
+    /// of the mean value per week to count as progressing or regressing.
+    pub fn new() -> Self {
+        Self {
+            metric: ProgressionMetric::OneRepMax,
+            formula: OneRepMaxFormula::Epley,
+            window_weeks: 8,
+            min_sessions: 3,
+            threshold_percent: 0.5,
+        }
+    }
+
+    pub fn with_metric(mut self, metric: ProgressionMetric) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    /// Sets the formula for `ProgressionMetric::OneRepMax`.
+    pub fn with_formula(mut self, formula: OneRepMaxFormula) -> Self {
+        self.formula = formula;
+        self
+    }
+
+    pub fn with_window_weeks(mut self, weeks: u32) -> Self {
+        self.window_weeks = weeks;
+        self
+    }
+
+    /// Sets the fewest sessions in the window an exercise needs to be classified.
+    pub fn with_min_sessions(mut self, sessions: usize) -> Self {
+        self.min_sessions = sessions;
+        self
+    }
+
+    /// Sets the change per week, in percent of the mean value, from which on an exercise is
+    /// progressing or regressing.
+    pub fn with_threshold_percent(mut self, percent: f32) -> Self {
+        self.threshold_percent = percent;
+        self
+    }
+}
+
+/// The trend of one exercise and the evidence it was based on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExerciseProgression {
+    /// The exercise's `canonical_name`.
+    pub exercise: String,
+    pub trend: Trend,
+    pub sessions: u32,
+    pub first_session: Date,
+    pub last_session: Date,
+    pub first_value: f32,
+    pub last_value: f32,
+    pub best_value: f32,
+    /// Slope of the regression in kilograms per week.
+    pub slope_per_week: f32,
+    /// The slope in percent of the mean value.
+    pub change_percent_per_week: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProgressionReport {
+    pub metric: ProgressionMetric,
+    pub window_weeks: u32,
+    /// Start of the latest workout, the end of the window.
+    pub until: Option<Date>,
+    /// Exercises with enough sessions in the window, sorted by name.
+    pub exercises: Vec<ExerciseProgression>,
+}
+
+/// Classifies every exercise with at least the minimum number of sessions in the window.
+pub fn detect_progression(workouts: &[Workout], options: &ProgressionOptions) -> ProgressionReport {
+    let until = workouts.iter().filter_map(Workout::start_time).max();
+    let window_start = until.map(|until| until - Duration::weeks(i64::from(options.window_weeks)));
+
+    let mut sessions: BTreeMap<&str, Vec<(OffsetDateTime, Date, f32)>> = BTreeMap::new();
+    for workout in workouts {
+        let (Some(start), Some(local_start)) = (workout.start_time(), workout.local_start_time())
+        else {
+            continue;
+        };
+        if window_start.is_some_and(|window_start| start <= window_start) {
+            continue;
+        }
+        let mut best: BTreeMap<&str, f32> = BTreeMap::new();
+        for exercise in &workout.exercises {
+            for set in &exercise.sets {
+                let value = match options.metric {
+                    ProgressionMetric::OneRepMax => options.formula.estimate_set(set),
+                    ProgressionMetric::TopSetWeight => {
+                        set.effective_load.filter(|_| set.is_completed)
+                    }
+                };
+                if let Some(value) = value {
+                    let entry = best.entry(&exercise.canonical_name).or_insert(value);
+                    *entry = entry.max(value);
+                }
+            }
+        }
+        for (exercise, value) in best {
+            sessions
+                .entry(exercise)
+                .or_default()
+                .push((start, local_start.date(), value));
+        }
+    }
+
+    let exercises = sessions
+        .into_iter()
+        .filter(|(_, sessions)| sessions.len() >= options.min_sessions.max(1))
+        .map(|(exercise, mut sessions)| {
+            sessions.sort_by_key(|(start, _, _)| *start);
+            classify(exercise, &sessions, options)
+        })
+        .collect();
+
+    ProgressionReport {
+        metric: options.metric,
+        window_weeks: options.window_weeks,
+        until: until.map(|until| until.date()),
+        exercises,
+    }
+}
+
+fn classify(
+    exercise: &str,
+    sessions: &[(OffsetDateTime, Date, f32)],
+    options: &ProgressionOptions,
+) -> ExerciseProgression {
+    let first = sessions[0];
+    let last = sessions[sessions.len() - 1];
+    let points: Vec<(f32, f32)> = sessions
+        .iter()
+        .map(|(start, _, value)| {
+            (
+                (*start - first.0).as_seconds_f32() / SECONDS_PER_WEEK,
+                *value,
+            )
+        })
+        .collect();
+
+    let count = points.len() as f32;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f32>() / count;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f32>() / count;
+    let covariance: f32 = points
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let variance: f32 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let slope_per_week = if variance > 0.0 {
+        covariance / variance
+    } else {
+        0.0
+    };
+    let change_percent_per_week = if mean_y > 0.0 {
+        slope_per_week / mean_y * 100.0
+    } else {
+        0.0
+    };
+
+    let trend = if change_percent_per_week >= options.threshold_percent {
+        Trend::Progressing
+    } else if change_percent_per_week <= -options.threshold_percent {
+        Trend::Regressing
+    } else {
+        Trend::Plateaued
+    };
+
+    ExerciseProgression {
+        exercise: exercise.to_string(),
+        trend,
+        sessions: sessions.len() as u32,
+        first_session: first.1,
+        last_session: last.1,
+        first_value: first.2,
+        last_value: last.2,
+        best_value: sessions
+            .iter()
+            .map(|(_, _, value)| *value)
+            .fold(f32::MIN, f32::max),
+        slope_per_week,
+        change_percent_per_week,
+    }
+}
+
+impl fmt::Display for ProgressionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Progression of {} over {} week(s): {} exercise(s)",
+            self.metric,
+            self.window_weeks,
+            self.exercises.len()
+        )?;
+        for exercise in &self.exercises {
+            write!(
+                f,
+                "\n  {}: {}, {:.1} -> {:.1} kg in {} sessions ({:+.2} kg, {:+.2}% per week)",
+                exercise.exercise,
+                exercise.trend,
+                exercise.first_value,
+                exercise.last_value,
+                exercise.sessions,
+                exercise.slope_per_week,
+                exercise.change_percent_per_week
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,451 @@
+//! DOTS, Wilks and IPF GL scores of the squat, bench press and deadlift bests.
+//!
+//! Which exercises count as the big three is up to the user, `BigThree` maps measurement ids
+//! to a lift:
+//!
+//! ```toml
+//! "b2f5a2de-c684-4e94-a6e5-581e0695fcac" = "squat"
+//! "ca9ee259-a69f-4839-bbf9-46ba8cf0d7d6" = "bench"
+//! "4d1d8e4a-1f3b-4a4e-9a53-0d0c7d4b6c1e" = "deadlift"
+//! ```
+//!
+//! The best of a lift is its heaviest completed `effective_load`, or its best estimated
+//! one-rep max with `ScoreOptions::with_estimate()`. Scores are computed on the total of the
+//! three bests and the athlete's body weight at the latest of the workouts they come from,
+//! taken from `Workout::body_weight`. Without all three bests or a body weight, there are no
+//! scores. The IPF GL points are those of classic (raw) powerlifting.
+
+use crate::analytics::one_rep_max::OneRepMaxFormula;
+use crate::data_transformer::Workout;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Sex {
+    #[default]
+    Male,
+    Female,
+}
+
+impl fmt::Display for Sex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match self {
+            Sex::Male => "male",
+            Sex::Female => "female",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Lift {
+    Squat,
+    Bench,
+    Deadlift,
+}
+
+impl fmt::Display for Lift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match self {
+            Lift::Squat => "squat",
+            Lift::Bench => "bench",
+            Lift::Deadlift => "deadlift",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoreFormula {
+    Dots,
+    Wilks,
+    IpfGl,
+}
+
+// Polynomial coefficients of the DOTS and Wilks denominators, lowest power first.
+const DOTS_MALE: [f64; 5] = [
+    -307.75076,
+    24.0900756,
+    -0.1918759221,
+    0.0007391293,
+    -0.000001093,
+];
+const DOTS_FEMALE: [f64; 5] = [
+    -57.96288,
+    13.6175032,
+    -0.1126655495,
+    0.0005158568,
+    -0.0000010706,
+];
+const WILKS_MALE: [f64; 6] = [
+    -216.0475144,
+    16.2606339,
+    -0.002388645,
+    -0.00113732,
+    7.01863e-6,
+    -1.291e-8,
+];
+const WILKS_FEMALE: [f64; 6] = [
+    594.31747775582,
+    -27.23842536447,
+    0.82112226871,
+    -0.00930733913,
+    4.731582e-5,
+    -9.054e-8,
+];
+// Parameters A, B and C of the IPF GL formula for classic powerlifting.
+const IPF_GL_MALE: [f64; 3] = [1199.72839, 1025.18162, 0.00921];
+const IPF_GL_FEMALE: [f64; 3] = [610.32796, 1045.59282, 0.03048];
+
+impl ScoreFormula {
+    /// Points of a `total` lifted at `body_weight`, both in kilograms. Body weights outside
+    /// the range a formula was fitted for are clamped to it.
+    pub fn points(self, sex: Sex, body_weight: f32, total: f32) -> f32 {
+        let body_weight = f64::from(body_weight);
+        let coefficient = match (self, sex) {
+            (ScoreFormula::Dots, Sex::Male) => {
+                500.0 / polynomial(body_weight.clamp(40.0, 210.0), &DOTS_MALE)
+            }
+            (ScoreFormula::Dots, Sex::Female) => {
+                500.0 / polynomial(body_weight.clamp(40.0, 150.0), &DOTS_FEMALE)
+            }
+            (ScoreFormula::Wilks, Sex::Male) => {
+                500.0 / polynomial(body_weight.clamp(40.0, 201.9), &WILKS_MALE)
+            }
+            (ScoreFormula::Wilks, Sex::Female) => {
+                500.0 / polynomial(body_weight.clamp(26.51, 154.53), &WILKS_FEMALE)
+            }
+            (ScoreFormula::IpfGl, Sex::Male) => 100.0 / ipf_gl(body_weight, IPF_GL_MALE),
+            (ScoreFormula::IpfGl, Sex::Female) => 100.0 / ipf_gl(body_weight, IPF_GL_FEMALE),
+        };
+        (coefficient * f64::from(total)) as f32
+    }
+}
+
+impl fmt::Display for ScoreFormula {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match self {
+            ScoreFormula::Dots => "dots",
+            ScoreFormula::Wilks => "wilks",
+            ScoreFormula::IpfGl => "ipf_gl",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+/// `coefficients[0] + coefficients[1] × x + coefficients[2] × x² + …`
+fn polynomial(x: f64, coefficients: &[f64]) -> f64 {
+    coefficients
+        .iter()
+        .rev()
+        .fold(0.0, |sum, coefficient| sum * x + coefficient)
+}
+
+/// `A - B × e^(-C × body weight)`
+fn ipf_gl(body_weight: f64, [a, b, c]: [f64; 3]) -> f64 {
+    a - b * (-c * body_weight).exp()
+}
+
+/// The measurement ids of the squat, bench press and deadlift.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct BigThree {
+    by_measurement_id: HashMap<String, Lift>,
+}
+
+impl BigThree {
+    /// Builds the mapping from `(measurement id, lift)` pairs. Several measurements can map to
+    /// the same lift, e.g. a custom and a global squat.
+    pub fn new<I, K>(lifts: I) -> Self
+    where
+        I: IntoIterator<Item = (K, Lift)>,
+        K: Into<String>,
+    {
+        Self {
+            by_measurement_id: lifts
+                .into_iter()
+                .map(|(measurement_id, lift)| (measurement_id.into(), lift))
+                .collect(),
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let map: HashMap<String, Lift> = serde_json::from_str(json)?;
+        Ok(Self::new(map))
+    }
+
+    pub fn from_toml(toml: &str) -> Result<Self, toml::de::Error> {
+        let map: HashMap<String, Lift> = toml::from_str(toml)?;
+        Ok(Self::new(map))
+    }
+
+    /// Reads the mapping from a `.json` file, anything else is read as TOML.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("json") => {
+                Ok(Self::from_json(&content)?)
+            }
+            _ => Ok(Self::from_toml(&content)?),
+        }
+    }
+
+    pub fn with_measurement(mut self, measurement_id: impl Into<String>, lift: Lift) -> Self {
+        self.by_measurement_id.insert(measurement_id.into(), lift);
+        self
+    }
+
+    pub fn lift(&self, measurement_id: &str) -> Option<Lift> {
+        self.by_measurement_id.get(measurement_id).copied()
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct ScoreOptions {
+    sex: Sex,
+    big_three: BigThree,
+    estimate: Option<OneRepMaxFormula>,
+}
+
+impl ScoreOptions {
+    /// Male athlete, no lifts mapped and bests by the heaviest weight lifted.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_sex(mut self, sex: Sex) -> Self {
+        self.sex = sex;
+        self
+    }
+
+    pub fn with_big_three(mut self, big_three: BigThree) -> Self {
+        self.big_three = big_three;
+        self
+    }
+
+    /// Takes the best estimated one-rep max as the best of a lift instead of the heaviest
+    /// weight lifted.
+    pub fn with_estimate(mut self, formula: OneRepMaxFormula) -> Self {
+        self.estimate = Some(formula);
+        self
+    }
+}
+
+/// The best of one lift and where it was set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LiftBest {
+    pub lift: Lift,
+    /// The exercise's `canonical_name`.
+    pub exercise: String,
+    pub workout_id: String,
+    pub start_date: Option<String>,
+    pub set_id: String,
+    /// Kilograms.
+    pub weight: f32,
+}
+
+/// The big three bests of an athlete and their scores.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrengthScores {
+    pub athlete: String,
+    pub sex: Sex,
+    /// Kilograms, at the latest of the workouts the bests come from.
+    pub body_weight: Option<f32>,
+    pub squat: Option<LiftBest>,
+    pub bench: Option<LiftBest>,
+    pub deadlift: Option<LiftBest>,
+    /// Sum of the three bests in kilograms, `None` unless all three were done.
+    pub total: Option<f32>,
+    pub dots: Option<f32>,
+    pub wilks: Option<f32>,
+    pub ipf_gl: Option<f32>,
+}
+
+impl StrengthScores {
+    pub fn score(&self, formula: ScoreFormula) -> Option<f32> {
+        match formula {
+            ScoreFormula::Dots => self.dots,
+            ScoreFormula::Wilks => self.wilks,
+            ScoreFormula::IpfGl => self.ipf_gl,
+        }
+    }
+}
+
+impl fmt::Display for StrengthScores {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}", self.athlete, self.sex)?;
+        if let Some(body_weight) = self.body_weight {
+            write!(f, ", {:.1} kg", body_weight)?;
+        }
+        write!(f, ")")?;
+        match (self.total, self.dots, self.wilks, self.ipf_gl) {
+            (Some(total), Some(dots), Some(wilks), Some(ipf_gl)) => write!(
+                f,
+                ": {:.1} kg total, DOTS {:.2}, Wilks {:.2}, IPF GL {:.2}",
+                total, dots, wilks, ipf_gl
+            )?,
+            (Some(total), ..) => write!(f, ": {:.1} kg total, no body weight", total)?,
+            _ => write!(f, ": incomplete total")?,
+        }
+        for best in [&self.squat, &self.bench, &self.deadlift]
+            .into_iter()
+            .flatten()
+        {
+            write!(
+                f,
+                "\n  {}: {:.1} kg ({})",
+                best.lift, best.weight, best.exercise
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The big three bests of `workouts` and their scores.
+pub fn strength_scores(
+    athlete: &str,
+    workouts: &[Workout],
+    options: &ScoreOptions,
+) -> StrengthScores {
+    // Best of each lift with the index of its workout.
+    let mut bests: HashMap<Lift, (usize, LiftBest)> = HashMap::new();
+    for (index, workout) in workouts.iter().enumerate() {
+        for exercise in &workout.exercises {
+            let Some(lift) = options.big_three.lift(&exercise.measurement_id) else {
+                continue;
+            };
+            for set in exercise.sets.iter().filter(|set| set.reps > 0) {
+                let weight = match options.estimate {
+                    Some(formula) => formula.estimate_set(set),
+                    None => set.effective_load.filter(|_| set.is_completed),
+                };
+                let Some(weight) = weight.filter(|weight| *weight > 0.0) else {
+                    continue;
+                };
+                if bests
+                    .get(&lift)
+                    .is_some_and(|(_, best)| best.weight >= weight)
+                {
+                    continue;
+                }
+                let best = LiftBest {
+                    lift,
+                    exercise: exercise.canonical_name.clone(),
+                    workout_id: workout.id.clone(),
+                    start_date: workout.start_date.clone(),
+                    set_id: set.id.clone(),
+                    weight,
+                };
+                bests.insert(lift, (index, best));
+            }
+        }
+    }
+
+    let latest = bests
+        .values()
+        .map(|(index, _)| &workouts[*index])
+        .max_by_key(|workout| workout.start_time());
+    let body_weight = latest.and_then(|workout| workout.body_weight);
+    let mut best = |lift| bests.remove(&lift).map(|(_, best)| best);
+    let (squat, bench, deadlift) = (best(Lift::Squat), best(Lift::Bench), best(Lift::Deadlift));
+    let total = match (&squat, &bench, &deadlift) {
+        (Some(squat), Some(bench), Some(deadlift)) => {
+            Some(squat.weight + bench.weight + deadlift.weight)
+        }
+        _ => None,
+    };
+    let points = |formula: ScoreFormula| Some(formula.points(options.sex, body_weight?, total?));
+
+    StrengthScores {
+        athlete: athlete.to_string(),
+        sex: options.sex,
+        body_weight,
+        squat,
+        bench,
+        deadlift,
+        total,
+        dots: points(ScoreFormula::Dots),
+        wilks: points(ScoreFormula::Wilks),
+        ipf_gl: points(ScoreFormula::IpfGl),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    /// 1 for the highest score, equal scores share a rank.
+    pub rank: u32,
+    pub score: f32,
+    pub scores: StrengthScores,
+}
+
+/// Athletes ranked by one of the scores.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub formula: ScoreFormula,
+    pub entries: Vec<LeaderboardEntry>,
+    /// Athletes without a score for lack of a best or a body weight.
+    pub unranked: Vec<String>,
+}
+
+impl fmt::Display for Leaderboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Leaderboard by {}: {} athlete(s), {} unranked",
+            self.formula,
+            self.entries.len(),
+            self.unranked.len()
+        )?;
+        for entry in &self.entries {
+            write!(
+                f,
+                "\n  {}. {} ({}, {:.1} kg): {:.2} with {:.1} kg",
+                entry.rank,
+                entry.scores.athlete,
+                entry.scores.sex,
+                entry.scores.body_weight.unwrap_or_default(),
+                entry.score,
+                entry.scores.total.unwrap_or_default()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Ranks the athletes of `scores` by `formula`, highest first.
+pub fn leaderboard(scores: Vec<StrengthScores>, formula: ScoreFormula) -> Leaderboard {
+    let (mut scored, unranked): (Vec<_>, Vec<_>) = scores
+        .into_iter()
+        .map(|scores| (scores.score(formula), scores))
+        .partition(|(score, _)| score.is_some());
+    scored.sort_by(|(a, _), (b, _)| b.unwrap_or_default().total_cmp(&a.unwrap_or_default()));
+
+    let mut entries: Vec<LeaderboardEntry> = Vec::with_capacity(scored.len());
+    for (position, (score, scores)) in scored.into_iter().enumerate() {
+        let score = score.unwrap_or_default();
+        let rank = match entries.last() {
+            Some(previous) if previous.score == score => previous.rank,
+            _ => position as u32 + 1,
+        };
+        entries.push(LeaderboardEntry {
+            rank,
+            score,
+            scores,
+        });
+    }
+
+    Leaderboard {
+        formula,
+        entries,
+        unranked: unranked
+            .into_iter()
+            .map(|(_, scores)| scores.athlete)
+            .collect(),
+    }
+}
@@ -0,0 +1,228 @@
+//! Sets, reps, tonnage and hard sets aggregated by workout, exercise, week, month and tag.
+//!
+//! Only completed sets count. Tonnage is `effective_load × reps` in kilograms and hard sets
+//! are sets with an RPE of at least `HARD_SET_RPE`. Weeks and months are taken from the start
+//! of each workout in its own timezone, workouts without a start date are left out of them.
+//! Periods, exercises and tags without any completed set aren't listed.
+
+use super::calendar::{iso_week, month_start, week_start};
+use crate::data_transformer::{Exercise, Set, Workout};
+use crate::models::measurement::MeasurementsResponse;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use time::{Date, Weekday};
+
+/// Lowest RPE a set counts as a hard set with.
+pub const HARD_SET_RPE: f32 = 7.0;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Volume {
+    pub sets: u32,
+    pub reps: u32,
+    /// Sum of `effective_load × reps` in kilograms.
+    pub tonnage: f32,
+    pub hard_sets: u32,
+}
+
+impl Volume {
+    /// Volume of the completed ones of `sets`.
+    pub fn of_sets<'a>(sets: impl IntoIterator<Item = &'a Set>) -> Self {
+        let mut volume = Volume::default();
+        for set in sets {
+            volume.add_set(set);
+        }
+        volume
+    }
+
+    /// Adds a set, unless it isn't completed.
+    pub fn add_set(&mut self, set: &Set) {
+        if !set.is_completed {
+            return;
+        }
+        self.sets += 1;
+        self.reps += set.reps;
+        self.tonnage += set.effective_load.unwrap_or(0.0) * set.reps as f32;
+        if set.rpe.is_some_and(|rpe| rpe >= HARD_SET_RPE) {
+            self.hard_sets += 1;
+        }
+    }
+
+    pub fn add(&mut self, other: &Volume) {
+        self.sets += other.sets;
+        self.reps += other.reps;
+        self.tonnage += other.tonnage;
+        self.hard_sets += other.hard_sets;
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkoutVolume {
+    pub workout_id: String,
+    pub name: String,
+    pub start_date: Option<String>,
+    pub volume: Volume,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExerciseVolume {
+    /// The exercise's `canonical_name`.
+    pub exercise: String,
+    /// Number of workouts the exercise was done in.
+    pub workouts: u32,
+    pub volume: Volume,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeekVolume {
+    /// First day of the week.
+    pub start: Date,
+    pub iso_year: i32,
+    pub iso_week: u8,
+    pub workouts: u32,
+    pub volume: Volume,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonthVolume {
+    /// First day of the month.
+    pub start: Date,
+    pub workouts: u32,
+    pub volume: Volume,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagVolume {
+    /// Tag of the exercises' measurements, e.g. `legs`.
+    pub tag: String,
+    pub workouts: u32,
+    pub volume: Volume,
+}
+
+/// Volume and number of workouts of one group while the workouts are walked in order.
+#[derive(Default)]
+struct Group {
+    workouts: u32,
+    last_workout: Option<usize>,
+    volume: Volume,
+}
+
+impl Group {
+    fn add(&mut self, workout_index: usize, volume: &Volume) {
+        if volume.sets == 0 {
+            return;
+        }
+        if self.last_workout != Some(workout_index) {
+            self.last_workout = Some(workout_index);
+            self.workouts += 1;
+        }
+        self.volume.add(volume);
+    }
+}
+
+/// Groups the volume of every exercise of every workout by the keys `keys` returns for it.
+fn group_by<K: Ord>(
+    workouts: &[Workout],
+    mut keys: impl FnMut(&Workout, &Exercise) -> Vec<K>,
+) -> BTreeMap<K, Group> {
+    let mut groups: BTreeMap<K, Group> = BTreeMap::new();
+    for (index, workout) in workouts.iter().enumerate() {
+        for exercise in &workout.exercises {
+            let volume = Volume::of_sets(&exercise.sets);
+            for key in keys(workout, exercise) {
+                groups.entry(key).or_default().add(index, &volume);
+            }
+        }
+    }
+    groups.retain(|_, group| group.workouts > 0);
+    groups
+}
+
+/// Volume of each workout, in the order of `workouts`.
+pub fn volume_by_workout(workouts: &[Workout]) -> Vec<WorkoutVolume> {
+    workouts
+        .iter()
+        .map(|workout| WorkoutVolume {
+            workout_id: workout.id.clone(),
+            name: workout.name.clone(),
+            start_date: workout.start_date.clone(),
+            volume: Volume::of_sets(workout.exercises.iter().flat_map(|exercise| &exercise.sets)),
+        })
+        .collect()
+}
+
+/// Volume of each exercise by `canonical_name`, sorted by name.
+pub fn volume_by_exercise(workouts: &[Workout]) -> Vec<ExerciseVolume> {
+    group_by(workouts, |_, exercise| {
+        vec![exercise.canonical_name.clone()]
+    })
+    .into_iter()
+    .map(|(exercise, group)| ExerciseVolume {
+        exercise,
+        workouts: group.workouts,
+        volume: group.volume,
+    })
+    .collect()
+}
+
+/// Volume of each week, with weeks starting on `first_weekday`, e.g. from
+/// `UserResponse::first_weekday()`. Sorted chronologically.
+pub fn volume_by_week(workouts: &[Workout], first_weekday: Weekday) -> Vec<WeekVolume> {
+    group_by(workouts, |workout, _| {
+        workout
+            .local_date()
+            .map(|date| week_start(date, first_weekday))
+            .into_iter()
+            .collect()
+    })
+    .into_iter()
+    .map(|(start, group)| {
+        let (iso_year, iso_week) = iso_week(start);
+        WeekVolume {
+            start,
+            iso_year,
+            iso_week,
+            workouts: group.workouts,
+            volume: group.volume,
+        }
+    })
+    .collect()
+}
+
+/// Volume of each month, sorted chronologically.
+pub fn volume_by_month(workouts: &[Workout]) -> Vec<MonthVolume> {
+    group_by(workouts, |workout, _| {
+        workout.local_date().map(month_start).into_iter().collect()
+    })
+    .into_iter()
+    .map(|(start, group)| MonthVolume {
+        start,
+        workouts: group.workouts,
+        volume: group.volume,
+    })
+    .collect()
+}
+
+/// Volume of each tag, like a muscle group, sorted by tag. The tags of an exercise are those of
+/// its measurement in `measurements`, a set counts for every one of them. Exercises without
+/// tags aren't counted.
+pub fn volume_by_tag(workouts: &[Workout], measurements: &MeasurementsResponse) -> Vec<TagVolume> {
+    let tags: HashMap<&str, Vec<&str>> = measurements
+        .embedded
+        .measurements
+        .iter()
+        .map(|measurement| (measurement.id.as_str(), measurement.tags().collect()))
+        .collect();
+
+    group_by(workouts, |_, exercise| {
+        tags.get(exercise.measurement_id.as_str())
+            .cloned()
+            .unwrap_or_default()
+    })
+    .into_iter()
+    .map(|(tag, group)| TagVolume {
+        tag: tag.to_string(),
+        workouts: group.workouts,
+        volume: group.volume,
+    })
+    .collect()
+}
@@ -0,0 +1,206 @@
+//! Acute and chronic workload, their ratio (ACWR) and Foster's monotony and strain.
+//!
+//! The load of a day is the sum of its workouts' loads, either the tonnage or the session RPE
+//! (sRPE, the workout's duration in minutes times the mean RPE of its sets). Days are local to
+//! each workout's timezone. Acute load is the load of the last 7 days and chronic load the
+//! average weekly load of the last 28 days, so both are on the same scale.
+
+use super::volume::Volume;
+use crate::data_transformer::Workout;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use time::{Date, Duration};
+
+const ACUTE_DAYS: usize = 7;
+const CHRONIC_DAYS: usize = 28;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkloadMeasure {
+    /// `effective_load × reps` of all completed sets, in kilograms.
+    #[default]
+    Tonnage,
+    /// Duration in minutes times the mean RPE of the completed sets with an RPE. Workouts
+    /// without an end date or without any RPE have no load.
+    SessionRpe,
+}
+
+impl WorkloadMeasure {
+    /// The load of a single workout.
+    pub fn workout_load(self, workout: &Workout) -> f32 {
+        let sets = workout.exercises.iter().flat_map(|exercise| &exercise.sets);
+        match self {
+            WorkloadMeasure::Tonnage => Volume::of_sets(sets).tonnage,
+            WorkloadMeasure::SessionRpe => {
+                let rpes: Vec<f32> = sets
+                    .filter(|set| set.is_completed)
+                    .filter_map(|set| set.rpe)
+                    .collect();
+                let minutes = match (workout.start_time(), workout.end_time()) {
+                    (Some(start), Some(end)) => (end - start).as_seconds_f32().max(0.0) / 60.0,
+                    _ => 0.0,
+                };
+                if rpes.is_empty() {
+                    return 0.0;
+                }
+                minutes * rpes.iter().sum::<f32>() / rpes.len() as f32
+            }
+        }
+    }
+}
+
+impl fmt::Display for WorkloadMeasure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match self {
+            WorkloadMeasure::Tonnage => "tonnage",
+            WorkloadMeasure::SessionRpe => "session_rpe",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+/// How to measure workload and which ACWR is considered safe.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorkloadOptions {
+    measure: WorkloadMeasure,
+    acwr_low: f32,
+    acwr_high: f32,
+}
+
+impl Default for WorkloadOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WorkloadOptions {
+    /// Tonnage with the commonly used ACWR band of 0.8 to 1.3.
+    pub fn new() -> Self {
+        Self {
+            measure: WorkloadMeasure::Tonnage,
+            acwr_low: 0.8,
+            acwr_high: 1.3,
+        }
+    }
+
+    pub fn with_measure(mut self, measure: WorkloadMeasure) -> Self {
+        self.measure = measure;
+        self
+    }
+
+    /// Sets the band of ACWRs that aren't flagged, both bounds included.
+    pub fn with_acwr_band(mut self, low: f32, high: f32) -> Self {
+        self.acwr_low = low;
+        self.acwr_high = high;
+        self
+    }
+}
+
+/// Why a day's ACWR is flagged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AcwrFlag {
+    /// Below the band, the athlete is doing less than they are used to.
+    BelowBand,
+    /// Above the band, the load is rising faster than the athlete is used to.
+    AboveBand,
+}
+
+impl fmt::Display for AcwrFlag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match self {
+            AcwrFlag::BelowBand => "below_band",
+            AcwrFlag::AboveBand => "above_band",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyWorkload {
+    pub date: Date,
+    pub measure: WorkloadMeasure,
+    /// Load of the day itself.
+    pub load: f32,
+    /// Load of the last 7 days.
+    pub acute: f32,
+    /// Average weekly load of the last 28 days.
+    pub chronic: f32,
+    /// `acute / chronic`. `None` until there are 28 days of history and while the chronic
+    /// load is 0.
+    pub acwr: Option<f32>,
+    /// Mean daily load of the last 7 days divided by its standard deviation, `None` if all
+    /// days had the same load.
+    pub monotony: Option<f32>,
+    /// `acute × monotony`.
+    pub strain: Option<f32>,
+    pub flag: Option<AcwrFlag>,
+}
+
+/// The workload of every day from the first workout up to `until`, e.g. `calendar::today()`.
+/// Workouts without a start date are left out.
+pub fn daily_workload(
+    workouts: &[Workout],
+    options: &WorkloadOptions,
+    until: Date,
+) -> Vec<DailyWorkload> {
+    let mut loads: BTreeMap<Date, f32> = BTreeMap::new();
+    for workout in workouts {
+        if let Some(start) = workout.local_start_time() {
+            *loads.entry(start.date()).or_default() += options.measure.workout_load(workout);
+        }
+    }
+    let Some(first_day) = loads.keys().next().copied() else {
+        return Vec::new();
+    };
+
+    let mut days: Vec<f32> = Vec::new();
+    let mut series = Vec::new();
+    let mut date = first_day;
+    while date <= until {
+        days.push(loads.get(&date).copied().unwrap_or(0.0));
+        let acute_days = &days[days.len().saturating_sub(ACUTE_DAYS)..];
+        let acute: f32 = acute_days.iter().sum();
+        let chronic: f32 = days[days.len().saturating_sub(CHRONIC_DAYS)..]
+            .iter()
+            .sum::<f32>()
+            / (CHRONIC_DAYS / ACUTE_DAYS) as f32;
+
+        let acwr = (days.len() >= CHRONIC_DAYS && chronic > 0.0).then(|| acute / chronic);
+        let monotony = monotony(acute_days);
+        let flag = acwr.and_then(|acwr| {
+            if acwr < options.acwr_low {
+                Some(AcwrFlag::BelowBand)
+            } else if acwr > options.acwr_high {
+                Some(AcwrFlag::AboveBand)
+            } else {
+                None
+            }
+        });
+
+        series.push(DailyWorkload {
+            date,
+            measure: options.measure,
+            load: days[days.len() - 1],
+            acute,
+            chronic,
+            acwr,
+            monotony,
+            strain: monotony.map(|monotony| acute * monotony),
+            flag,
+        });
+        date += Duration::days(1);
+    }
+    series
+}
+
+/// Foster's monotony of the daily loads, the days before the first workout count as rest days.
+fn monotony(days: &[f32]) -> Option<f32> {
+    let mut days = days.to_vec();
+    days.resize(ACUTE_DAYS, 0.0);
+    let mean = days.iter().sum::<f32>() / ACUTE_DAYS as f32;
+    let variance = days.iter().map(|load| (load - mean).powi(2)).sum::<f32>() / ACUTE_DAYS as f32;
+    let deviation = variance.sqrt();
+    (deviation > 0.0).then(|| mean / deviation)
+}
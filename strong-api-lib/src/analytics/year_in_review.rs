@@ -0,0 +1,303 @@
+//! A lifter's year in numbers.
+//!
+//! Sessions, hours, tonnage, favourite exercises, the records that grew the most, the longest
+//! streaks, the busiest weekday and month, and a heatmap with every day of the year. Workouts
+//! belong to the year and day they started on in their own timezone and only count with at
+//! least one completed set. Weeks start on Monday.
+
+use super::frequency::{Streak, training_frequency};
+use super::one_rep_max::OneRepMaxFormula;
+use super::personal_records::{RecordKind, personal_record_timeline};
+use super::volume::Volume;
+use crate::data_transformer::Workout;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use time::{Date, Duration, Month, Weekday};
+
+/// How many favourite exercises and records are listed.
+pub const TOP: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FavouriteExercise {
+    /// The exercise's `canonical_name`.
+    pub exercise: String,
+    /// Workouts the exercise was done in.
+    pub sessions: u32,
+    /// Completed sets.
+    pub sets: u32,
+}
+
+/// How much the heaviest weight or best e1RM of an exercise grew over the year.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordGain {
+    /// The exercise's `canonical_name`.
+    pub exercise: String,
+    pub kind: RecordKind,
+    /// The record before the first one set in the year, in kilograms.
+    pub before: f32,
+    /// The record at the end of the year, in kilograms.
+    pub after: f32,
+    pub percent: f32,
+    /// The workout the last record of the year was set in.
+    pub workout_id: String,
+    pub start_date: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HeatmapDay {
+    pub date: Date,
+    pub workouts: u32,
+    /// Kilograms.
+    pub tonnage: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct YearInReview {
+    pub year: i32,
+    pub sessions: u32,
+    pub training_days: u32,
+    /// Time between start and end of the workouts.
+    pub hours: f32,
+    pub sets: u32,
+    pub reps: u32,
+    /// Kilograms.
+    pub tonnage: f32,
+    /// Exercises done in the most workouts, then with the most sets.
+    pub favourite_exercises: Vec<FavouriteExercise>,
+    /// Records that grew the most, by percent. The first workout of an exercise sets no record,
+    /// so an exercise started that year grows from its first workout.
+    pub biggest_records: Vec<RecordGain>,
+    /// Consecutive days with a workout.
+    pub longest_streak: Option<Streak>,
+    /// Consecutive weeks with a workout.
+    pub longest_weekly_streak: Option<Streak>,
+    /// Workouts per weekday, Monday first.
+    pub workouts_by_weekday: Vec<(Weekday, u32)>,
+    pub busiest_weekday: Option<Weekday>,
+    /// Workouts per month, January first.
+    pub workouts_by_month: Vec<(Month, u32)>,
+    pub busiest_month: Option<Month>,
+    /// Every day of the year.
+    pub heatmap: Vec<HeatmapDay>,
+}
+
+impl fmt::Display for YearInReview {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} in review: {} session(s), {:.1} hour(s), {} sets, {:.0} kg",
+            self.year, self.sessions, self.hours, self.sets, self.tonnage
+        )?;
+        if let Some(streak) = self.longest_streak {
+            write!(
+                f,
+                "\n  Longest streak: {} day(s) from {}",
+                streak.length, streak.start
+            )?;
+        }
+        if let Some(streak) = self.longest_weekly_streak {
+            write!(
+                f,
+                "\n  Longest weekly streak: {} week(s) from {}",
+                streak.length, streak.start
+            )?;
+        }
+        if let (Some(weekday), Some(month)) = (self.busiest_weekday, self.busiest_month) {
+            write!(
+                f,
+                "\n  Busiest weekday: {}, busiest month: {}",
+                weekday, month
+            )?;
+        }
+        for favourite in &self.favourite_exercises {
+            write!(
+                f,
+                "\n  Favourite: {} ({} session(s), {} sets)",
+                favourite.exercise, favourite.sessions, favourite.sets
+            )?;
+        }
+        for record in &self.biggest_records {
+            write!(
+                f,
+                "\n  Record: {} {} {:.1} -> {:.1} kg ({:+.1}%)",
+                record.exercise, record.kind, record.before, record.after, record.percent
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The year in review of `year`. Records are estimated with Epley and taken from the whole
+/// history, so `workouts` should include the years before. Returns `None` for years `time`
+/// can't represent with a week to spare, i.e. before -9998 or after 9998.
+pub fn year_in_review(workouts: &[Workout], year: i32) -> Option<YearInReview> {
+    // Streaks and weeks look a few days past the year, which must not overflow `Date`.
+    if year <= Date::MIN.year() || year >= Date::MAX.year() {
+        return None;
+    }
+    let first_day = Date::from_ordinal_date(year, 1).ok()?;
+    let last_day = Date::from_ordinal_date(year, time::util::days_in_year(year)).ok()?;
+
+    let of_year: Vec<(&Workout, Date, Volume)> = workouts
+        .iter()
+        .filter_map(|workout| {
+            let date = workout.local_date()?;
+            let volume =
+                Volume::of_sets(workout.exercises.iter().flat_map(|exercise| &exercise.sets));
+            (date.year() == year && volume.sets > 0).then_some((workout, date, volume))
+        })
+        .collect();
+
+    let mut review = YearInReview {
+        year,
+        sessions: of_year.len() as u32,
+        training_days: 0,
+        hours: 0.0,
+        sets: 0,
+        reps: 0,
+        tonnage: 0.0,
+        favourite_exercises: Vec::new(),
+        biggest_records: biggest_records(workouts, &of_year),
+        longest_streak: None,
+        longest_weekly_streak: None,
+        workouts_by_weekday: (0..7)
+            .map(|day| (Weekday::Monday.nth_next(day), 0))
+            .collect(),
+        busiest_weekday: None,
+        workouts_by_month: (0..12)
+            .map(|month| (Month::January.nth_next(month), 0))
+            .collect(),
+        busiest_month: None,
+        heatmap: Vec::new(),
+    };
+
+    let mut days: BTreeMap<Date, HeatmapDay> = BTreeMap::new();
+    let mut favourites: HashMap<&str, (HashSet<&str>, u32)> = HashMap::new();
+    for (workout, date, volume) in &of_year {
+        review.sets += volume.sets;
+        review.reps += volume.reps;
+        review.tonnage += volume.tonnage;
+        if let (Some(start), Some(end)) = (workout.start_time(), workout.end_time()) {
+            review.hours += (end - start).as_seconds_f32().max(0.0) / 3600.0;
+        }
+        review.workouts_by_weekday[date.weekday().number_days_from_monday() as usize].1 += 1;
+        review.workouts_by_month[date.month() as usize - 1].1 += 1;
+
+        let day = days.entry(*date).or_insert(HeatmapDay {
+            date: *date,
+            workouts: 0,
+            tonnage: 0.0,
+        });
+        day.workouts += 1;
+        day.tonnage += volume.tonnage;
+
+        for exercise in &workout.exercises {
+            let sets = Volume::of_sets(&exercise.sets).sets;
+            if sets == 0 {
+                continue;
+            }
+            let favourite = favourites.entry(&exercise.canonical_name).or_default();
+            favourite.0.insert(&workout.id);
+            favourite.1 += sets;
+        }
+    }
+    review.training_days = days.len() as u32;
+
+    let mut favourite_exercises: Vec<FavouriteExercise> = favourites
+        .into_iter()
+        .map(|(exercise, (sessions, sets))| FavouriteExercise {
+            exercise: exercise.to_string(),
+            sessions: sessions.len() as u32,
+            sets,
+        })
+        .collect();
+    favourite_exercises.sort_by(|a, b| {
+        (b.sessions, b.sets)
+            .cmp(&(a.sessions, a.sets))
+            .then_with(|| a.exercise.cmp(&b.exercise))
+    });
+    favourite_exercises.truncate(TOP);
+    review.favourite_exercises = favourite_exercises;
+
+    review.busiest_weekday = busiest(&review.workouts_by_weekday);
+    review.busiest_month = busiest(&review.workouts_by_month);
+
+    let year_workouts: Vec<Workout> = of_year
+        .iter()
+        .map(|(workout, _, _)| (*workout).clone())
+        .collect();
+    let frequency = training_frequency(&year_workouts, Weekday::Monday, Some(1), last_day);
+    review.longest_streak = frequency.longest_daily_streak;
+    review.longest_weekly_streak = frequency.longest_weekly_streak;
+
+    let mut date = first_day;
+    while date <= last_day {
+        review
+            .heatmap
+            .push(days.get(&date).copied().unwrap_or(HeatmapDay {
+                date,
+                workouts: 0,
+                tonnage: 0.0,
+            }));
+        date += Duration::days(1);
+    }
+    Some(review)
+}
+
+/// The value with the most workouts, the first one on ties.
+fn busiest<T: Copy>(counts: &[(T, u32)]) -> Option<T> {
+    counts
+        .iter()
+        .filter(|(_, workouts)| *workouts > 0)
+        .rev()
+        .max_by_key(|(_, workouts)| *workouts)
+        .map(|(value, _)| *value)
+}
+
+/// Growth of the heaviest weight and best e1RM of every exercise with records before and in
+/// the year, the biggest `TOP` by percent.
+fn biggest_records(workouts: &[Workout], of_year: &[(&Workout, Date, Volume)]) -> Vec<RecordGain> {
+    let in_year: HashSet<&str> = of_year
+        .iter()
+        .map(|(workout, _, _)| workout.id.as_str())
+        .collect();
+    let mut gains: HashMap<(String, RecordKind), RecordGain> = HashMap::new();
+    for record in personal_record_timeline(workouts, OneRepMaxFormula::Epley) {
+        let Some(previous) = record.previous else {
+            continue;
+        };
+        if !matches!(
+            record.kind,
+            RecordKind::HeaviestWeight | RecordKind::BestOneRepMax
+        ) || !in_year.contains(record.workout_id.as_str())
+        {
+            continue;
+        }
+        let gain = gains
+            .entry((record.exercise.clone(), record.kind))
+            .or_insert_with(|| RecordGain {
+                exercise: record.exercise.clone(),
+                kind: record.kind,
+                before: previous,
+                after: previous,
+                percent: 0.0,
+                workout_id: String::new(),
+                start_date: None,
+            });
+        gain.after = record.value;
+        gain.percent = (record.value - gain.before) / gain.before * 100.0;
+        gain.workout_id = record.workout_id;
+        gain.start_date = record.start_date;
+    }
+
+    let mut gains: Vec<RecordGain> = gains.into_values().collect();
+    gains.sort_by(|a, b| {
+        b.percent
+            .total_cmp(&a.percent)
+            .then_with(|| a.exercise.cmp(&b.exercise))
+            .then_with(|| a.kind.to_string().cmp(&b.kind.to_string()))
+    });
+    gains.truncate(TOP);
+    gains
+}
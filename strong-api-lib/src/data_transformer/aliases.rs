@@ -0,0 +1,88 @@
+//! User supplied mapping of exercises to a canonical exercise.
+//!
+//! Custom exercises like "Bench" and the global "Bench Press (Barbell)", or a measurement
+//! that was renamed at some point, split the history of what is really one lift. The alias
+//! map merges them: every key, either a measurement id or an exercise name, maps to the
+//! canonical name the exercise is reported under.
+//!
+//! ```toml
+//! "Bench" = "Bench Press (Barbell)"
+//! "4a304d9a-9a7a-440f-bb9a-9fd5cde6b590" = "Bench Press (Barbell)"
+//! ```
+//!
+//! The same map can be given as a JSON object.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::Path;
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct ExerciseAliases {
+    by_measurement_id: HashMap<String, String>,
+    by_name: HashMap<String, String>,
+}
+
+impl ExerciseAliases {
+    /// Builds the aliases from `(measurement id or name, canonical name)` pairs.
+    pub fn new<I, K, V>(aliases: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let mut by_measurement_id = HashMap::new();
+        let mut by_name = HashMap::new();
+        for (key, canonical) in aliases {
+            // A key can be either, so it is registered as both. Ids never collide with names.
+            let key = key.into();
+            let canonical = canonical.into();
+            by_name.insert(normalize(&key), canonical.clone());
+            by_measurement_id.insert(key, canonical);
+        }
+        Self {
+            by_measurement_id,
+            by_name,
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let map: HashMap<String, String> = serde_json::from_str(json)?;
+        Ok(Self::new(map))
+    }
+
+    pub fn from_toml(toml: &str) -> Result<Self, toml::de::Error> {
+        let map: HashMap<String, String> = toml::from_str(toml)?;
+        Ok(Self::new(map))
+    }
+
+    /// Reads the aliases from a `.json` file, anything else is read as TOML.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("json") => {
+                Ok(Self::from_json(&content)?)
+            }
+            _ => Ok(Self::from_toml(&content)?),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_measurement_id.is_empty()
+    }
+
+    /// The canonical name of an exercise. The measurement id wins over the name, which is
+    /// compared ignoring case and surrounding whitespace. Exercises without an alias keep
+    /// their name.
+    pub fn canonical_name(&self, measurement_id: &str, name: &str) -> String {
+        self.by_measurement_id
+            .get(measurement_id)
+            .or_else(|| self.by_name.get(&normalize(name)))
+            .cloned()
+            .unwrap_or_else(|| name.to_string())
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.trim().to_lowercase()
+}
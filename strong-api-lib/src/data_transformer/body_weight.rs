@@ -0,0 +1,70 @@
+use super::cell_value;
+use crate::models::measured_value::MeasuredValue;
+use crate::models::preferences::WeightUnit;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+/// The athlete's body weight in kilograms at a point in time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BodyWeight {
+    pub measured_at: OffsetDateTime,
+    pub weight: f32,
+}
+
+impl BodyWeight {
+    /// Extracts the body weights from the measured values of the given body weight measurement.
+    /// Values are converted from `unit` to kilograms, values without a parsable date or
+    /// number are skipped.
+    pub fn from_measured_values(
+        measured_values: &[MeasuredValue],
+        measurement_id: &str,
+        unit: WeightUnit,
+    ) -> Vec<BodyWeight> {
+        measured_values
+            .iter()
+            .filter(|measured_value| {
+                measured_value
+                    .links
+                    .measurement
+                    .as_ref()
+                    .is_some_and(|link| link.href.rsplit('/').next() == Some(measurement_id))
+            })
+            .filter_map(|measured_value| {
+                let date = measured_value
+                    .date
+                    .as_deref()
+                    .unwrap_or(&measured_value.created);
+                let measured_at = OffsetDateTime::parse(date, &Rfc3339).ok()?;
+                let weight = measured_value
+                    .cells
+                    .iter()
+                    .filter_map(|cell| cell.value.as_deref())
+                    .find_map(cell_value::parse_decimal)?;
+
+                Some(BodyWeight {
+                    measured_at,
+                    weight: unit.to_kilograms(weight),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Picks the body weight for a workout started at `time`: the latest measurement before it,
+/// otherwise the configured default, otherwise the earliest measurement after it.
+/// `body_weights` has to be sorted by `measured_at`.
+pub(crate) fn body_weight_at(
+    body_weights: &[BodyWeight],
+    default: Option<f32>,
+    time: Option<OffsetDateTime>,
+) -> Option<f32> {
+    let Some(time) = time else {
+        return default;
+    };
+
+    let measured_before = body_weights.partition_point(|entry| entry.measured_at <= time);
+    match measured_before {
+        0 => default.or_else(|| body_weights.first().map(|entry| entry.weight)),
+        n => Some(body_weights[n - 1].weight),
+    }
+}
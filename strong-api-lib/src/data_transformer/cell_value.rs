@@ -0,0 +1,79 @@
+//! Lenient parsers for the string values stored in `Cell`s.
+//!
+//! The backend stores every cell value as a string, and depending on the app version and
+//! the device locale these strings come in slightly different shapes: `"62.5"` and `"62,5"`,
+//! `" 10 "`, `"12.0"` for reps or `"1:30"` for durations. The parsers here accept all of them.
+
+/// Parses a decimal value such as a weight, a distance or an RPE.
+///
+/// Surrounding whitespace is ignored and both `.` and `,` are accepted as decimal separator.
+/// If both appear, the one that comes last is the decimal separator and the other one is
+/// treated as a thousands separator (`"1,234.5"` and `"1.234,5"` both parse as `1234.5`).
+/// Scientific notation (`"1e2"`) is accepted, non-finite values are rejected.
+pub fn parse_decimal(value: &str) -> Option<f32> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    let normalized = match (value.rfind('.'), value.rfind(',')) {
+        (Some(dot), Some(comma)) if comma > dot => value.replace('.', "").replace(',', "."),
+        (Some(_), Some(_)) => value.replace(',', ""),
+        (None, Some(_)) if value.matches(',').count() > 1 => value.replace(',', ""),
+        (None, Some(_)) => value.replace(',', "."),
+        _ => value.to_string(),
+    };
+
+    normalized
+        .parse::<f32>()
+        .ok()
+        .filter(|parsed| parsed.is_finite())
+}
+
+/// Parses a whole, non-negative number such as reps.
+///
+/// Accepts everything `parse_decimal` accepts as long as it has no fractional part,
+/// so `"12.0"`, `"12,0"` and `"1.2e1"` all parse as `12`, while `"12.5"` is rejected.
+pub fn parse_count(value: &str) -> Option<u32> {
+    let parsed = parse_decimal(value)?;
+    if parsed < 0.0 || parsed.fract() != 0.0 || parsed > u32::MAX as f32 {
+        return None;
+    }
+    Some(parsed as u32)
+}
+
+/// Parses a duration into whole seconds.
+///
+/// Accepts plain seconds (`"90"`, `"90.4"`) as well as clock notation, either `mm:ss` or
+/// `hh:mm:ss`. In clock notation every part but the first has to be below 60.
+/// Fractional seconds are rounded to the nearest second.
+pub fn parse_duration(value: &str) -> Option<u32> {
+    let value = value.trim();
+    if !value.contains(':') {
+        let seconds = parse_decimal(value)?;
+        return (seconds >= 0.0).then(|| seconds.round() as u32);
+    }
+
+    let parts: Vec<&str> = value.split(':').map(str::trim).collect();
+    if parts.len() > 3 {
+        return None;
+    }
+
+    let (last, leading) = parts.split_last()?;
+    let seconds = parse_decimal(last)?;
+    if !(0.0..60.0).contains(&seconds) {
+        return None;
+    }
+
+    let mut total = 0u32;
+    for (index, part) in leading.iter().enumerate() {
+        let number: u32 = part.parse().ok()?;
+        // Minutes are bounded by the hours in front of them, the first part is unbounded.
+        if index > 0 && number >= 60 {
+            return None;
+        }
+        total = total.checked_mul(60)?.checked_add(number)?;
+    }
+
+    total.checked_mul(60)?.checked_add(seconds.round() as u32)
+}
@@ -0,0 +1,145 @@
+//! Writes `Workout`s as CSV in the layout of Strong's "Export data", the inverse of `csv_import`.
+//!
+//! Like the app, a row is written for every completed set, with the workout's columns repeated
+//! on each row. Weights and distances are written in the unit of their set, dates in the
+//! workout's timezone, and exercise notes as a `Note` row after the exercise's sets. A workout
+//! with notes but no rows gets a single row without an exercise to keep them.
+
+use super::Workout;
+use std::io::Write;
+use time::macros::format_description;
+
+/// The header of Strong's export.
+pub const HEADER: [&str; 12] = [
+    "Date",
+    "Workout Name",
+    "Duration",
+    "Exercise Name",
+    "Set Order",
+    "Weight",
+    "Reps",
+    "Distance",
+    "Seconds",
+    "Notes",
+    "Workout Notes",
+    "RPE",
+];
+
+/// Writes the workouts with a header. Workouts without a start date are left out, as are
+/// incomplete sets, which the app doesn't export either. Returns the number of workouts that
+/// got at least one row.
+pub fn workouts_to_csv(workouts: &[Workout], writer: impl Write) -> Result<usize, csv::Error> {
+    let mut writer = csv::Writer::from_writer(writer);
+    writer.write_record(HEADER)?;
+    let mut written = 0;
+    for workout in workouts {
+        let Some(start) = workout.local_start_time() else {
+            continue;
+        };
+        let date = start
+            .format(format_description!(
+                "[year]-[month]-[day] [hour]:[minute]:[second]"
+            ))
+            .unwrap_or_default();
+        let duration = workout
+            .start_time()
+            .zip(workout.end_time())
+            .map(|(start, end)| format_duration((end - start).whole_seconds()))
+            .unwrap_or_default();
+        let workout_notes = workout.notes.as_deref().unwrap_or_default();
+
+        let has_rows = workout.exercises.iter().any(|exercise| {
+            exercise.notes.is_some() || exercise.sets.iter().any(|set| set.is_completed)
+        });
+        if !has_rows && workout.notes.is_none() {
+            continue;
+        }
+
+        let mut row = |exercise_name: &str,
+                       set_order: &str,
+                       values: [String; 4],
+                       notes: &str,
+                       rpe: String| {
+            let [weight, reps, distance, seconds] = values;
+            writer.write_record([
+                date.as_str(),
+                workout.name.as_str(),
+                duration.as_str(),
+                exercise_name,
+                set_order,
+                &weight,
+                &reps,
+                &distance,
+                &seconds,
+                notes,
+                workout_notes,
+                &rpe,
+            ])
+        };
+        for exercise in &workout.exercises {
+            for (index, set) in exercise
+                .sets
+                .iter()
+                .filter(|set| set.is_completed)
+                .enumerate()
+            {
+                // The app writes 0 for values that weren't logged, except for the RPE.
+                let weight = set
+                    .weight
+                    .map_or(0.0, |weight| set.weight_unit.from_kilograms(weight));
+                let distance = set
+                    .distance
+                    .map_or(0.0, |distance| set.distance_unit.from_kilometers(distance));
+                row(
+                    &exercise.name,
+                    &(index + 1).to_string(),
+                    [
+                        format_number(weight),
+                        set.reps.to_string(),
+                        format_number(distance),
+                        set.duration.unwrap_or(0).to_string(),
+                    ],
+                    set.notes.as_deref().unwrap_or_default(),
+                    set.rpe.map(format_number).unwrap_or_default(),
+                )?;
+            }
+            if let Some(notes) = &exercise.notes {
+                row(
+                    &exercise.name,
+                    "Note",
+                    Default::default(),
+                    notes,
+                    String::new(),
+                )?;
+            }
+        }
+        // Keep the notes of a workout without sets in a row without an exercise.
+        if !has_rows {
+            row("", "", Default::default(), "", String::new())?;
+        }
+        written += 1;
+    }
+    writer.flush()?;
+    Ok(written)
+}
+
+/// Formats a duration like the app, e.g. `1h 5m`, `45m` or `30s`.
+fn format_duration(seconds: i64) -> String {
+    let (hours, minutes) = (seconds / 3600, seconds % 3600 / 60);
+    match (hours, minutes) {
+        (0, 0) => format!("{}s", seconds.max(0)),
+        (0, _) => format!("{minutes}m"),
+        _ => format!("{hours}h {minutes}m"),
+    }
+}
+
+/// Formats a weight, distance or RPE with at most two decimals, e.g. `100.0` or `22.68`.
+fn format_number(value: f32) -> String {
+    let text = format!("{:.2}", (value * 100.0).round() / 100.0);
+    let text = text.trim_end_matches('0');
+    // Keep one decimal like the app, `100.` becomes `100.0`.
+    match text.strip_suffix('.') {
+        Some(whole) => format!("{whole}.0"),
+        None => text.to_string(),
+    }
+}
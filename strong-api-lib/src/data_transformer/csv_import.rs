@@ -0,0 +1,581 @@
+//! Imports the CSV of Strong's "Export data" as `Workout`s, an offline alternative to the API.
+//!
+//! Every row is a set, rows of one workout share its date and name, and rows of one exercise
+//! follow each other. Columns are found by their header, so older exports with `Weight Unit`,
+//! `Distance Unit` or `Workout Duration` columns and `;` as delimiter work too. Units are taken
+//! from those columns, from headers like `Weight (kg)`, or else from the preferences like for
+//! logs. `Rest Timer` rows are skipped and `Note` rows become the notes of their exercise.
+//!
+//! The export has no ids, so workouts, exercises and sets get UUIDs derived from their date,
+//! name and position. Importing the same export twice gives the same ids, and saving them again
+//! replaces the rows saved before instead of adding new ones. Workouts sharing date and name,
+//! e.g. one started from the other within the same second, are numbered to keep them apart.
+
+use super::diagnostics::{Diagnostics, MissingBodyWeight, UnmatchedExercise, UnparsableValue};
+use super::{DataTransformer, Exercise, LoadType, Set, Workout, body_weight, cell_value};
+use crate::models::measurement::Measurement;
+use crate::models::preferences::{DistanceUnit, WeightUnit};
+use csv::StringRecord;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+use time::format_description::well_known::Rfc3339;
+use time::macros::format_description;
+use time::{Duration, OffsetDateTime, PrimitiveDateTime, UtcOffset};
+use time_tz::{PrimitiveDateTimeExt, timezones};
+use uuid::Uuid;
+
+/// Namespace of the UUIDs of imported workouts, exercises and sets.
+pub const CSV_NAMESPACE: Uuid = Uuid::from_u128(0x6f1c2b9e_8d4a_4e57_9c3b_51d07a2ef843);
+
+/// The export couldn't be read.
+#[derive(Debug)]
+pub enum CsvImportError {
+    Io(std::io::Error),
+    Csv(csv::Error),
+    /// A column every export has, like `Date` or `Exercise Name`, is missing.
+    MissingColumn(&'static str),
+    InvalidDate {
+        line: u64,
+        value: String,
+    },
+}
+
+impl fmt::Display for CsvImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvImportError::Io(error) => write!(f, "cannot read CSV: {}", error),
+            CsvImportError::Csv(error) => write!(f, "invalid CSV: {}", error),
+            CsvImportError::MissingColumn(column) => write!(f, "CSV has no '{}' column", column),
+            CsvImportError::InvalidDate { line, value } => {
+                write!(f, "invalid date '{}' on line {}", value, line)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CsvImportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CsvImportError::Io(error) => Some(error),
+            CsvImportError::Csv(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for CsvImportError {
+    fn from(error: std::io::Error) -> Self {
+        CsvImportError::Io(error)
+    }
+}
+
+impl From<csv::Error> for CsvImportError {
+    fn from(error: csv::Error) -> Self {
+        CsvImportError::Csv(error)
+    }
+}
+
+/// Positions of the columns of an export and the units their headers name.
+#[derive(Default)]
+struct Columns {
+    date: usize,
+    workout_name: usize,
+    exercise_name: usize,
+    set_order: usize,
+    duration: Option<usize>,
+    weight: Option<usize>,
+    weight_unit: Option<usize>,
+    reps: Option<usize>,
+    distance: Option<usize>,
+    distance_unit: Option<usize>,
+    seconds: Option<usize>,
+    notes: Option<usize>,
+    workout_notes: Option<usize>,
+    rpe: Option<usize>,
+    /// From a header like `Weight (lbs)`.
+    header_weight_unit: Option<WeightUnit>,
+    /// From a header like `Distance (mi)`.
+    header_distance_unit: Option<DistanceUnit>,
+}
+
+impl Columns {
+    fn from_headers(headers: &StringRecord) -> Result<Self, CsvImportError> {
+        let mut columns = Columns::default();
+        let (mut date, mut workout_name, mut exercise_name, mut set_order) =
+            (None, None, None, None);
+        for (index, header) in headers.iter().enumerate() {
+            let header = header.trim().to_lowercase();
+            let (name, unit) = match header.split_once('(') {
+                Some((name, unit)) => (name.trim(), Some(unit.trim_end_matches(')').trim())),
+                None => (header.as_str(), None),
+            };
+            match name {
+                "date" => date = Some(index),
+                "workout name" => workout_name = Some(index),
+                "exercise name" => exercise_name = Some(index),
+                "set order" => set_order = Some(index),
+                "duration" | "workout duration" => columns.duration = Some(index),
+                "weight" => {
+                    columns.weight = Some(index);
+                    columns.header_weight_unit = unit.and_then(parse_weight_unit);
+                }
+                "weight unit" => columns.weight_unit = Some(index),
+                "reps" => columns.reps = Some(index),
+                "distance" => {
+                    columns.distance = Some(index);
+                    columns.header_distance_unit = unit.and_then(parse_distance_unit);
+                }
+                "distance unit" => columns.distance_unit = Some(index),
+                "seconds" => columns.seconds = Some(index),
+                "notes" => columns.notes = Some(index),
+                "workout notes" => columns.workout_notes = Some(index),
+                "rpe" => columns.rpe = Some(index),
+                _ => {}
+            }
+        }
+        columns.date = date.ok_or(CsvImportError::MissingColumn("Date"))?;
+        columns.workout_name = workout_name.ok_or(CsvImportError::MissingColumn("Workout Name"))?;
+        columns.exercise_name =
+            exercise_name.ok_or(CsvImportError::MissingColumn("Exercise Name"))?;
+        columns.set_order = set_order.ok_or(CsvImportError::MissingColumn("Set Order"))?;
+        Ok(columns)
+    }
+}
+
+/// A row of the export with its line number.
+struct Row {
+    line: u64,
+    record: StringRecord,
+}
+
+impl Row {
+    /// The trimmed value of a column, `None` if the column is missing or the value blank.
+    fn get(&self, column: Option<usize>) -> Option<&str> {
+        self.record
+            .get(column?)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    }
+
+    fn text(&self, column: usize) -> &str {
+        self.get(Some(column)).unwrap_or_default()
+    }
+}
+
+impl DataTransformer {
+    /// Reads the CSV of Strong's "Export data". Its dates have no timezone, they are taken to
+    /// be in the IANA `timezone`, e.g. `Europe/Berlin`, or in UTC if it is `None` or unknown.
+    pub fn workouts_from_csv(
+        &self,
+        csv: impl Read,
+        timezone: Option<&str>,
+    ) -> Result<Vec<Workout>, CsvImportError> {
+        self.workouts_from_csv_with_diagnostics(csv, timezone)
+            .map(|(workouts, _)| workouts)
+    }
+
+    /// Same as `workouts_from_csv`, but additionally returns the `Diagnostics`. Exercises
+    /// missing from the measurements are reported with their name, and values that can't be
+    /// parsed with the id of their set or workout and the name of their column.
+    pub fn workouts_from_csv_with_diagnostics(
+        &self,
+        mut csv: impl Read,
+        timezone: Option<&str>,
+    ) -> Result<(Vec<Workout>, Diagnostics), CsvImportError> {
+        let mut text = String::new();
+        csv.read_to_string(&mut text)?;
+        let text = text.trim_start_matches('\u{feff}');
+        let first_line = text.lines().next().unwrap_or_default();
+        let delimiter = if first_line.matches(';').count() > first_line.matches(',').count() {
+            b';'
+        } else {
+            b','
+        };
+
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .flexible(true)
+            .from_reader(text.as_bytes());
+        let columns = Columns::from_headers(reader.headers()?)?;
+
+        // Consecutive rows with the same date and workout name make one workout.
+        let mut groups: Vec<Vec<Row>> = Vec::new();
+        for record in reader.records() {
+            let record = record?;
+            let row = Row {
+                line: record.position().map_or(0, |position| position.line()),
+                record,
+            };
+            let same_workout = groups
+                .last()
+                .and_then(|group| group.last())
+                .is_some_and(|last| {
+                    last.text(columns.date) == row.text(columns.date)
+                        && last.text(columns.workout_name) == row.text(columns.workout_name)
+                });
+            match groups.last_mut() {
+                Some(group) if same_workout => group.push(row),
+                _ => groups.push(vec![row]),
+            }
+        }
+
+        let measurements = self.measurements_by_name();
+        let mut diagnostics = Diagnostics::default();
+        // How often each date and workout name was seen, to tell apart workouts sharing them.
+        let mut occurrences: HashMap<(&str, &str), usize> = HashMap::new();
+        let workouts = groups
+            .iter()
+            .map(|rows| {
+                let key = (
+                    rows[0].text(columns.date),
+                    rows[0].text(columns.workout_name),
+                );
+                let occurrence = occurrences.entry(key).or_default();
+                *occurrence += 1;
+                self.rows_to_workout(
+                    rows,
+                    *occurrence,
+                    &columns,
+                    &measurements,
+                    timezone,
+                    &mut diagnostics,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((workouts, diagnostics))
+    }
+
+    /// Measurements by their name in any locale, lowercase.
+    fn measurements_by_name(&self) -> HashMap<String, &Measurement> {
+        let mut by_name = HashMap::new();
+        for measurement in self
+            .measurements_response
+            .iter()
+            .flat_map(|measurements| &measurements.embedded.measurements)
+        {
+            for name in measurement.name.values() {
+                by_name.entry(name.to_lowercase()).or_insert(measurement);
+            }
+        }
+        by_name
+    }
+
+    /// `occurrence` counts the workouts with the same date and name so far, starting at 1.
+    fn rows_to_workout(
+        &self,
+        rows: &[Row],
+        occurrence: usize,
+        columns: &Columns,
+        measurements: &HashMap<String, &Measurement>,
+        timezone: Option<&str>,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<Workout, CsvImportError> {
+        let first = &rows[0];
+        let date = first.text(columns.date);
+        let name = first.text(columns.workout_name);
+        let start =
+            parse_local_date(date, timezone).ok_or_else(|| CsvImportError::InvalidDate {
+                line: first.line,
+                value: date.to_string(),
+            })?;
+        // Only repeated workouts get their occurrence in the id, so the ids of the others don't
+        // change when workouts are added to or removed from the export.
+        let key = match occurrence {
+            1 => format!("{date}\n{name}"),
+            occurrence => format!("{date}\n{name}\n{occurrence}"),
+        };
+        let id = Uuid::new_v5(&CSV_NAMESPACE, key.as_bytes()).to_string();
+
+        let duration = rows
+            .iter()
+            .find_map(|row| row.get(columns.duration))
+            .and_then(|value| {
+                let duration = parse_workout_duration(value);
+                if duration.is_none() {
+                    diagnostics.unparsable_values.push(UnparsableValue {
+                        cell_id: id.clone(),
+                        cell_type: "Duration".to_string(),
+                        value: value.to_string(),
+                    });
+                }
+                duration
+            });
+        let body_weight =
+            body_weight::body_weight_at(&self.body_weights, self.default_body_weight, Some(start));
+
+        // Consecutive rows of one exercise make one exercise, unless the set order starts over.
+        let mut groups: Vec<Vec<&Row>> = Vec::new();
+        let mut last_set_order: Option<u32> = None;
+        for row in rows {
+            let set_order = row.text(columns.set_order);
+            // Rows without an exercise and set only carry the notes of a workout without sets.
+            if set_order.eq_ignore_ascii_case("rest timer")
+                || (set_order.is_empty() && row.text(columns.exercise_name).is_empty())
+            {
+                continue;
+            }
+            let number = set_order.parse::<u32>().ok();
+            let same_exercise = groups
+                .last()
+                .and_then(|group| group.last())
+                .is_some_and(|last| {
+                    last.text(columns.exercise_name) == row.text(columns.exercise_name)
+                        && number
+                            .zip(last_set_order)
+                            .is_none_or(|(number, last)| number > last)
+                });
+            match groups.last_mut() {
+                Some(group) if same_exercise => group.push(row),
+                _ => groups.push(vec![row]),
+            }
+            last_set_order = number.or(last_set_order.filter(|_| same_exercise));
+        }
+
+        let exercises = groups
+            .iter()
+            .enumerate()
+            .map(|(index, rows)| {
+                let exercise_id =
+                    Uuid::new_v5(&CSV_NAMESPACE, format!("{id}\n{index}").as_bytes()).to_string();
+                self.rows_to_exercise(
+                    &id,
+                    exercise_id,
+                    rows,
+                    columns,
+                    measurements,
+                    body_weight,
+                    diagnostics,
+                )
+            })
+            .collect();
+
+        let format = |time: OffsetDateTime| time.to_offset(UtcOffset::UTC).format(&Rfc3339).ok();
+        Ok(Workout {
+            id,
+            name: name.to_string(),
+            timezone: timezone
+                .filter(|timezone| timezones::get_by_name(timezone).is_some())
+                .map(str::to_string),
+            start_date: format(start),
+            end_date: duration
+                .and_then(|duration| format(start + Duration::seconds(i64::from(duration)))),
+            body_weight,
+            template_id: None,
+            notes: rows
+                .iter()
+                .find_map(|row| row.get(columns.workout_notes))
+                .map(str::to_string),
+            exercises,
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn rows_to_exercise(
+        &self,
+        workout_id: &str,
+        id: String,
+        rows: &[&Row],
+        columns: &Columns,
+        measurements: &HashMap<String, &Measurement>,
+        body_weight: Option<f32>,
+        diagnostics: &mut Diagnostics,
+    ) -> Exercise {
+        let csv_name = rows[0].text(columns.exercise_name);
+        let measurement = measurements.get(&csv_name.to_lowercase()).copied();
+        let measurement_id =
+            measurement.map_or_else(String::new, |measurement| measurement.id.clone());
+        let (name, name_locale) = measurement
+            .and_then(|measurement| measurement.name.localized(&self.locales))
+            .map_or_else(
+                || (csv_name.to_string(), String::new()),
+                |(name, locale)| (name.to_string(), locale.to_string()),
+            );
+        if measurement.is_none() {
+            diagnostics.unmatched_exercises.push(UnmatchedExercise {
+                log_id: workout_id.to_string(),
+                exercise_id: id.clone(),
+                name: csv_name.to_string(),
+            });
+        }
+        // The weight cell the measurement is logged with, if it's known.
+        let weight_cell_type = measurement.map(|measurement| {
+            measurement
+                .cell_type_configs
+                .iter()
+                .find(|config| LoadType::from_cell_type(&config.cell_type).is_some())
+                .map(|config| config.cell_type.clone())
+        });
+
+        let mut notes = Vec::new();
+        let mut sets = Vec::new();
+        for row in rows {
+            if row.text(columns.set_order).eq_ignore_ascii_case("note") {
+                notes.extend(row.get(columns.notes));
+                continue;
+            }
+            let set_id = Uuid::new_v5(&CSV_NAMESPACE, format!("{id}\n{}", sets.len()).as_bytes())
+                .to_string();
+            let mut parse =
+                |column: Option<usize>, header: &str, parse: fn(&str) -> Option<f32>| {
+                    let value = row.get(column)?;
+                    let parsed = parse(value);
+                    if parsed.is_none() {
+                        diagnostics.unparsable_values.push(UnparsableValue {
+                            cell_id: set_id.clone(),
+                            cell_type: header.to_string(),
+                            value: value.to_string(),
+                        });
+                    }
+                    parsed
+                };
+            let logged_weight = parse(columns.weight, "Weight", cell_value::parse_decimal);
+            let reps = parse(columns.reps, "Reps", |value| {
+                cell_value::parse_count(value).map(|reps| reps as f32)
+            });
+            let rpe = parse(columns.rpe, "RPE", cell_value::parse_decimal);
+            let distance = parse(columns.distance, "Distance", cell_value::parse_decimal);
+            let seconds = parse(columns.seconds, "Seconds", |value| {
+                cell_value::parse_duration(value).map(|seconds| seconds as f32)
+            });
+
+            // Exports write 0 for values that weren't logged.
+            let (load_type, set_weight_cell_type) = match &weight_cell_type {
+                Some(Some(cell_type)) => (
+                    LoadType::from_cell_type(cell_type).unwrap_or_default(),
+                    Some(cell_type.clone()),
+                ),
+                Some(None) => (LoadType::Unweighted, None),
+                None if logged_weight.is_some_and(|weight| weight > 0.0) => {
+                    (LoadType::External, None)
+                }
+                None => (LoadType::Unweighted, None),
+            };
+            let weight_unit = row
+                .get(columns.weight_unit)
+                .and_then(parse_weight_unit)
+                .or(columns.header_weight_unit)
+                .unwrap_or_else(|| self.weight_unit(load_type, &measurement_id));
+            let weight = match load_type {
+                LoadType::Unweighted => None,
+                _ => logged_weight.map(|weight| weight_unit.to_kilograms(weight)),
+            };
+            let (bar_weight, total_load, effective_load) =
+                self.loads(load_type, &measurement_id, weight, body_weight);
+            if matches!(
+                load_type,
+                LoadType::WeightedBodyweight | LoadType::AssistedBodyweight
+            ) && effective_load.is_none()
+            {
+                diagnostics.missing_body_weights.push(MissingBodyWeight {
+                    log_id: workout_id.to_string(),
+                    set_id: set_id.clone(),
+                });
+            }
+            let distance_unit = row
+                .get(columns.distance_unit)
+                .and_then(parse_distance_unit)
+                .or(columns.header_distance_unit)
+                .unwrap_or_else(|| {
+                    self.preferences
+                        .distance_unit(&self.user_id, &measurement_id)
+                });
+
+            sets.push(Set {
+                id: set_id,
+                weight,
+                weight_unit,
+                load_type,
+                weight_cell_type: set_weight_cell_type,
+                bar_weight,
+                total_load,
+                effective_load,
+                reps: reps.map_or(0, |reps| reps as u32),
+                rpe,
+                duration: seconds
+                    .filter(|seconds| *seconds > 0.0)
+                    .map(|seconds| seconds as u32),
+                distance: distance
+                    .filter(|distance| *distance > 0.0)
+                    .map(|distance| distance_unit.to_kilometers(distance)),
+                distance_unit,
+                // Only finished workouts are exported, with the sets that were done.
+                is_completed: true,
+                notes: row.get(columns.notes).map(str::to_string),
+            });
+        }
+
+        let canonical_name = self.exercise_aliases.canonical_name(&measurement_id, &name);
+        Exercise {
+            id,
+            measurement_id,
+            name,
+            name_locale,
+            canonical_name,
+            notes: (!notes.is_empty()).then(|| notes.join("\n")),
+            sets,
+        }
+    }
+}
+
+fn parse_weight_unit(unit: &str) -> Option<WeightUnit> {
+    match unit.trim().to_lowercase().as_str() {
+        "kg" | "kgs" => Some(WeightUnit::Kilograms),
+        "lb" | "lbs" => Some(WeightUnit::Pounds),
+        _ => None,
+    }
+}
+
+fn parse_distance_unit(unit: &str) -> Option<DistanceUnit> {
+    match unit.trim().to_lowercase().as_str() {
+        "km" => Some(DistanceUnit::Kilometers),
+        "mi" | "miles" => Some(DistanceUnit::Miles),
+        "m" => Some(DistanceUnit::Meters),
+        _ => None,
+    }
+}
+
+/// Parses a local date like `2024-11-20 18:05:14` in `timezone`.
+fn parse_local_date(value: &str, timezone: Option<&str>) -> Option<OffsetDateTime> {
+    let local = PrimitiveDateTime::parse(
+        value,
+        format_description!("[year]-[month]-[day] [hour]:[minute]:[second]"),
+    )
+    .or_else(|_| {
+        PrimitiveDateTime::parse(
+            value,
+            format_description!("[year]-[month]-[day] [hour]:[minute]"),
+        )
+    })
+    .ok()?;
+    Some(match timezone.and_then(timezones::get_by_name) {
+        // Times skipped by a daylight saving change are taken as UTC.
+        Some(timezone) => local
+            .assume_timezone(timezone)
+            .take_first()
+            .unwrap_or_else(|| local.assume_timezone_utc(timezone)),
+        None => local.assume_utc(),
+    })
+}
+
+/// Parses a workout duration like `1h 5m`, `45m` or `50s`, or plain seconds and clock notation.
+fn parse_workout_duration(value: &str) -> Option<u32> {
+    if !value.contains(['h', 'm', 's']) {
+        return cell_value::parse_duration(value);
+    }
+    let mut seconds = 0u32;
+    for part in value.split_whitespace() {
+        let split = part.find(|character: char| character.is_alphabetic())?;
+        let (number, unit) = part.split_at(split);
+        let number: u32 = number.parse().ok()?;
+        let unit = match unit {
+            "h" => 3600,
+            "m" | "min" => 60,
+            "s" => 1,
+            _ => return None,
+        };
+        // Durations too long for a `u32` are reported like any other unparsable value.
+        seconds = seconds.checked_add(number.checked_mul(unit)?)?;
+    }
+    Some(seconds)
+}
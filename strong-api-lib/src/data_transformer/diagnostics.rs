@@ -0,0 +1,138 @@
+use serde::Serialize;
+use std::fmt;
+
+/// A cell set group whose measurement link could not be resolved to a known measurement.
+/// `measurement_id` is empty if the group had no measurement link at all.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnresolvedMeasurement {
+    pub log_id: String,
+    pub cell_set_group_id: String,
+    pub measurement_id: String,
+}
+
+/// An exercise of a CSV export whose name matches no measurement, in any locale.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnmatchedExercise {
+    pub log_id: String,
+    pub exercise_id: String,
+    /// The exercise name as written in the export.
+    pub name: String,
+}
+
+/// A cell whose value could not be parsed into the type expected for its cell type.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnparsableValue {
+    pub cell_id: String,
+    pub cell_type: String,
+    pub value: String,
+}
+
+/// A cell with a cell type the transformer doesn't know how to interpret.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnknownCellType {
+    pub cell_id: String,
+    pub cell_type: String,
+}
+
+/// An exercise's cell set group that didn't produce a single set, e.g. because it only contained
+/// rest timers. Groups without a measurement are the workout's notes and never reported.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EmptyGroup {
+    pub log_id: String,
+    pub cell_set_group_id: String,
+}
+
+/// A bodyweight set whose effective load is unknown because no body weight was available.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MissingBodyWeight {
+    pub log_id: String,
+    pub set_id: String,
+}
+
+/// Collects everything the `DataTransformer` had to drop or default while transforming logs.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct Diagnostics {
+    pub unresolved_measurements: Vec<UnresolvedMeasurement>,
+    pub unmatched_exercises: Vec<UnmatchedExercise>,
+    pub unparsable_values: Vec<UnparsableValue>,
+    pub unknown_cell_types: Vec<UnknownCellType>,
+    pub empty_groups: Vec<EmptyGroup>,
+    pub missing_body_weights: Vec<MissingBodyWeight>,
+}
+
+impl Diagnostics {
+    /// Returns `true` if the transformation didn't run into any issue.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Total number of collected issues.
+    pub fn len(&self) -> usize {
+        self.unresolved_measurements.len()
+            + self.unmatched_exercises.len()
+            + self.unparsable_values.len()
+            + self.unknown_cell_types.len()
+            + self.empty_groups.len()
+            + self.missing_body_weights.len()
+    }
+
+    /// Appends all issues of `other` to this collector.
+    pub fn merge(&mut self, other: Diagnostics) {
+        self.unresolved_measurements
+            .extend(other.unresolved_measurements);
+        self.unmatched_exercises.extend(other.unmatched_exercises);
+        self.unparsable_values.extend(other.unparsable_values);
+        self.unknown_cell_types.extend(other.unknown_cell_types);
+        self.empty_groups.extend(other.empty_groups);
+        self.missing_body_weights.extend(other.missing_body_weights);
+    }
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Diagnostics: {} issue(s)", self.len())?;
+        for unresolved in &self.unresolved_measurements {
+            write!(
+                f,
+                "\n  unresolved measurement '{}' (log {}, group {})",
+                unresolved.measurement_id, unresolved.log_id, unresolved.cell_set_group_id
+            )?;
+        }
+        for unmatched in &self.unmatched_exercises {
+            write!(
+                f,
+                "\n  unmatched exercise '{}' (log {}, exercise {})",
+                unmatched.name, unmatched.log_id, unmatched.exercise_id
+            )?;
+        }
+        for unparsable in &self.unparsable_values {
+            write!(
+                f,
+                "\n  unparsable {} value '{}' in cell {}",
+                unparsable.cell_type, unparsable.value, unparsable.cell_id
+            )?;
+        }
+        for unknown in &self.unknown_cell_types {
+            write!(
+                f,
+                "\n  unknown cell type '{}' in cell {}",
+                unknown.cell_type, unknown.cell_id
+            )?;
+        }
+        for empty in &self.empty_groups {
+            write!(
+                f,
+                "\n  empty group {} in log {}",
+                empty.cell_set_group_id, empty.log_id
+            )?;
+        }
+        for missing in &self.missing_body_weights {
+            write!(
+                f,
+                "\n  missing body weight for set {} in log {}",
+                missing.set_id, missing.log_id
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,614 @@
+pub mod aliases;
+pub mod body_weight;
+pub mod cell_value;
+pub mod csv_export;
+pub mod csv_import;
+pub mod diagnostics;
+pub mod reverse;
+pub mod stream;
+
+use crate::models::measurement::{Measurement, MeasurementsResponse};
+use crate::models::preferences::{CustomBarType, DistanceUnit, Preferences, WeightUnit};
+use crate::models::workout::{Cell, CellSet, CellSetGroup, CellSetGroupLinks, Log};
+use aliases::ExerciseAliases;
+use body_weight::BodyWeight;
+use diagnostics::{
+    Diagnostics, EmptyGroup, MissingBodyWeight, UnknownCellType, UnparsableValue,
+    UnresolvedMeasurement,
+};
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use stream::Workouts;
+use time::format_description::well_known::Rfc3339;
+use time::{Date, OffsetDateTime};
+use time_tz::{OffsetDateTimeExt, timezones};
+
+/// Measurements by id, borrowed from the transformer's measurements response.
+type MeasurementLookup<'a> = HashMap<&'a str, &'a Measurement>;
+
+/// Cell types the transformer knows about. Anything else is reported as an unknown cell type.
+const KNOWN_CELL_TYPES: &[&str] = &[
+    "REPS",
+    "RPE",
+    "OTHER_WEIGHT",
+    "DUMBBELL_WEIGHT",
+    "BARBELL_WEIGHT",
+    "WEIGHTED_BODYWEIGHT",
+    "ASSISTED_BODYWEIGHT",
+    "DURATION",
+    "DISTANCE",
+    "REST_TIMER",
+    "NOTE",
+];
+
+/// How the logged weight of a set relates to the load the athlete actually moved.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LoadType {
+    /// No weight cell, e.g. cardio or plain bodyweight exercises.
+    #[default]
+    Unweighted,
+    /// Barbell, dumbbell or machine weight, the logged weight is the load.
+    External,
+    /// Weight added on top of the body weight, e.g. a weighted pull-up.
+    WeightedBodyweight,
+    /// Assistance taken off the body weight, e.g. a machine assisted dip.
+    AssistedBodyweight,
+}
+
+impl LoadType {
+    pub(crate) fn from_cell_type(cell_type: &str) -> Option<LoadType> {
+        match cell_type {
+            "OTHER_WEIGHT" | "DUMBBELL_WEIGHT" | "BARBELL_WEIGHT" => Some(LoadType::External),
+            "WEIGHTED_BODYWEIGHT" => Some(LoadType::WeightedBodyweight),
+            "ASSISTED_BODYWEIGHT" => Some(LoadType::AssistedBodyweight),
+            _ => None,
+        }
+    }
+
+    /// Computes the load actually moved from the logged `weight` and the athlete's `body_weight`.
+    /// Bodyweight exercises without a known body weight have no effective load.
+    pub fn effective_load(self, weight: Option<f32>, body_weight: Option<f32>) -> Option<f32> {
+        match self {
+            LoadType::Unweighted => None,
+            LoadType::External => weight,
+            LoadType::WeightedBodyweight => Some(body_weight? + weight.unwrap_or(0.0)),
+            LoadType::AssistedBodyweight => Some((body_weight? - weight.unwrap_or(0.0)).max(0.0)),
+        }
+    }
+}
+
+impl std::fmt::Display for LoadType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let value = match self {
+            LoadType::Unweighted => "unweighted",
+            LoadType::External => "external",
+            LoadType::WeightedBodyweight => "weighted_bodyweight",
+            LoadType::AssistedBodyweight => "assisted_bodyweight",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+/// A single set. Weights are always in kilograms and distances in kilometers,
+/// the units the athlete logged them in are kept in `weight_unit` and `distance_unit`.
+/// `weight` is the number the athlete logged, `total_load` adds the bar and base weight to it
+/// unless the logged weight already includes them, and `effective_load` is what the athlete
+/// actually moved and is what tonnage should be computed from.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Set {
+    pub id: String,
+    pub weight: Option<f32>,
+    pub weight_unit: WeightUnit,
+    pub load_type: LoadType,
+    /// The cell type the weight was logged in, e.g. `BARBELL_WEIGHT`.
+    pub weight_cell_type: Option<String>,
+    /// Bar plus base weight configured for the exercise, `None` if nothing is configured.
+    pub bar_weight: Option<f32>,
+    pub total_load: Option<f32>,
+    pub effective_load: Option<f32>,
+    pub reps: u32,
+    pub rpe: Option<f32>,
+    /// Duration in seconds.
+    pub duration: Option<u32>,
+    pub distance: Option<f32>,
+    pub distance_unit: DistanceUnit,
+    pub is_completed: bool,
+    pub notes: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Exercise {
+    pub id: String,
+    /// Id of the measurement the exercise was logged as, empty if the log didn't link one.
+    pub measurement_id: String,
+    pub name: String,
+    /// Locale `name` was taken from, e.g. `de`, `en` or `custom`. Empty if the name is unknown.
+    pub name_locale: String,
+    /// The name the exercise is reported under, see `ExerciseAliases`. Equals `name` if the
+    /// exercise has no alias.
+    pub canonical_name: String,
+    /// Notes the athlete left on the exercise.
+    pub notes: Option<String>,
+    pub sets: Vec<Set>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Workout {
+    pub id: String,
+    pub name: String,
+    pub timezone: Option<String>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    /// The athlete's body weight in kilograms at the time of the workout, if known.
+    pub body_weight: Option<f32>,
+    /// Id of the template the workout was started from, taken from the log's `template` link.
+    pub template_id: Option<String>,
+    pub notes: Option<String>,
+    pub exercises: Vec<Exercise>,
+}
+
+impl Workout {
+    pub fn start_time(&self) -> Option<OffsetDateTime> {
+        parse_date(self.start_date.as_deref())
+    }
+
+    pub fn end_time(&self) -> Option<OffsetDateTime> {
+        parse_date(self.end_date.as_deref())
+    }
+
+    /// Start of the workout in its own timezone, or as logged if the timezone is unknown.
+    pub fn local_start_time(&self) -> Option<OffsetDateTime> {
+        Some(in_timezone(self.start_time()?, self.timezone.as_deref()))
+    }
+
+    /// Day the workout started on in its own timezone, see `local_start_time`.
+    pub fn local_date(&self) -> Option<Date> {
+        self.local_start_time().map(|start| start.date())
+    }
+}
+
+/// `time` in the IANA `timezone`, e.g. `Europe/Berlin`. Unchanged if the timezone is unknown.
+pub fn in_timezone(time: OffsetDateTime, timezone: Option<&str>) -> OffsetDateTime {
+    match timezone.and_then(timezones::get_by_name) {
+        Some(timezone) => time.to_timezone(timezone),
+        None => time,
+    }
+}
+
+fn parse_date(date: Option<&str>) -> Option<OffsetDateTime> {
+    OffsetDateTime::parse(date?, &Rfc3339).ok()
+}
+
+/// The values of the `NOTE` cells, one per line, `None` if there are none.
+fn notes_of<'a>(cell_sets: impl IntoIterator<Item = &'a CellSet>) -> Option<String> {
+    let notes: Vec<&str> = cell_sets
+        .into_iter()
+        .flat_map(|cell_set| &cell_set.cells)
+        .filter(|cell| cell.cell_type == "NOTE")
+        .filter_map(|cell| cell.value.as_deref())
+        .map(str::trim)
+        .filter(|note| !note.is_empty())
+        .collect();
+    (!notes.is_empty()).then(|| notes.join("\n"))
+}
+
+pub struct DataTransformer {
+    measurements_response: Option<MeasurementsResponse>,
+    user_id: String,
+    preferences: Preferences,
+    body_weights: Vec<BodyWeight>,
+    default_body_weight: Option<f32>,
+    custom_bar_types: Vec<CustomBarType>,
+    logged_weight_includes_bar: bool,
+    exercise_aliases: ExerciseAliases,
+    locales: Vec<String>,
+}
+
+impl Default for DataTransformer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DataTransformer {
+    pub fn new() -> Self {
+        Self {
+            measurements_response: None,
+            user_id: String::new(),
+            preferences: Preferences::default(),
+            body_weights: Vec::new(),
+            default_body_weight: None,
+            custom_bar_types: Vec::new(),
+            logged_weight_includes_bar: true,
+            exercise_aliases: ExerciseAliases::default(),
+            locales: Vec::new(),
+        }
+    }
+
+    pub fn with_measurements_response(
+        mut self,
+        with_measurements_response: MeasurementsResponse,
+    ) -> Self {
+        self.measurements_response = Some(with_measurements_response);
+        self
+    }
+
+    /// Sets the preferences of the user the logs belong to. They decide which unit the
+    /// logged values are in, without them every value is assumed to be metric.
+    pub fn with_preferences(mut self, user_id: &str, preferences: Preferences) -> Self {
+        self.user_id = user_id.to_string();
+        self.preferences = preferences;
+        self
+    }
+
+    /// Sets the measured body weights used for bodyweight exercises. Each workout uses the
+    /// latest body weight measured before it started.
+    pub fn with_body_weights(mut self, mut body_weights: Vec<BodyWeight>) -> Self {
+        body_weights.sort_by_key(|body_weight| body_weight.measured_at);
+        self.body_weights = body_weights;
+        self
+    }
+
+    /// Sets the body weight in kilograms used for workouts without an earlier measurement.
+    pub fn with_default_body_weight(mut self, body_weight: f32) -> Self {
+        self.default_body_weight = Some(body_weight);
+        self
+    }
+
+    /// Sets the user's custom bars, referenced by id from the `barType` preference.
+    pub fn with_custom_bar_types(mut self, custom_bar_types: Vec<CustomBarType>) -> Self {
+        self.custom_bar_types = custom_bar_types;
+        self
+    }
+
+    /// Sets whether the logged weight of external loads already includes the bar and base
+    /// weight, which is how Strong logs them. If `false`, they are added on top for `total_load`.
+    pub fn with_logged_weight_includes_bar(mut self, includes_bar: bool) -> Self {
+        self.logged_weight_includes_bar = includes_bar;
+        self
+    }
+
+    /// Sets the aliases used to merge exercises that are the same lift under one canonical name.
+    pub fn with_exercise_aliases(mut self, exercise_aliases: ExerciseAliases) -> Self {
+        self.exercise_aliases = exercise_aliases;
+        self
+    }
+
+    /// Sets the locales to take exercise and workout names from, most preferred first, e.g.
+    /// `["de-AT", "de"]`. Names missing in all of them fall back to English.
+    pub fn with_locales<S: Into<String>>(mut self, locales: impl IntoIterator<Item = S>) -> Self {
+        self.locales = locales.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn get_measurements_from_logs(
+        &self,
+        logs_option: &Option<Vec<Log>>,
+    ) -> Result<Vec<Workout>, serde_json::Error> {
+        self.get_workouts_with_diagnostics(logs_option)
+            .map(|(workouts, _)| workouts)
+    }
+
+    /// Same as `get_measurements_from_logs`, but additionally returns the `Diagnostics`
+    /// listing everything that couldn't be resolved or parsed along the way.
+    pub fn get_workouts_with_diagnostics(
+        &self,
+        logs_option: &Option<Vec<Log>>,
+    ) -> Result<(Vec<Workout>, Diagnostics), serde_json::Error> {
+        let mut workouts = self.workouts(logs_option.iter().flatten());
+        let collected = workouts.by_ref().collect();
+        Ok((collected, workouts.into_diagnostics()))
+    }
+
+    /// Transforms logs one at a time as the returned iterator is consumed. Accepts borrowed
+    /// logs as well as owned ones, e.g. from `stream::read_logs`, so a long history doesn't
+    /// have to be held in memory as a whole.
+    pub fn workouts<I>(&self, logs: I) -> Workouts<'_, I::IntoIter>
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Log>,
+    {
+        Workouts::new(self, self.create_measurement_lookup(), logs.into_iter())
+    }
+
+    fn create_measurement_lookup(&self) -> MeasurementLookup<'_> {
+        self.measurements_response
+            .iter()
+            .flat_map(|measurements| &measurements.embedded.measurements)
+            .map(|measurement| (measurement.id.as_str(), measurement))
+            .collect()
+    }
+
+    fn process_log_to_workout(
+        &self,
+        log: &Log,
+        lookup: &MeasurementLookup,
+        diagnostics: &mut Diagnostics,
+    ) -> Workout {
+        let body_weight = body_weight::body_weight_at(
+            &self.body_weights,
+            self.default_body_weight,
+            parse_date(log.start_date.as_deref()),
+        );
+
+        let exercises = log
+            .embedded
+            .cell_set_group
+            .iter()
+            .filter_map(|cell_set_group| {
+                self.process_cell_set_group_to_exercise(
+                    log,
+                    cell_set_group,
+                    lookup,
+                    body_weight,
+                    diagnostics,
+                )
+            })
+            .collect();
+
+        Workout {
+            id: log.id.clone(),
+            name: log
+                .name
+                .as_ref()
+                .and_then(|name| name.localized(&self.locales))
+                .map_or_else(|| "Unknown".to_string(), |(name, _)| name.to_string()),
+            timezone: log.timezone_id.clone(),
+            start_date: log.start_date.clone(),
+            end_date: log.end_date.clone(),
+            body_weight,
+            template_id: Self::get_template_id_from_link(&log.links),
+            // Workout notes are groups of their own, without a measurement.
+            notes: notes_of(
+                log.embedded
+                    .cell_set_group
+                    .iter()
+                    .filter(|cell_set_group| cell_set_group.links.measurement.is_none())
+                    .flat_map(|cell_set_group| &cell_set_group.cell_sets),
+            ),
+            exercises,
+        }
+    }
+
+    fn process_cell_set_group_to_exercise(
+        &self,
+        log: &Log,
+        cell_set_group: &CellSetGroup,
+        lookup: &MeasurementLookup,
+        body_weight: Option<f32>,
+        diagnostics: &mut Diagnostics,
+    ) -> Option<Exercise> {
+        let measurement_id = Self::get_measurement_id_from_link(&cell_set_group.links);
+
+        let sets: Vec<Set> = cell_set_group
+            .cell_sets
+            .iter()
+            .filter_map(|cell_set| {
+                self.process_cell_set_to_set(cell_set, &measurement_id, body_weight, diagnostics)
+            })
+            .collect();
+
+        for set in &sets {
+            let bodyweight_based = matches!(
+                set.load_type,
+                LoadType::WeightedBodyweight | LoadType::AssistedBodyweight
+            );
+            if bodyweight_based && set.effective_load.is_none() {
+                diagnostics.missing_body_weights.push(MissingBodyWeight {
+                    log_id: log.id.clone(),
+                    set_id: set.id.clone(),
+                });
+            }
+        }
+
+        if sets.is_empty() {
+            // Groups without a measurement hold the workout's notes, they aren't empty.
+            if cell_set_group.links.measurement.is_some() {
+                diagnostics.empty_groups.push(EmptyGroup {
+                    log_id: log.id.clone(),
+                    cell_set_group_id: cell_set_group.id.clone(),
+                });
+            }
+            return None;
+        }
+
+        // Get exercise name from measurements if available
+        let (name, name_locale) = match lookup.get(measurement_id.as_str()) {
+            Some(measurement) => measurement
+                .name
+                .localized(&self.locales)
+                .map(|(name, locale)| (name.to_string(), locale.to_string()))
+                .unwrap_or_else(|| ("Unknown".to_string(), String::new())),
+            None => {
+                diagnostics
+                    .unresolved_measurements
+                    .push(UnresolvedMeasurement {
+                        log_id: log.id.clone(),
+                        cell_set_group_id: cell_set_group.id.clone(),
+                        measurement_id: measurement_id.clone(),
+                    });
+                (String::new(), String::new())
+            }
+        };
+
+        let canonical_name = self.exercise_aliases.canonical_name(&measurement_id, &name);
+
+        Some(Exercise {
+            id: cell_set_group.id.clone(),
+            measurement_id,
+            name,
+            name_locale,
+            canonical_name,
+            // Exercise notes are cell sets of their own.
+            notes: notes_of(&cell_set_group.cell_sets),
+            sets,
+        })
+    }
+
+    fn process_cell_set_to_set(
+        &self,
+        cell_set: &CellSet,
+        measurement_id: &str,
+        body_weight: Option<f32>,
+        diagnostics: &mut Diagnostics,
+    ) -> Option<Set> {
+        // Skip rest timers or notes
+        if cell_set
+            .cells
+            .iter()
+            .any(|cell| matches!(cell.cell_type.as_str(), "REST_TIMER" | "NOTE"))
+        {
+            return None;
+        }
+
+        for cell in &cell_set.cells {
+            if !KNOWN_CELL_TYPES.contains(&cell.cell_type.as_str()) {
+                diagnostics.unknown_cell_types.push(UnknownCellType {
+                    cell_id: cell.id.clone(),
+                    cell_type: cell.cell_type.clone(),
+                });
+            }
+        }
+
+        let (load_type, weight_cell) = cell_set
+            .cells
+            .iter()
+            .find_map(|cell| LoadType::from_cell_type(&cell.cell_type).map(|load| (load, cell)))
+            .map_or((LoadType::Unweighted, None), |(load, cell)| {
+                (load, Some(cell))
+            });
+
+        let weight_unit = self.weight_unit(load_type, measurement_id);
+        let weight = weight_cell
+            .and_then(|cell| Self::parse_cell_value(cell, cell_value::parse_decimal, diagnostics))
+            .map(|weight| weight_unit.to_kilograms(weight));
+
+        let (bar_weight, total_load, effective_load) =
+            self.loads(load_type, measurement_id, weight, body_weight);
+
+        let reps = cell_set
+            .cells
+            .iter()
+            .find(|cell| cell.cell_type == "REPS")
+            .and_then(|cell| Self::parse_cell_value(cell, cell_value::parse_count, diagnostics))
+            .unwrap_or(0);
+
+        let rpe = cell_set
+            .cells
+            .iter()
+            .find(|cell| cell.cell_type == "RPE")
+            .and_then(|cell| Self::parse_cell_value(cell, cell_value::parse_decimal, diagnostics));
+
+        let duration = cell_set
+            .cells
+            .iter()
+            .find(|cell| cell.cell_type == "DURATION")
+            .and_then(|cell| Self::parse_cell_value(cell, cell_value::parse_duration, diagnostics));
+
+        let distance_unit = self
+            .preferences
+            .distance_unit(&self.user_id, measurement_id);
+        let distance = cell_set
+            .cells
+            .iter()
+            .find(|cell| cell.cell_type == "DISTANCE")
+            .and_then(|cell| Self::parse_cell_value(cell, cell_value::parse_decimal, diagnostics))
+            .map(|distance| distance_unit.to_kilometers(distance));
+
+        Some(Set {
+            id: cell_set.id.clone(),
+            weight,
+            weight_unit,
+            load_type,
+            weight_cell_type: weight_cell.map(|cell| cell.cell_type.clone()),
+            bar_weight,
+            total_load,
+            effective_load,
+            reps,
+            rpe,
+            duration,
+            distance,
+            distance_unit,
+            is_completed: cell_set.is_completed.unwrap_or(false),
+            notes: None,
+        })
+    }
+
+    /// The unit weights of `measurement_id` are logged in. Assistance is configured like a
+    /// machine's resistance, everything else like a weight.
+    fn weight_unit(&self, load_type: LoadType, measurement_id: &str) -> WeightUnit {
+        match load_type {
+            LoadType::AssistedBodyweight => self
+                .preferences
+                .resistance_weight_unit(&self.user_id, measurement_id),
+            _ => self.preferences.weight_unit(&self.user_id, measurement_id),
+        }
+    }
+
+    /// Bar weight, total load and effective load of a set with the logged `weight` in
+    /// kilograms. Only external loads are put on a bar or machine, bodyweight exercises have
+    /// no bar.
+    fn loads(
+        &self,
+        load_type: LoadType,
+        measurement_id: &str,
+        weight: Option<f32>,
+        body_weight: Option<f32>,
+    ) -> (Option<f32>, Option<f32>, Option<f32>) {
+        let bar_weight = match load_type {
+            LoadType::External => {
+                self.preferences
+                    .bar_weight(&self.user_id, measurement_id, &self.custom_bar_types)
+            }
+            _ => None,
+        };
+        let total_load = match bar_weight {
+            Some(bar_weight) if !self.logged_weight_includes_bar => {
+                weight.map(|weight| weight + bar_weight)
+            }
+            _ => weight,
+        };
+        (
+            bar_weight,
+            total_load,
+            load_type.effective_load(total_load, body_weight),
+        )
+    }
+
+    /// Parses the value of a cell with one of the `cell_value` parsers, reporting values that
+    /// are present but can't be parsed. Blank values are treated like missing ones.
+    fn parse_cell_value<T>(
+        cell: &Cell,
+        parse: fn(&str) -> Option<T>,
+        diagnostics: &mut Diagnostics,
+    ) -> Option<T> {
+        let value = cell
+            .value
+            .as_ref()
+            .filter(|value| !value.trim().is_empty())?;
+        let parsed = parse(value);
+        if parsed.is_none() {
+            diagnostics.unparsable_values.push(UnparsableValue {
+                cell_id: cell.id.clone(),
+                cell_type: cell.cell_type.clone(),
+                value: value.clone(),
+            });
+        }
+        parsed
+    }
+
+    fn get_template_id_from_link(links: &serde_json::Value) -> Option<String> {
+        let href = links.get("template")?.get("href")?.as_str()?;
+        href.rsplit('/').next().map(str::to_string)
+    }
+
+    fn get_measurement_id_from_link(links: &CellSetGroupLinks) -> String {
+        let url = match &links.measurement {
+            Some(link) => link.href.clone(),
+            None => return String::new(),
+        };
+
+        let parts: Vec<&str> = url.split("/").collect();
+        parts[parts.len() - 1].to_string()
+    }
+}
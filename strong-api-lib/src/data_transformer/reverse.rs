@@ -0,0 +1,297 @@
+//! Turns `Workout`s back into Strong `Log`s, e.g. to upload workouts imported from another app.
+//!
+//! Which cells a set gets is taken from the measurement's `cellTypeConfigs`: mandatory cells are
+//! always written, optional ones only if the set has a value for them. Exercises whose
+//! measurement isn't in the catalogue get cells derived from the values of their sets.
+
+use super::{DataTransformer, Exercise, LoadType, Set, Workout};
+use crate::models::common::{Link, Name};
+use crate::models::measurement::Measurement;
+use crate::models::workout::{
+    Cell, CellSet, CellSetGroup, CellSetGroupEmbedded, CellSetGroupLinks, Log, LogEmbedded,
+};
+use serde_json::json;
+use std::fmt;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+use uuid::Uuid;
+
+/// An exercise that has no measurement id and whose name matches no measurement of the catalogue.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownExercise {
+    pub exercise_id: String,
+    pub name: String,
+}
+
+impl fmt::Display for UnknownExercise {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "exercise '{}' ({}) matches no measurement",
+            self.name, self.exercise_id
+        )
+    }
+}
+
+impl std::error::Error for UnknownExercise {}
+
+impl DataTransformer {
+    /// Builds a Strong `Log` from a workout. Weights and distances are written in the unit
+    /// they were logged in. Ids that aren't UUIDs are replaced by new ones, cells always get
+    /// new ids.
+    pub fn workout_to_log(&self, workout: &Workout) -> Result<Log, UnknownExercise> {
+        self.build_log(workout, "WORKOUT")
+    }
+
+    /// Builds a Strong template from a workout, e.g. a session proposed by the `planner`.
+    /// Templates have no time zone, dates or template link, their sets are never completed.
+    pub fn workout_to_template(&self, workout: &Workout) -> Result<Log, UnknownExercise> {
+        let template = Workout {
+            timezone: None,
+            template_id: None,
+            start_date: None,
+            end_date: None,
+            exercises: workout
+                .exercises
+                .iter()
+                .map(|exercise| Exercise {
+                    sets: exercise
+                        .sets
+                        .iter()
+                        .map(|set| Set {
+                            is_completed: false,
+                            ..set.clone()
+                        })
+                        .collect(),
+                    ..exercise.clone()
+                })
+                .collect(),
+            ..workout.clone()
+        };
+        self.build_log(&template, "TEMPLATE")
+    }
+
+    /// Same as `workout_to_template` for a list of workouts.
+    pub fn workouts_to_templates(&self, workouts: &[Workout]) -> Result<Vec<Log>, UnknownExercise> {
+        workouts
+            .iter()
+            .map(|workout| self.workout_to_template(workout))
+            .collect()
+    }
+
+    fn build_log(&self, workout: &Workout, log_type: &str) -> Result<Log, UnknownExercise> {
+        let id = uuid_or_new(&workout.id);
+        let collection = match log_type {
+            "TEMPLATE" => "templates",
+            _ => "logs",
+        };
+        let now = OffsetDateTime::now_utc()
+            .format(&Rfc3339)
+            .unwrap_or_default();
+
+        // Workout notes are a group of their own, without a measurement.
+        let notes = workout.notes.as_deref().map(|notes| CellSetGroup {
+            links: CellSetGroupLinks { measurement: None },
+            embedded: CellSetGroupEmbedded {},
+            id: Uuid::new_v4().to_string(),
+            cell_sets: vec![note_cell_set(notes)],
+        });
+        let cell_set_group = notes
+            .into_iter()
+            .map(Ok)
+            .chain(
+                workout
+                    .exercises
+                    .iter()
+                    .map(|exercise| self.exercise_to_cell_set_group(exercise)),
+            )
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut links = json!({
+            "self": { "href": format!("/api/users/{}/{}/{}", self.user_id, collection, id) },
+            "user": { "href": format!("/api/users/{}", self.user_id) },
+        });
+        if let Some(template_id) = &workout.template_id {
+            links["template"] = json!({
+                "href": format!("/api/users/{}/templates/{}", self.user_id, template_id)
+            });
+        }
+
+        Ok(Log {
+            links,
+            embedded: LogEmbedded { cell_set_group },
+            timezone_id: workout.timezone.clone(),
+            id,
+            created: now.clone(),
+            last_changed: now,
+            name: (!workout.name.is_empty()).then(|| Name::from(workout.name.clone())),
+            access: "PRIVATE".to_string(),
+            start_date: workout.start_date.clone(),
+            end_date: workout.end_date.clone(),
+            log_type: log_type.to_string(),
+        })
+    }
+
+    /// Same as `workout_to_log` for a list of workouts.
+    pub fn workouts_to_logs(&self, workouts: &[Workout]) -> Result<Vec<Log>, UnknownExercise> {
+        workouts
+            .iter()
+            .map(|workout| self.workout_to_log(workout))
+            .collect()
+    }
+
+    fn exercise_to_cell_set_group(
+        &self,
+        exercise: &Exercise,
+    ) -> Result<CellSetGroup, UnknownExercise> {
+        let measurement = self.find_measurement(exercise);
+        let measurement_id = match (exercise.measurement_id.as_str(), measurement) {
+            ("", Some(measurement)) => measurement.id.clone(),
+            ("", None) => {
+                return Err(UnknownExercise {
+                    exercise_id: exercise.id.clone(),
+                    name: exercise.name.clone(),
+                });
+            }
+            (measurement_id, _) => measurement_id.to_string(),
+        };
+
+        // Notes go first, in a cell set of their own.
+        let note = exercise.notes.as_deref().map(note_cell_set);
+
+        Ok(CellSetGroup {
+            links: CellSetGroupLinks {
+                measurement: Some(Link {
+                    href: format!(
+                        "/api/users/{}/measurements/{}",
+                        self.user_id, measurement_id
+                    ),
+                }),
+            },
+            embedded: CellSetGroupEmbedded {},
+            id: uuid_or_new(&exercise.id),
+            cell_sets: note
+                .into_iter()
+                .chain(
+                    exercise
+                        .sets
+                        .iter()
+                        .map(|set| set_to_cell_set(set, measurement)),
+                )
+                .collect(),
+        })
+    }
+
+    /// Looks the exercise up by its measurement id, or by its name in any locale if it has none.
+    fn find_measurement(&self, exercise: &Exercise) -> Option<&Measurement> {
+        let measurements = &self.measurements_response.as_ref()?.embedded.measurements;
+        if exercise.measurement_id.is_empty() {
+            measurements.iter().find(|measurement| {
+                measurement
+                    .name
+                    .values()
+                    .any(|name| name.eq_ignore_ascii_case(&exercise.name))
+            })
+        } else {
+            measurements
+                .iter()
+                .find(|measurement| measurement.id == exercise.measurement_id)
+        }
+    }
+}
+
+fn set_to_cell_set(set: &Set, measurement: Option<&Measurement>) -> CellSet {
+    let cell_types: Vec<&str> = match measurement {
+        Some(measurement) => measurement
+            .cell_type_configs
+            .iter()
+            .filter(|config| {
+                config.mandatory == Some(true) || cell_value(set, &config.cell_type).is_some()
+            })
+            .map(|config| config.cell_type.as_str())
+            .collect(),
+        None => derived_cell_types(set),
+    };
+
+    CellSet {
+        id: uuid_or_new(&set.id),
+        cells: cell_types
+            .into_iter()
+            .map(|cell_type| Cell {
+                id: Uuid::new_v4().to_string(),
+                cell_type: cell_type.to_string(),
+                value: cell_value(set, cell_type),
+            })
+            .collect(),
+        is_completed: Some(set.is_completed),
+    }
+}
+
+fn note_cell_set(notes: &str) -> CellSet {
+    CellSet {
+        id: Uuid::new_v4().to_string(),
+        cells: vec![Cell {
+            id: Uuid::new_v4().to_string(),
+            cell_type: "NOTE".to_string(),
+            value: Some(notes.to_string()),
+        }],
+        is_completed: None,
+    }
+}
+
+/// Cell types for a set without a known measurement, based on the values it has.
+fn derived_cell_types(set: &Set) -> Vec<&str> {
+    let weight_cell_type = match set.load_type {
+        LoadType::Unweighted => None,
+        LoadType::External => Some(set.weight_cell_type.as_deref().unwrap_or("OTHER_WEIGHT")),
+        LoadType::WeightedBodyweight => Some("WEIGHTED_BODYWEIGHT"),
+        LoadType::AssistedBodyweight => Some("ASSISTED_BODYWEIGHT"),
+    };
+
+    let mut cell_types: Vec<&str> = weight_cell_type.into_iter().collect();
+    if set.reps > 0 || weight_cell_type.is_some() {
+        cell_types.push("REPS");
+    }
+    if set.rpe.is_some() {
+        cell_types.push("RPE");
+    }
+    if set.distance.is_some() {
+        cell_types.push("DISTANCE");
+    }
+    if set.duration.is_some() {
+        cell_types.push("DURATION");
+    }
+    cell_types
+}
+
+fn cell_value(set: &Set, cell_type: &str) -> Option<String> {
+    match cell_type {
+        "OTHER_WEIGHT"
+        | "DUMBBELL_WEIGHT"
+        | "BARBELL_WEIGHT"
+        | "WEIGHTED_BODYWEIGHT"
+        | "ASSISTED_BODYWEIGHT" => set
+            .weight
+            .map(|weight| format_decimal(set.weight_unit.from_kilograms(weight))),
+        "REPS" => (set.reps > 0).then(|| set.reps.to_string()),
+        "RPE" => set.rpe.map(format_decimal),
+        "DURATION" => set.duration.map(|duration| duration.to_string()),
+        "DISTANCE" => set
+            .distance
+            .map(|distance| format_decimal(set.distance_unit.from_kilometers(distance))),
+        _ => None,
+    }
+}
+
+/// Formats a value like Strong does, without trailing zeros and with at most three decimals,
+/// which also hides the noise of converting back from kilograms or kilometers.
+fn format_decimal(value: f32) -> String {
+    ((value * 1000.0).round() / 1000.0).to_string()
+}
+
+fn uuid_or_new(id: &str) -> String {
+    match Uuid::parse_str(id) {
+        Ok(uuid) => uuid.to_string(),
+        Err(_) => Uuid::new_v4().to_string(),
+    }
+}
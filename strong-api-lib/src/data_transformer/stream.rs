@@ -0,0 +1,202 @@
+//! Incremental transformation for long histories.
+//!
+//! `DataTransformer::workouts` turns logs into workouts one at a time, and `read_logs` reads
+//! logs one at a time from JSON, so neither the logs nor the workouts of a whole history have
+//! to be kept in memory.
+
+use super::diagnostics::Diagnostics;
+use super::{DataTransformer, MeasurementLookup, Workout};
+use crate::models::workout::Log;
+use serde::de::Error as _;
+use std::borrow::Borrow;
+use std::io::{BufRead, BufReader, Read};
+
+/// Iterator over the workouts of a list of logs, created by `DataTransformer::workouts`.
+/// Collects the `Diagnostics` of all logs consumed so far.
+pub struct Workouts<'a, I> {
+    transformer: &'a DataTransformer,
+    lookup: MeasurementLookup<'a>,
+    logs: I,
+    diagnostics: Diagnostics,
+}
+
+impl<'a, I> Workouts<'a, I> {
+    pub(super) fn new(
+        transformer: &'a DataTransformer,
+        lookup: MeasurementLookup<'a>,
+        logs: I,
+    ) -> Self {
+        Self {
+            transformer,
+            lookup,
+            logs,
+            diagnostics: Diagnostics::default(),
+        }
+    }
+
+    /// Issues of the logs transformed so far.
+    pub fn diagnostics(&self) -> &Diagnostics {
+        &self.diagnostics
+    }
+
+    pub fn into_diagnostics(self) -> Diagnostics {
+        self.diagnostics
+    }
+}
+
+impl<I> Iterator for Workouts<'_, I>
+where
+    I: Iterator,
+    I::Item: Borrow<Log>,
+{
+    type Item = Workout;
+
+    fn next(&mut self) -> Option<Workout> {
+        let log = self.logs.next()?;
+        Some(self.transformer.process_log_to_workout(
+            log.borrow(),
+            &self.lookup,
+            &mut self.diagnostics,
+        ))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.logs.size_hint()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ReadState {
+    Start,
+    FirstInArray,
+    InArray,
+    Concatenated,
+    Done,
+}
+
+/// Iterator over the logs of a JSON document, created by `read_logs`.
+pub struct LogReader<R> {
+    reader: BufReader<R>,
+    state: ReadState,
+    buffer: Vec<u8>,
+}
+
+/// Reads logs one at a time from either a JSON array of logs, like the `log` list of a user's
+/// `_embedded`, or from logs separated by whitespace, like newline delimited JSON.
+/// Stops after the first error.
+pub fn read_logs<R: Read>(reader: R) -> LogReader<R> {
+    LogReader {
+        reader: BufReader::new(reader),
+        state: ReadState::Start,
+        buffer: Vec::new(),
+    }
+}
+
+impl<R: Read> LogReader<R> {
+    /// Skips whitespace and returns the next byte without consuming it.
+    fn peek(&mut self) -> Result<Option<u8>, serde_json::Error> {
+        loop {
+            let buffer = self.reader.fill_buf().map_err(serde_json::Error::custom)?;
+            match buffer.first() {
+                None => return Ok(None),
+                Some(byte) if byte.is_ascii_whitespace() => self.reader.consume(1),
+                Some(byte) => return Ok(Some(*byte)),
+            }
+        }
+    }
+
+    /// Moves to the start of the next log, returns `false` if there is none.
+    fn advance(&mut self) -> Result<bool, serde_json::Error> {
+        match (self.state, self.peek()?) {
+            (ReadState::Done, _) => Ok(false),
+            (ReadState::Start, Some(b'[')) => {
+                self.reader.consume(1);
+                self.state = ReadState::FirstInArray;
+                self.advance()
+            }
+            (ReadState::Start, _) => {
+                self.state = ReadState::Concatenated;
+                self.advance()
+            }
+            (ReadState::FirstInArray | ReadState::InArray, Some(b']')) => {
+                self.reader.consume(1);
+                self.state = ReadState::Done;
+                Ok(false)
+            }
+            (ReadState::FirstInArray, Some(_)) => {
+                self.state = ReadState::InArray;
+                Ok(true)
+            }
+            (ReadState::InArray, Some(b',')) => {
+                self.reader.consume(1);
+                Ok(self.peek()?.is_some())
+            }
+            (ReadState::FirstInArray | ReadState::InArray, _) => Err(serde_json::Error::custom(
+                "expected `,` or `]` between logs",
+            )),
+            (ReadState::Concatenated, next) => Ok(next.is_some()),
+        }
+    }
+
+    /// Copies the next JSON object into `buffer`. Parsing it from a slice afterwards is a lot
+    /// faster than letting serde_json read it byte by byte.
+    fn read_object(&mut self) -> Result<(), serde_json::Error> {
+        self.buffer.clear();
+        let mut depth = 0usize;
+        let mut in_string = false;
+        let mut escaped = false;
+        loop {
+            let available = self.reader.fill_buf().map_err(serde_json::Error::custom)?;
+            if available.is_empty() {
+                return Err(serde_json::Error::custom("unexpected end of input in log"));
+            }
+
+            let mut end = None;
+            for (index, byte) in available.iter().enumerate() {
+                match (in_string, escaped, byte) {
+                    (true, true, _) => escaped = false,
+                    (true, false, b'\\') => escaped = true,
+                    (true, false, b'"') => in_string = false,
+                    (true, false, _) => {}
+                    (false, _, b'"') => in_string = true,
+                    (false, _, b'{' | b'[') => depth += 1,
+                    (false, _, b'}' | b']') => depth = depth.saturating_sub(1),
+                    (false, _, _) => {}
+                }
+                if depth == 0 {
+                    end = Some(index + 1);
+                    break;
+                }
+            }
+
+            let consumed = end.unwrap_or(available.len());
+            self.buffer.extend_from_slice(&available[..consumed]);
+            self.reader.consume(consumed);
+            if end.is_some() {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for LogReader<R> {
+    type Item = Result<Log, serde_json::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.advance().and_then(|has_next| {
+            if !has_next {
+                return Ok(None);
+            }
+            self.read_object()?;
+            serde_json::from_slice(&self.buffer).map(Some)
+        });
+
+        match result {
+            Ok(log) => log.map(Ok),
+            Err(error) => {
+                self.state = ReadState::Done;
+                Some(Err(error))
+            }
+        }
+    }
+}
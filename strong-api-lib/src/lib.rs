@@ -1,3 +1,8 @@
+pub mod analytics;
 pub mod data_transformer;
 pub mod models;
+pub mod planner;
+pub mod plate_calculator;
+pub mod program;
+pub mod query;
 pub mod strong_api;
@@ -1,4 +1,6 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Link {
@@ -12,10 +14,82 @@ pub struct Links {
     pub next: Option<Link>,
 }
 
+/// A name in one or more locales. Global measurements are named per locale, custom ones
+/// only have `custom`.
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Name {
     pub en: Option<String>,
     pub custom: Option<String>,
+    /// Every other locale the backend returned, e.g. `de`. Locales without a name (`null`) or
+    /// with something other than a string are left out.
+    #[serde(flatten, deserialize_with = "string_values")]
+    pub locales: BTreeMap<String, String>,
+}
+
+impl Name {
+    /// The name in the given locale. `de-AT` falls back to `de` if there is no exact match.
+    pub fn get(&self, locale: &str) -> Option<&str> {
+        self.entry(locale).map(|(name, _)| name)
+    }
+
+    /// Picks the name for the first locale of `preferred` it exists in, falling back to
+    /// `en`, `custom` and then any other locale. Returns the name together with the locale it
+    /// was taken from, or `None` if there is no name at all.
+    pub fn localized<S: AsRef<str>>(&self, preferred: &[S]) -> Option<(&str, &str)> {
+        preferred
+            .iter()
+            .map(AsRef::as_ref)
+            .chain(["en", "custom"])
+            .find_map(|locale| self.entry(locale))
+            .or_else(|| {
+                self.locales
+                    .iter()
+                    .next()
+                    .map(|(locale, name)| (name.as_str(), locale.as_str()))
+            })
+    }
+
+    /// The names of all locales.
+    pub fn values(&self) -> impl Iterator<Item = &str> {
+        self.en
+            .iter()
+            .chain(&self.custom)
+            .chain(self.locales.values())
+            .map(String::as_str)
+    }
+
+    fn entry(&self, locale: &str) -> Option<(&str, &str)> {
+        let exact = match locale {
+            "en" => self.en.as_deref().map(|name| (name, "en")),
+            "custom" => self.custom.as_deref().map(|name| (name, "custom")),
+            _ => self
+                .locales
+                .get_key_value(locale)
+                .map(|(locale, name)| (name.as_str(), locale.as_str())),
+        };
+        let language = locale.split(['-', '_']).next().unwrap_or(locale);
+        exact.or_else(|| {
+            if language != locale {
+                self.entry(language)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+fn string_values<'de, D>(deserializer: D) -> Result<BTreeMap<String, String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw: BTreeMap<String, Value> = BTreeMap::deserialize(deserializer)?;
+    Ok(raw
+        .into_iter()
+        .filter_map(|(locale, name)| match name {
+            Value::String(name) => Some((locale, name)),
+            _ => None,
+        })
+        .collect())
 }
 
 impl std::fmt::Display for Name {
@@ -47,6 +121,7 @@ impl From<String> for Name {
         Name {
             en: Some(name),
             custom: None,
+            locales: BTreeMap::new(),
         }
     }
 }
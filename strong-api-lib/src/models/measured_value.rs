@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+use super::common::Link;
+use super::workout::Cell;
+
+/// A single value the user measured outside of a workout, e.g. their body weight.
+/// The backend returns these in `_embedded.measuredValue` when `Includes::MeasuredValue` is requested.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MeasuredValue {
+    #[serde(rename = "_links", default)]
+    pub links: MeasuredValueLinks,
+    pub id: String,
+    #[serde(default)]
+    pub created: String,
+    /// When the value was measured, falls back to `created` if absent.
+    #[serde(default)]
+    pub date: Option<String>,
+    #[serde(default)]
+    pub cells: Vec<Cell>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MeasuredValueLinks {
+    pub measurement: Option<Link>,
+}
@@ -81,3 +81,14 @@ impl MeasurementsResponse {
         }
     }
 }
+
+impl Measurement {
+    /// Tags of the measurement, e.g. `legs` or `cardio`, taken from its tag links.
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.links
+            .tag
+            .iter()
+            .flatten()
+            .filter_map(|link| link.href.rsplit('/').next())
+    }
+}
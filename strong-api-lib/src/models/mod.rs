@@ -1,5 +1,7 @@
 pub mod auth;
 pub mod common;
 pub mod error;
+pub mod measured_value;
 pub mod measurement;
+pub mod preferences;
 pub mod workout;
@@ -0,0 +1,231 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fmt;
+
+const POUNDS_PER_KILOGRAM: f32 = 2.204_622_6;
+const KILOMETERS_PER_MILE: f32 = 1.609_344;
+const OLYMPIC_BAR_KILOGRAMS: f32 = 20.0;
+const OLYMPIC_BAR_POUNDS: f32 = 45.0;
+
+/// The user's preferences. Every map is keyed either by the user id, holding the user-wide
+/// setting, or by a measurement id, holding an override for that single exercise.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Preferences {
+    #[serde(rename = "weightUnit", default, deserialize_with = "known_values")]
+    pub weight_unit: HashMap<String, WeightUnit>,
+    #[serde(rename = "distanceUnit", default, deserialize_with = "known_values")]
+    pub distance_unit: HashMap<String, DistanceUnit>,
+    #[serde(
+        rename = "resistanceWeightUnit",
+        default,
+        deserialize_with = "known_values"
+    )]
+    pub resistance_weight_unit: HashMap<String, WeightUnit>,
+    /// `NONE`, `OLYMPIC_BAR` or the id of one of the user's `CustomBarType`s.
+    #[serde(rename = "barType", default, deserialize_with = "known_values")]
+    pub bar_type: HashMap<String, String>,
+    /// Weight of e.g. a machine's sled that isn't part of the plates, in the user's weight unit.
+    #[serde(rename = "baseWeight", default, deserialize_with = "known_values")]
+    pub base_weight: HashMap<String, f32>,
+    /// Plates like `PLATE_20KG` or `PLATE_2_5KG`, see `plate_calculator::Plate`.
+    #[serde(rename = "availablePlates", default, deserialize_with = "known_values")]
+    pub available_plates: HashMap<String, Vec<String>>,
+    /// All other preferences (rest timers, goals, ...) as returned by the backend. This includes
+    /// `lengthUnit`: body measurements other than body weight aren't read, so there is nothing
+    /// to convert with it yet.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+impl Preferences {
+    /// Unit the user logs weights in for the given measurement.
+    pub fn weight_unit(&self, user_id: &str, measurement_id: &str) -> WeightUnit {
+        resolve(&self.weight_unit, user_id, measurement_id)
+    }
+
+    /// Unit the user logs distances in for the given measurement.
+    pub fn distance_unit(&self, user_id: &str, measurement_id: &str) -> DistanceUnit {
+        resolve(&self.distance_unit, user_id, measurement_id)
+    }
+
+    /// Unit the user logs resistance, like machine assistance, in for the given measurement.
+    pub fn resistance_weight_unit(&self, user_id: &str, measurement_id: &str) -> WeightUnit {
+        resolve(&self.resistance_weight_unit, user_id, measurement_id)
+    }
+
+    /// Weight in kilograms of the bar plus the base weight configured for the given measurement.
+    /// Returns `None` if neither is configured or the bar type is unknown.
+    pub fn bar_weight(
+        &self,
+        user_id: &str,
+        measurement_id: &str,
+        custom_bar_types: &[CustomBarType],
+    ) -> Option<f32> {
+        let unit = self.weight_unit(user_id, measurement_id);
+        let bar_type = self
+            .bar_type
+            .get(measurement_id)
+            .or_else(|| self.bar_type.get(user_id));
+        let bar = match bar_type.map(String::as_str) {
+            None => None,
+            Some("NONE") => Some(0.0),
+            Some("OLYMPIC_BAR") => Some(match unit {
+                WeightUnit::Kilograms => OLYMPIC_BAR_KILOGRAMS,
+                WeightUnit::Pounds => unit.to_kilograms(OLYMPIC_BAR_POUNDS),
+            }),
+            Some(custom_id) => Some(
+                custom_bar_types
+                    .iter()
+                    .find(|custom| custom.id == custom_id)?
+                    .weight(unit),
+            ),
+        };
+        let base = self
+            .base_weight
+            .get(measurement_id)
+            .or_else(|| self.base_weight.get(user_id))
+            .map(|base| unit.to_kilograms(*base));
+
+        match (bar, base) {
+            (None, None) => None,
+            (bar, base) => Some(bar.unwrap_or(0.0) + base.unwrap_or(0.0)),
+        }
+    }
+
+    /// Plates the user has available, as configured in `availablePlates`.
+    pub fn available_plates(&self, user_id: &str) -> Vec<String> {
+        self.available_plates
+            .get(user_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// A bar the user defined themselves, found in `legacyCustomBarTypes` of the user.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomBarType {
+    #[serde(rename = "legacyCustomBarTypeId")]
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "weightInKilograms")]
+    pub weight_in_kilograms: f32,
+    #[serde(rename = "weightInPounds")]
+    pub weight_in_pounds: f32,
+}
+
+impl CustomBarType {
+    /// The bar's weight in kilograms, using the value the user entered for their unit.
+    pub fn weight(&self, unit: WeightUnit) -> f32 {
+        match unit {
+            WeightUnit::Kilograms => self.weight_in_kilograms,
+            WeightUnit::Pounds => unit.to_kilograms(self.weight_in_pounds),
+        }
+    }
+}
+
+/// Looks up the measurement specific value first, then the user-wide one.
+fn resolve<T: Copy + Default>(map: &HashMap<String, T>, user_id: &str, measurement_id: &str) -> T {
+    map.get(measurement_id)
+        .or_else(|| map.get(user_id))
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Deserializes a preference map, dropping entries with values this library doesn't know,
+/// so a new unit on the backend doesn't break parsing of the whole user.
+fn known_values<'de, D, T>(deserializer: D) -> Result<HashMap<String, T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let raw: Option<HashMap<String, Value>> = Option::deserialize(deserializer)?;
+    Ok(raw
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(key, value)| serde_json::from_value(value).ok().map(|value| (key, value)))
+        .collect())
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum WeightUnit {
+    #[default]
+    Kilograms,
+    Pounds,
+}
+
+impl WeightUnit {
+    /// Converts a value given in this unit to kilograms.
+    pub fn to_kilograms(self, value: f32) -> f32 {
+        match self {
+            WeightUnit::Kilograms => value,
+            WeightUnit::Pounds => value / POUNDS_PER_KILOGRAM,
+        }
+    }
+
+    /// Converts a value given in kilograms to this unit.
+    pub fn from_kilograms(self, kilograms: f32) -> f32 {
+        match self {
+            WeightUnit::Kilograms => kilograms,
+            WeightUnit::Pounds => kilograms * POUNDS_PER_KILOGRAM,
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            WeightUnit::Kilograms => "kg",
+            WeightUnit::Pounds => "lb",
+        }
+    }
+}
+
+impl fmt::Display for WeightUnit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DistanceUnit {
+    #[default]
+    Kilometers,
+    Miles,
+    Meters,
+}
+
+impl DistanceUnit {
+    /// Converts a value given in this unit to kilometers.
+    pub fn to_kilometers(self, value: f32) -> f32 {
+        match self {
+            DistanceUnit::Kilometers => value,
+            DistanceUnit::Miles => value * KILOMETERS_PER_MILE,
+            DistanceUnit::Meters => value / 1000.0,
+        }
+    }
+
+    /// Converts a value given in kilometers to this unit.
+    pub fn from_kilometers(self, kilometers: f32) -> f32 {
+        match self {
+            DistanceUnit::Kilometers => kilometers,
+            DistanceUnit::Miles => kilometers / KILOMETERS_PER_MILE,
+            DistanceUnit::Meters => kilometers * 1000.0,
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            DistanceUnit::Kilometers => "km",
+            DistanceUnit::Miles => "mi",
+            DistanceUnit::Meters => "m",
+        }
+    }
+}
+
+impl fmt::Display for DistanceUnit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
@@ -1,7 +1,9 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
+use time::Weekday;
 
 use super::common::{Link, Name};
+use super::preferences::{CustomBarType, Preferences};
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct UserResponse {
@@ -19,11 +21,18 @@ pub struct UserResponse {
     pub email_verified: bool,
     pub name: Option<String>,
     pub avatar: Value,
-    pub preferences: Value,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub preferences: Preferences,
     #[serde(rename = "legacyPurchase")]
     pub legacy_purchase: Value,
     #[serde(rename = "legacyGoals")]
     pub legacy_goals: Value,
+    #[serde(
+        rename = "legacyCustomBarTypes",
+        default,
+        deserialize_with = "null_as_default"
+    )]
+    pub legacy_custom_bar_types: Vec<CustomBarType>,
     #[serde(rename = "startHistoryFromDate")]
     pub start_history_from_date: String,
     #[serde(rename = "firstWeekDay")]
@@ -33,6 +42,49 @@ pub struct UserResponse {
     pub migrated: String,
 }
 
+/// Deserializes `null` like a missing field, as the default.
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::deserialize(deserializer)?.unwrap_or_default())
+}
+
+impl UserResponse {
+    /// The day the user's weeks start on, from `firstWeekDay`. Monday if it is unknown.
+    pub fn first_weekday(&self) -> Weekday {
+        match self.first_week_day.as_str() {
+            "TUESDAY" => Weekday::Tuesday,
+            "WEDNESDAY" => Weekday::Wednesday,
+            "THURSDAY" => Weekday::Thursday,
+            "FRIDAY" => Weekday::Friday,
+            "SATURDAY" => Weekday::Saturday,
+            "SUNDAY" => Weekday::Sunday,
+            _ => Weekday::Monday,
+        }
+    }
+
+    /// Workouts per week the user aims for, from `legacyGoals`.
+    pub fn workouts_per_week_goal(&self) -> Option<u32> {
+        self.legacy_goals
+            .get("workoutsPerWeek")?
+            .as_u64()
+            .and_then(|goal| u32::try_from(goal).ok())
+    }
+
+    /// The user's templates from `_embedded.template`. They are shaped like logs with a
+    /// `logType` of `TEMPLATE`, templates that don't parse as a `Log` are left out.
+    pub fn templates(&self) -> Vec<Log> {
+        self.embedded
+            .template
+            .iter()
+            .flatten()
+            .filter_map(|template| serde_json::from_value(template.clone()).ok())
+            .collect()
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Embedded {
     pub measurement: Option<Vec<Value>>,
@@ -0,0 +1,468 @@
+//! Proposes the targets of the next session of a template from the athlete's recent history.
+//!
+//! The template is transformed into a `Workout` like any log. For every exercise of it the
+//! planner looks up the last session of the same measurement, or of the same canonical name
+//! for exercises without one, and applies a `ProgressionRule` to its completed sets. Exercises
+//! without such a session keep the template's targets.
+//!
+//! The plan is a `Workout` of incomplete sets, which `DataTransformer::workout_to_template`
+//! turns into a template log ready to upload. Planned sets keep the unit the athlete logs the
+//! exercise in, increments and rounding steps are given in that unit, e.g. 2.5 for a kilogram
+//! and 5 for a pound exercise. Like every `Set`, planned weights are stored in kilograms.
+
+use crate::analytics::one_rep_max::{OneRepMaxFormula, rpe_table_load};
+use crate::data_transformer::{Exercise, Set, Workout};
+use crate::models::preferences::WeightUnit;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// How the targets of an exercise move from one session to the next.
+/// Rules are checked with `validate()` when they are deserialized.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "rule",
+    rename_all = "snake_case",
+    try_from = "UncheckedProgressionRule"
+)]
+pub enum ProgressionRule {
+    /// Adds `increment` to the weight once every set of the last session reached the
+    /// template's reps, repeats the weight otherwise.
+    Linear { increment: f32 },
+    /// Adds a rep to every set up to `max_reps`. Once every set reached `max_reps`, adds
+    /// `increment` to the weight and starts over at `min_reps`.
+    Double {
+        min_reps: u32,
+        max_reps: u32,
+        increment: f32,
+    },
+    /// Picks the weight for the template's reps at `target_rpe` from the one-rep max the RPE
+    /// chart estimates from the last session. Sets logged without RPE are estimated with Epley.
+    RpeAutoregulation { target_rpe: f32 },
+}
+
+impl ProgressionRule {
+    /// Rejects a double progression whose `min_reps` is above its `max_reps`.
+    pub fn validate(&self) -> Result<(), InvalidRule> {
+        match *self {
+            ProgressionRule::Double {
+                min_reps, max_reps, ..
+            } if min_reps > max_reps => Err(InvalidRule {
+                rule: self.to_string(),
+                reason: format!("min_reps {min_reps} is above max_reps {max_reps}"),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The same as `ProgressionRule`, read before it is validated.
+#[derive(Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+enum UncheckedProgressionRule {
+    Linear {
+        increment: f32,
+    },
+    Double {
+        min_reps: u32,
+        max_reps: u32,
+        increment: f32,
+    },
+    RpeAutoregulation {
+        target_rpe: f32,
+    },
+}
+
+impl TryFrom<UncheckedProgressionRule> for ProgressionRule {
+    type Error = InvalidRule;
+
+    fn try_from(rule: UncheckedProgressionRule) -> Result<Self, Self::Error> {
+        let rule = match rule {
+            UncheckedProgressionRule::Linear { increment } => ProgressionRule::Linear { increment },
+            UncheckedProgressionRule::Double {
+                min_reps,
+                max_reps,
+                increment,
+            } => ProgressionRule::Double {
+                min_reps,
+                max_reps,
+                increment,
+            },
+            UncheckedProgressionRule::RpeAutoregulation { target_rpe } => {
+                ProgressionRule::RpeAutoregulation { target_rpe }
+            }
+        };
+        rule.validate()?;
+        Ok(rule)
+    }
+}
+
+/// A progression rule that can't be applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidRule {
+    pub rule: String,
+    pub reason: String,
+}
+
+impl fmt::Display for InvalidRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid {} rule: {}", self.rule, self.reason)
+    }
+}
+
+impl std::error::Error for InvalidRule {}
+
+impl Default for ProgressionRule {
+    fn default() -> Self {
+        ProgressionRule::Linear { increment: 2.5 }
+    }
+}
+
+impl fmt::Display for ProgressionRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match self {
+            ProgressionRule::Linear { .. } => "linear",
+            ProgressionRule::Double { .. } => "double",
+            ProgressionRule::RpeAutoregulation { .. } => "rpe_autoregulation",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+/// What the planner did with an exercise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Adjustment {
+    /// No earlier session, the template's targets are kept.
+    NoHistory,
+    /// The last session was good enough, the weight goes up.
+    IncreasedWeight,
+    /// The last session fell short, its weight is repeated.
+    RepeatedWeight,
+    /// The weight is repeated for more reps.
+    AddedReps,
+    /// The weight was derived from the estimated one-rep max.
+    Autoregulated,
+}
+
+impl fmt::Display for Adjustment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = match self {
+            Adjustment::NoHistory => "no_history",
+            Adjustment::IncreasedWeight => "increased_weight",
+            Adjustment::RepeatedWeight => "repeated_weight",
+            Adjustment::AddedReps => "added_reps",
+            Adjustment::Autoregulated => "autoregulated",
+        };
+        write!(f, "{}", value)
+    }
+}
+
+/// How one exercise of the template was planned.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExercisePlan {
+    /// The exercise's `canonical_name`.
+    pub exercise: String,
+    pub measurement_id: String,
+    pub rule: ProgressionRule,
+    /// Id of the workout the targets were derived from.
+    pub last_session: Option<String>,
+    pub adjustment: Adjustment,
+}
+
+/// The proposed next session and how each exercise was planned.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plan {
+    pub workout: Workout,
+    /// In template order.
+    pub exercises: Vec<ExercisePlan>,
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Plan for {}: {} exercise(s)",
+            self.workout.name,
+            self.exercises.len()
+        )?;
+        for (plan, exercise) in self.exercises.iter().zip(&self.workout.exercises) {
+            let sets: Vec<String> = exercise.sets.iter().map(format_set).collect();
+            write!(
+                f,
+                "\n  {}: {} ({}, {})",
+                plan.exercise,
+                sets.join(", "),
+                plan.rule,
+                plan.adjustment
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn format_set(set: &Set) -> String {
+    let mut text = match set.weight {
+        Some(weight) => format!(
+            "{} {} x {}",
+            (set.weight_unit.from_kilograms(weight) * 1000.0).round() / 1000.0,
+            set.weight_unit.symbol(),
+            set.reps
+        ),
+        None => format!("{} reps", set.reps),
+    };
+    if let Some(rpe) = set.rpe {
+        text.push_str(&format!(" @{rpe}"));
+    }
+    text
+}
+
+/// Plans the next session of templates with one rule for all exercises and optional rules
+/// per measurement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Planner {
+    rule: ProgressionRule,
+    rules: HashMap<String, ProgressionRule>,
+    rounding: Option<f32>,
+}
+
+impl Default for Planner {
+    fn default() -> Self {
+        Self::new(ProgressionRule::default())
+    }
+}
+
+impl Planner {
+    /// Plans every exercise with `rule`, without rounding the weights.
+    pub fn new(rule: ProgressionRule) -> Self {
+        Self {
+            rule,
+            rules: HashMap::new(),
+            rounding: None,
+        }
+    }
+
+    /// Plans the exercises of one measurement with a different rule.
+    pub fn with_rule(mut self, measurement_id: impl Into<String>, rule: ProgressionRule) -> Self {
+        self.rules.insert(measurement_id.into(), rule);
+        self
+    }
+
+    /// Rounds planned weights to the nearest multiple of `step`, in the unit the exercise is
+    /// logged in.
+    pub fn with_rounding(mut self, step: f32) -> Self {
+        self.rounding = (step > 0.0).then_some(step);
+        self
+    }
+
+    pub fn rule_for(&self, measurement_id: &str) -> ProgressionRule {
+        self.rules.get(measurement_id).copied().unwrap_or(self.rule)
+    }
+
+    /// Proposes the next session of `template` based on `history`, in any order. The plan
+    /// gets no ids, `DataTransformer::workout_to_template` assigns new ones.
+    pub fn plan(&self, template: &Workout, history: &[Workout]) -> Plan {
+        let mut history: Vec<&Workout> = history.iter().collect();
+        history.sort_by_key(|workout| std::cmp::Reverse(workout.start_time()));
+
+        let (exercises, plans) = template
+            .exercises
+            .iter()
+            .map(|exercise| self.plan_exercise(exercise, &history))
+            .unzip();
+
+        Plan {
+            workout: Workout {
+                id: String::new(),
+                name: template.name.clone(),
+                exercises,
+                ..Default::default()
+            },
+            exercises: plans,
+        }
+    }
+
+    fn plan_exercise(&self, exercise: &Exercise, history: &[&Workout]) -> (Exercise, ExercisePlan) {
+        let rule = self.rule_for(&exercise.measurement_id);
+        let last_session = last_session(exercise, history);
+
+        let (sets, adjustment) = match &last_session {
+            Some((_, last_sets)) => self.apply(rule, &exercise.sets, last_sets),
+            None => (exercise.sets.clone(), Adjustment::NoHistory),
+        };
+
+        let planned = Exercise {
+            id: String::new(),
+            sets: sets
+                .into_iter()
+                .map(|set| Set {
+                    id: String::new(),
+                    total_load: None,
+                    effective_load: None,
+                    is_completed: false,
+                    ..set
+                })
+                .collect(),
+            ..exercise.clone()
+        };
+        let plan = ExercisePlan {
+            exercise: exercise.canonical_name.clone(),
+            measurement_id: exercise.measurement_id.clone(),
+            rule,
+            last_session: last_session.map(|(workout_id, _)| workout_id),
+            adjustment,
+        };
+        (planned, plan)
+    }
+
+    /// Applies `rule` to the sets of the template given the completed sets of the last session.
+    /// Template set `i` follows set `i` of the last session, or its last set if it had fewer.
+    fn apply(
+        &self,
+        rule: ProgressionRule,
+        template: &[Set],
+        last: &[&Set],
+    ) -> (Vec<Set>, Adjustment) {
+        let previous = |index: usize| last[index.min(last.len() - 1)];
+        let target_reps = |index: usize, set: &Set| match set.reps {
+            0 => previous(index).reps,
+            reps => reps,
+        };
+        let all_sets_done = last.len() >= template.len();
+
+        match rule {
+            ProgressionRule::Linear { increment } => {
+                let succeeded = all_sets_done
+                    && template
+                        .iter()
+                        .enumerate()
+                        .all(|(index, set)| last[index].reps >= target_reps(index, set));
+                let (increment, adjustment) = if succeeded {
+                    (increment, Adjustment::IncreasedWeight)
+                } else {
+                    (0.0, Adjustment::RepeatedWeight)
+                };
+                let sets = template
+                    .iter()
+                    .enumerate()
+                    .map(|(index, set)| Set {
+                        weight: self.add(previous(index), set.weight_unit, increment),
+                        reps: target_reps(index, set),
+                        ..set.clone()
+                    })
+                    .collect();
+                (sets, adjustment)
+            }
+            ProgressionRule::Double {
+                min_reps,
+                max_reps,
+                increment,
+            } => {
+                let succeeded =
+                    all_sets_done && (0..template.len()).all(|index| last[index].reps >= max_reps);
+                let sets = template
+                    .iter()
+                    .enumerate()
+                    .map(|(index, set)| {
+                        let previous = previous(index);
+                        if succeeded {
+                            Set {
+                                weight: self.add(previous, set.weight_unit, increment),
+                                reps: min_reps,
+                                ..set.clone()
+                            }
+                        } else {
+                            Set {
+                                weight: self.add(previous, set.weight_unit, 0.0),
+                                // Not `clamp`, which panics for rules built with `min_reps`
+                                // above `max_reps` without `validate()`.
+                                reps: (previous.reps + 1).min(max_reps).max(min_reps),
+                                ..set.clone()
+                            }
+                        }
+                    })
+                    .collect();
+                let adjustment = if succeeded {
+                    Adjustment::IncreasedWeight
+                } else {
+                    Adjustment::AddedReps
+                };
+                (sets, adjustment)
+            }
+            ProgressionRule::RpeAutoregulation { target_rpe } => {
+                let one_rep_max = last
+                    .iter()
+                    .filter_map(|set| {
+                        OneRepMaxFormula::RpeTable
+                            .estimate_set(set)
+                            .or_else(|| OneRepMaxFormula::Epley.estimate_set(set))
+                    })
+                    .reduce(f32::max);
+                let Some(one_rep_max) = one_rep_max else {
+                    return self.apply(ProgressionRule::Linear { increment: 0.0 }, template, last);
+                };
+                let sets = template
+                    .iter()
+                    .enumerate()
+                    .map(|(index, set)| {
+                        let previous = previous(index);
+                        let reps = target_reps(index, set);
+                        // The load includes the bar and the body weight, the logged weight doesn't.
+                        let offset =
+                            previous.effective_load.unwrap_or(0.0) - previous.weight.unwrap_or(0.0);
+                        let weight = rpe_table_load(one_rep_max, reps, target_rpe)
+                            .map(|load| (load - offset).max(0.0))
+                            .or(previous.weight);
+                        Set {
+                            weight: weight.map(|weight| self.round(weight, set.weight_unit)),
+                            reps,
+                            rpe: Some(target_rpe),
+                            ..set.clone()
+                        }
+                    })
+                    .collect();
+                (sets, Adjustment::Autoregulated)
+            }
+        }
+    }
+
+    /// The weight of `set` plus `increment`, which is in `unit`, rounded.
+    fn add(&self, set: &Set, unit: WeightUnit, increment: f32) -> Option<f32> {
+        let weight = unit.to_kilograms(unit.from_kilograms(set.weight?) + increment);
+        Some(self.round(weight, unit))
+    }
+
+    /// Rounds a weight in kilograms to the rounding step in `unit`.
+    fn round(&self, weight: f32, unit: WeightUnit) -> f32 {
+        match self.rounding {
+            Some(step) => unit.to_kilograms((unit.from_kilograms(weight) / step).round() * step),
+            None => weight,
+        }
+    }
+}
+
+/// The latest workout of `history`, sorted latest first, with completed sets of the exercise,
+/// and those sets.
+fn last_session<'a>(
+    exercise: &Exercise,
+    history: &[&'a Workout],
+) -> Option<(String, Vec<&'a Set>)> {
+    history.iter().find_map(|workout| {
+        let sets: Vec<&Set> = workout
+            .exercises
+            .iter()
+            .filter(|candidate| same_exercise(candidate, exercise))
+            .flat_map(|candidate| &candidate.sets)
+            .filter(|set| set.is_completed)
+            .collect();
+        (!sets.is_empty()).then(|| (workout.id.clone(), sets))
+    })
+}
+
+fn same_exercise(candidate: &Exercise, exercise: &Exercise) -> bool {
+    if exercise.measurement_id.is_empty() {
+        candidate.canonical_name == exercise.canonical_name
+    } else {
+        candidate.measurement_id == exercise.measurement_id
+    }
+}
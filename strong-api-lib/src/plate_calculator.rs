@@ -0,0 +1,150 @@
+//! Works out which plates to put on each side of a bar to reach a target weight.
+//!
+//! Plates are named like in the `availablePlates` preference: `PLATE_20KG`, `PLATE_2_5KG`,
+//! `PLATE_45LB`, where an underscore between digits is the decimal separator.
+
+use crate::models::preferences::WeightUnit;
+
+/// A single plate as configured in the user's `availablePlates`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plate {
+    pub weight: f32,
+    pub unit: WeightUnit,
+}
+
+impl Plate {
+    /// Parses a plate name like `PLATE_2_5KG`. Returns `None` for names in any other shape.
+    pub fn parse(name: &str) -> Option<Plate> {
+        let rest = name.strip_prefix("PLATE_")?;
+        let (number, unit) = if let Some(number) = rest.strip_suffix("KG") {
+            (number, WeightUnit::Kilograms)
+        } else if let Some(number) = rest.strip_suffix("LB") {
+            (number, WeightUnit::Pounds)
+        } else {
+            return None;
+        };
+
+        let weight: f32 = number.replacen('_', ".", 1).parse().ok()?;
+        (weight.is_finite() && weight > 0.0).then_some(Plate { weight, unit })
+    }
+
+    pub fn kilograms(&self) -> f32 {
+        self.unit.to_kilograms(self.weight)
+    }
+}
+
+/// How many plates of one kind go on each side of the bar.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlateCount {
+    pub plate: Plate,
+    pub per_side: u32,
+}
+
+/// The plates to load for a target weight. All weights are in kilograms.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlateBreakdown {
+    pub target: f32,
+    pub bar_weight: f32,
+    /// Plates per side, heaviest first.
+    pub plates: Vec<PlateCount>,
+    /// The weight actually loaded, bar included. Lower than `target` if it can't be reached
+    /// exactly with the available plates.
+    pub loaded: f32,
+}
+
+impl PlateBreakdown {
+    /// Weight missing to reach the target.
+    pub fn remainder(&self) -> f32 {
+        self.target - self.loaded
+    }
+
+    pub fn is_exact(&self) -> bool {
+        self.remainder().abs() < 0.001
+    }
+}
+
+/// Breaks `target` down into plates per side of a bar weighing `bar_weight`, both in
+/// kilograms. Every plate is assumed to be available in pairs as often as needed, plates
+/// are picked heaviest first. `plate_names` that can't be parsed are ignored.
+pub fn plate_breakdown(target: f32, bar_weight: f32, plate_names: &[String]) -> PlateBreakdown {
+    let mut plates: Vec<Plate> = plate_names
+        .iter()
+        .filter_map(|name| Plate::parse(name))
+        .collect();
+    plates.sort_by(|a, b| b.kilograms().total_cmp(&a.kilograms()));
+
+    // Work in grams, so the sums don't collect floating point errors.
+    let mut per_side = (((target - bar_weight) / 2.0).max(0.0) * 1000.0).round() as u64;
+    let mut counts = Vec::new();
+    let mut loaded = bar_weight;
+    for plate in plates {
+        let grams = (plate.kilograms() * 1000.0).round() as u64;
+        if grams == 0 {
+            continue;
+        }
+        let count = per_side / grams;
+        if count > 0 {
+            per_side -= count * grams;
+            loaded += 2.0 * count as f32 * plate.kilograms();
+            counts.push(PlateCount {
+                plate,
+                per_side: count as u32,
+            });
+        }
+    }
+
+    PlateBreakdown {
+        target,
+        bar_weight,
+        plates: counts,
+        loaded,
+    }
+}
+
+/// The loadable weight closest to `target`, with a bar weighing `bar_weight`, both in
+/// kilograms. Halfway between two loadable weights the lighter one wins, targets below the
+/// bar round to the bar. Without any parsable plate `target` is returned unchanged.
+pub fn round_to_plates(target: f32, bar_weight: f32, plate_names: &[String]) -> f32 {
+    // Work in grams like `plate_breakdown`, divided by the plates' greatest common divisor.
+    let grams: Vec<u64> = plate_names
+        .iter()
+        .filter_map(|name| Plate::parse(name))
+        .map(|plate| (plate.kilograms() * 1000.0).round() as u64)
+        .filter(|&grams| grams > 0)
+        .collect();
+    let Some(step) = grams.iter().copied().reduce(gcd) else {
+        return target;
+    };
+    let units: Vec<usize> = grams.iter().map(|&grams| (grams / step) as usize).collect();
+    let heaviest = units.iter().copied().max().unwrap_or_default();
+
+    // Which weights per side can be loaded, up to the first one at or above the target. Plates
+    // of sets like 25, 20 and 15 kg can't be picked heaviest first, so try every combination.
+    let per_side = ((target - bar_weight) / 2.0).max(0.0) * 1000.0 / step as f32;
+    let limit = per_side.ceil() as usize + heaviest;
+    let mut loadable = vec![false; limit + 1];
+    loadable[0] = true;
+    for weight in 1..=limit {
+        loadable[weight] = units
+            .iter()
+            .any(|&plate| plate <= weight && loadable[weight - plate]);
+    }
+
+    let loaded = |weight: usize| bar_weight + 2.0 * (weight as u64 * step) as f32 / 1000.0;
+    let lighter = (0..=per_side.floor() as usize)
+        .rev()
+        .find(|&weight| loadable[weight])
+        .map_or(bar_weight, loaded);
+    let heavier = (per_side.ceil() as usize..=limit)
+        .find(|&weight| loadable[weight])
+        .map_or(lighter, loaded);
+    if target - lighter <= heavier - target {
+        lighter
+    } else {
+        heavier
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
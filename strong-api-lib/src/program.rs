@@ -0,0 +1,344 @@
+//! Percentage based programs like 5/3/1 or GZCL.
+//!
+//! A `ProgramDefinition` lists the days of a program and, for every exercise, its sets of each
+//! week as percentages of the exercise's training max:
+//!
+//! ```toml
+//! name = "5/3/1"
+//!
+//! [[days]]
+//! name = "Bench"
+//!
+//! [[days.exercises]]
+//! measurement_id = "ca9ee259-a69f-4839-bbf9-46ba8cf0d7d6"
+//! training_max = 100
+//! weeks = [
+//!     [{ percent = 65, reps = 5 }, { percent = 75, reps = 5 }, { percent = 85, reps = 5 }],
+//!     [{ percent = 70, reps = 3 }, { percent = 80, reps = 3 }, { percent = 90, reps = 3 }],
+//! ]
+//! ```
+//!
+//! `ProgramGenerator` turns it into a mesocycle of workouts, one per week and day, with every
+//! weight rounded to what can be loaded with the user's `availablePlates`. Exercises are named
+//! and loaded like their measurement, if the generator is given the measurements.
+//! `DataTransformer::workout_to_template` turns them into template logs, to create with
+//! `StrongApi::create_template` or to export as JSON.
+
+use crate::analytics::one_rep_max::{OneRepMaxFormula, best_one_rep_maxes};
+use crate::data_transformer::aliases::ExerciseAliases;
+use crate::data_transformer::{Exercise, LoadType, Set, Workout};
+use crate::models::measurement::{Measurement, MeasurementsResponse};
+use crate::models::preferences::{CustomBarType, Preferences, WeightUnit};
+use crate::models::workout::UserResponse;
+use crate::plate_calculator::round_to_plates;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+
+/// A program as read from a definition file.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProgramDefinition {
+    pub name: String,
+    /// Unit of the training maxes given in the definition.
+    #[serde(default)]
+    pub weight_unit: WeightUnit,
+    pub days: Vec<ProgramDay>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProgramDay {
+    pub name: String,
+    pub exercises: Vec<ProgramExercise>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProgramExercise {
+    pub measurement_id: String,
+    /// Training max in the definition's `weight_unit`. Wins over the generator's training maxes.
+    #[serde(default)]
+    pub training_max: Option<f32>,
+    /// The sets of each week. An exercise with fewer weeks than the program starts over.
+    pub weeks: Vec<Vec<PercentageSet>>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PercentageSet {
+    /// Percent of the training max.
+    pub percent: f32,
+    pub reps: u32,
+    /// How often the set is repeated, e.g. 5 for GZCL's 5x3.
+    #[serde(default = "one")]
+    pub sets: u32,
+    #[serde(default)]
+    pub rpe: Option<f32>,
+}
+
+fn one() -> u32 {
+    1
+}
+
+impl ProgramDefinition {
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn from_toml(toml: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(toml)
+    }
+
+    /// Reads the definition from a `.json` file, anything else is read as TOML.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("json") => {
+                Ok(Self::from_json(&content)?)
+            }
+            _ => Ok(Self::from_toml(&content)?),
+        }
+    }
+
+    /// Length of the mesocycle, the most weeks any exercise has.
+    pub fn weeks(&self) -> usize {
+        self.days
+            .iter()
+            .flat_map(|day| &day.exercises)
+            .map(|exercise| exercise.weeks.len())
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Training maxes in kilograms by measurement id.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct TrainingMaxes {
+    by_measurement_id: HashMap<String, f32>,
+}
+
+impl TrainingMaxes {
+    /// Builds the training maxes from `(measurement id, kilograms)` pairs.
+    pub fn new<I, K>(training_maxes: I) -> Self
+    where
+        I: IntoIterator<Item = (K, f32)>,
+        K: Into<String>,
+    {
+        Self {
+            by_measurement_id: training_maxes
+                .into_iter()
+                .map(|(measurement_id, training_max)| (measurement_id.into(), training_max))
+                .collect(),
+        }
+    }
+
+    /// Derives the training maxes from the best estimated one-rep max of every measurement in
+    /// `workouts`, times `share`, e.g. 0.9 for 5/3/1.
+    pub fn from_history(workouts: &[Workout], formula: OneRepMaxFormula, share: f32) -> Self {
+        let mut by_measurement_id: HashMap<String, f32> = HashMap::new();
+        for best in workouts
+            .iter()
+            .flat_map(best_one_rep_maxes)
+            .filter(|best| best.formula == formula && !best.measurement_id.is_empty())
+        {
+            let training_max = by_measurement_id.entry(best.measurement_id).or_default();
+            *training_max = training_max.max(best.one_rep_max * share);
+        }
+        Self { by_measurement_id }
+    }
+
+    /// Sets the training max of one measurement, in kilograms.
+    pub fn with_training_max(mut self, measurement_id: impl Into<String>, kilograms: f32) -> Self {
+        self.by_measurement_id
+            .insert(measurement_id.into(), kilograms);
+        self
+    }
+
+    pub fn get(&self, measurement_id: &str) -> Option<f32> {
+        self.by_measurement_id.get(measurement_id).copied()
+    }
+}
+
+/// An exercise of the program without a training max.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissingTrainingMax {
+    pub day: String,
+    pub measurement_id: String,
+}
+
+impl fmt::Display for MissingTrainingMax {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "no training max for measurement {} on day '{}'",
+            self.measurement_id, self.day
+        )
+    }
+}
+
+impl std::error::Error for MissingTrainingMax {}
+
+/// Generates the workouts of a program from training maxes and the user's settings.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct ProgramGenerator {
+    training_maxes: TrainingMaxes,
+    user_id: String,
+    preferences: Preferences,
+    custom_bar_types: Vec<CustomBarType>,
+    measurements: Vec<Measurement>,
+    exercise_aliases: ExerciseAliases,
+    locales: Vec<String>,
+}
+
+impl ProgramGenerator {
+    /// Without a user, weights are in kilograms and not rounded.
+    pub fn new(training_maxes: TrainingMaxes) -> Self {
+        Self {
+            training_maxes,
+            ..Default::default()
+        }
+    }
+
+    /// Takes weight units, bar weights and `availablePlates` from the user's preferences.
+    pub fn with_user(mut self, user: &UserResponse) -> Self {
+        self.user_id = user.id.clone();
+        self.preferences = user.preferences.clone();
+        self.custom_bar_types = user.legacy_custom_bar_types.clone();
+        self
+    }
+
+    /// Takes exercise names and load types from the measurements. Without them exercises have
+    /// no name and are loaded like a barbell.
+    pub fn with_measurements_response(
+        mut self,
+        measurements_response: MeasurementsResponse,
+    ) -> Self {
+        self.measurements = measurements_response.embedded.measurements;
+        self
+    }
+
+    /// Sets the aliases the canonical names of the exercises are taken from.
+    pub fn with_exercise_aliases(mut self, exercise_aliases: ExerciseAliases) -> Self {
+        self.exercise_aliases = exercise_aliases;
+        self
+    }
+
+    /// Sets the locales to take exercise names from, most preferred first, like
+    /// `DataTransformer::with_locales`.
+    pub fn with_locales<S: Into<String>>(mut self, locales: impl IntoIterator<Item = S>) -> Self {
+        self.locales = locales.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// The mesocycle of `definition`, week by week and day by day. Workouts are named
+    /// `<program> - Week <n> - <day>` and, like their exercises and sets, have no ids yet.
+    pub fn generate(
+        &self,
+        definition: &ProgramDefinition,
+    ) -> Result<Vec<Workout>, MissingTrainingMax> {
+        let plates = self.preferences.available_plates(&self.user_id);
+        let mut workouts = Vec::new();
+        for week in 0..definition.weeks() {
+            for day in &definition.days {
+                let exercises = day
+                    .exercises
+                    .iter()
+                    .map(|exercise| self.exercise(definition, day, exercise, week, &plates))
+                    .collect::<Result<_, _>>()?;
+                workouts.push(Workout {
+                    name: format!("{} - Week {} - {}", definition.name, week + 1, day.name),
+                    exercises,
+                    ..Default::default()
+                });
+            }
+        }
+        Ok(workouts)
+    }
+
+    fn exercise(
+        &self,
+        definition: &ProgramDefinition,
+        day: &ProgramDay,
+        exercise: &ProgramExercise,
+        week: usize,
+        plates: &[String],
+    ) -> Result<Exercise, MissingTrainingMax> {
+        let measurement_id = exercise.measurement_id.as_str();
+        let training_max = exercise
+            .training_max
+            .map(|training_max| definition.weight_unit.to_kilograms(training_max))
+            .or_else(|| self.training_maxes.get(measurement_id))
+            .ok_or_else(|| MissingTrainingMax {
+                day: day.name.clone(),
+                measurement_id: measurement_id.to_string(),
+            })?;
+
+        // Like the transformer, names and load types come from the measurement.
+        let measurement = self
+            .measurements
+            .iter()
+            .find(|measurement| measurement.id == measurement_id);
+        let (name, name_locale) = measurement
+            .map(|measurement| {
+                measurement
+                    .name
+                    .localized(&self.locales)
+                    .map(|(name, locale)| (name.to_string(), locale.to_string()))
+                    .unwrap_or_else(|| ("Unknown".to_string(), String::new()))
+            })
+            .unwrap_or_default();
+        let canonical_name = self.exercise_aliases.canonical_name(measurement_id, &name);
+        let load_type = measurement.map_or(LoadType::External, |measurement| {
+            measurement
+                .cell_type_configs
+                .iter()
+                .find_map(|config| LoadType::from_cell_type(&config.cell_type))
+                .unwrap_or(LoadType::Unweighted)
+        });
+
+        let weight_unit = match load_type {
+            LoadType::AssistedBodyweight => self
+                .preferences
+                .resistance_weight_unit(&self.user_id, measurement_id),
+            _ => self.preferences.weight_unit(&self.user_id, measurement_id),
+        };
+        // Only external loads go on a bar.
+        let bar_weight = match load_type {
+            LoadType::External => {
+                self.preferences
+                    .bar_weight(&self.user_id, measurement_id, &self.custom_bar_types)
+            }
+            _ => None,
+        };
+        let sets = match exercise.weeks.len() {
+            0 => &[][..],
+            weeks => &exercise.weeks[week % weeks],
+        };
+
+        Ok(Exercise {
+            measurement_id: measurement_id.to_string(),
+            name,
+            name_locale,
+            canonical_name,
+            sets: sets
+                .iter()
+                .flat_map(|set| {
+                    let target = training_max * set.percent / 100.0;
+                    // Strong logs weights including the bar, so the loaded weight is logged.
+                    let weight = round_to_plates(target, bar_weight.unwrap_or(0.0), plates);
+                    let planned = Set {
+                        weight: Some(weight),
+                        weight_unit,
+                        load_type,
+                        bar_weight,
+                        reps: set.reps,
+                        rpe: set.rpe,
+                        ..Default::default()
+                    };
+                    std::iter::repeat_n(planned, set.sets as usize)
+                })
+                .collect(),
+            ..Default::default()
+        })
+    }
+}
@@ -0,0 +1,98 @@
+//! Grouping of workouts, e.g. the result of a `WorkoutQuery`, to run analytics per group.
+//!
+//! Groups keep the order of the workouts they were taken from and are sorted by key. Grouping
+//! by exercise or tag narrows each workout down to the exercises of its group, so a workout
+//! with squats and curls is in both the `Squat` and the `Curl` group, with one exercise each.
+
+use crate::analytics::calendar::{month_start, week_start};
+use crate::data_transformer::{Exercise, Workout};
+use crate::models::measurement::MeasurementsResponse;
+use std::collections::{BTreeMap, HashMap};
+use time::{Date, Weekday};
+
+/// Groups the workouts by the key `key` returns for them, workouts without a key are left out.
+pub fn group_by<K: Ord>(
+    workouts: &[Workout],
+    mut key: impl FnMut(&Workout) -> Option<K>,
+) -> BTreeMap<K, Vec<Workout>> {
+    let mut groups: BTreeMap<K, Vec<Workout>> = BTreeMap::new();
+    for workout in workouts {
+        if let Some(key) = key(workout) {
+            groups.entry(key).or_default().push(workout.clone());
+        }
+    }
+    groups
+}
+
+/// Groups the exercises of every workout by the keys `keys` returns for them. Each group has
+/// the workouts with at least one of its exercises, holding only those.
+pub fn group_exercises_by<K: Ord + Clone>(
+    workouts: &[Workout],
+    mut keys: impl FnMut(&Workout, &Exercise) -> Vec<K>,
+) -> BTreeMap<K, Vec<Workout>> {
+    let mut groups: BTreeMap<K, Vec<Workout>> = BTreeMap::new();
+    for workout in workouts {
+        let mut exercises: BTreeMap<K, Vec<Exercise>> = BTreeMap::new();
+        for exercise in &workout.exercises {
+            for key in keys(workout, exercise) {
+                exercises.entry(key).or_default().push(exercise.clone());
+            }
+        }
+        for (key, exercises) in exercises {
+            groups.entry(key).or_default().push(Workout {
+                exercises,
+                ..workout.clone()
+            });
+        }
+    }
+    groups
+}
+
+/// Workouts by the first day of the week they started in, in their own timezone.
+pub fn group_by_week(workouts: &[Workout], first_weekday: Weekday) -> BTreeMap<Date, Vec<Workout>> {
+    group_by(workouts, |workout| {
+        workout
+            .local_date()
+            .map(|date| week_start(date, first_weekday))
+    })
+}
+
+/// Workouts by the first day of the month they started in, in their own timezone.
+pub fn group_by_month(workouts: &[Workout]) -> BTreeMap<Date, Vec<Workout>> {
+    group_by(workouts, |workout| workout.local_date().map(month_start))
+}
+
+pub fn group_by_workout_name(workouts: &[Workout]) -> BTreeMap<String, Vec<Workout>> {
+    group_by(workouts, |workout| Some(workout.name.clone()))
+}
+
+/// Workouts by exercise `canonical_name`.
+pub fn group_by_exercise(workouts: &[Workout]) -> BTreeMap<String, Vec<Workout>> {
+    group_exercises_by(workouts, |_, exercise| {
+        vec![exercise.canonical_name.clone()]
+    })
+}
+
+/// Workouts by the tags of their exercises' measurements in `measurements`. An exercise is in
+/// the group of each of its tags, exercises without tags are left out.
+pub fn group_by_tag(
+    workouts: &[Workout],
+    measurements: &MeasurementsResponse,
+) -> BTreeMap<String, Vec<Workout>> {
+    let tags: HashMap<&str, Vec<String>> = measurements
+        .embedded
+        .measurements
+        .iter()
+        .map(|measurement| {
+            (
+                measurement.id.as_str(),
+                measurement.tags().map(str::to_string).collect(),
+            )
+        })
+        .collect();
+    group_exercises_by(workouts, |_, exercise| {
+        tags.get(exercise.measurement_id.as_str())
+            .cloned()
+            .unwrap_or_default()
+    })
+}
@@ -0,0 +1,383 @@
+//! Filtering of transformed `Workout`s with composable predicates.
+//!
+//! A `WorkoutQuery` narrows workouts down on three levels: workouts by their local start date
+//! and name, exercises by name, measurement id or tag, and sets by load, RPE and whether they
+//! were completed. Different predicates must all hold, several values of one predicate (like
+//! two exercises) match if any of them does. Queries are built with `with_*` or parsed from a
+//! string like `exercise:"Squat (Barbell)" since:2024-01-01 load:100.. completed`, see
+//! `WorkoutQuery::parse()`. `group` splits the result by week, month, exercise and more.
+
+pub mod group;
+
+use crate::analytics::comparison::Period;
+use crate::data_transformer::{Exercise, Set, Workout};
+use crate::models::measurement::MeasurementsResponse;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use time::Date;
+use time::macros::format_description;
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct WorkoutQuery {
+    since: Option<Date>,
+    until: Option<Date>,
+    name_pattern: Option<String>,
+    /// Lowercase.
+    exercises: Vec<String>,
+    measurement_ids: Vec<String>,
+    tags: Vec<String>,
+    min_load: Option<f32>,
+    max_load: Option<f32>,
+    min_rpe: Option<f32>,
+    max_rpe: Option<f32>,
+    completed_only: bool,
+    /// Tags by measurement id, from `with_measurements()`.
+    measurement_tags: HashMap<String, Vec<String>>,
+}
+
+impl WorkoutQuery {
+    /// A query matching every workout.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a query of whitespace separated terms, values with spaces in double quotes:
+    ///
+    /// - `since:2024-01-01` and `until:2024-12-31`, local start dates, both included
+    /// - `name:"Push*"`, the workout name, `*` matching any text and `?` one character
+    /// - `exercise:Squat`, the exercise's canonical or original name
+    /// - `measurement:<id>` and `tag:legs`, the latter needs `with_measurements()`
+    /// - `load:100..140` and `rpe:7..9`, either bound may be left out (`load:..60`) and a
+    ///   single number matches exactly
+    /// - `completed`, only completed sets
+    pub fn parse(query: &str) -> Result<Self, InvalidQuery> {
+        let mut parsed = Self::new();
+        for term in terms(query)? {
+            let invalid = |reason: &str| InvalidQuery {
+                term: term.clone(),
+                reason: reason.to_string(),
+            };
+            if term == "completed" {
+                parsed = parsed.with_completed_only(true);
+                continue;
+            }
+            let (key, value) = term
+                .split_once(':')
+                .ok_or_else(|| invalid("expected key:value"))?;
+            let value = value.trim_matches('"');
+            if value.is_empty() {
+                return Err(invalid("empty value"));
+            }
+            parsed = match key {
+                "since" => {
+                    parsed.with_since(parse_date(value).ok_or_else(|| invalid("not a date"))?)
+                }
+                "until" => {
+                    parsed.with_until(parse_date(value).ok_or_else(|| invalid("not a date"))?)
+                }
+                "name" => parsed.with_name_pattern(value),
+                "exercise" => parsed.with_exercise(value),
+                "measurement" => parsed.with_measurement_id(value),
+                "tag" => parsed.with_tag(value),
+                "load" => {
+                    let (min, max) = parse_range(value).ok_or_else(|| invalid("not a range"))?;
+                    parsed.with_load_range(min, max)
+                }
+                "rpe" => {
+                    let (min, max) = parse_range(value).ok_or_else(|| invalid("not a range"))?;
+                    parsed.with_rpe_range(min, max)
+                }
+                _ => return Err(invalid("unknown key")),
+            };
+        }
+        Ok(parsed)
+    }
+
+    /// Workouts starting on or after `date`, in their own timezone.
+    pub fn with_since(mut self, date: Date) -> Self {
+        self.since = Some(date);
+        self
+    }
+
+    /// Workouts starting on or before `date`, in their own timezone.
+    pub fn with_until(mut self, date: Date) -> Self {
+        self.until = Some(date);
+        self
+    }
+
+    pub fn with_period(self, period: Period) -> Self {
+        self.with_since(period.start).with_until(period.end)
+    }
+
+    /// Workouts whose name matches `pattern`, ignoring case. `*` matches any text and `?` any
+    /// single character.
+    pub fn with_name_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.name_pattern = Some(pattern.into().to_lowercase());
+        self
+    }
+
+    /// Exercises whose `canonical_name` or `name` is `name`, ignoring case.
+    pub fn with_exercise(mut self, name: impl Into<String>) -> Self {
+        self.exercises.push(name.into().to_lowercase());
+        self
+    }
+
+    pub fn with_measurement_id(mut self, measurement_id: impl Into<String>) -> Self {
+        self.measurement_ids.push(measurement_id.into());
+        self
+    }
+
+    /// Exercises whose measurement has `tag`, like a muscle group, ignoring case. Tags are
+    /// taken from `with_measurements()`, without them no exercise has a tag.
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into().to_lowercase());
+        self
+    }
+
+    /// Takes the tags of the measurements for `with_tag()`.
+    pub fn with_measurements(mut self, measurements: &MeasurementsResponse) -> Self {
+        self.measurement_tags = measurements
+            .embedded
+            .measurements
+            .iter()
+            .map(|measurement| {
+                (
+                    measurement.id.clone(),
+                    measurement.tags().map(str::to_lowercase).collect(),
+                )
+            })
+            .collect();
+        self
+    }
+
+    /// Sets whose `effective_load` is within the bounds in kilograms, both included. Sets
+    /// without a load don't match a load range.
+    pub fn with_load_range(mut self, min: Option<f32>, max: Option<f32>) -> Self {
+        self.min_load = min;
+        self.max_load = max;
+        self
+    }
+
+    /// Sets whose RPE is within the bounds, both included. Sets without an RPE don't match an
+    /// RPE range.
+    pub fn with_rpe_range(mut self, min: Option<f32>, max: Option<f32>) -> Self {
+        self.min_rpe = min;
+        self.max_rpe = max;
+        self
+    }
+
+    pub fn with_completed_only(mut self, completed_only: bool) -> Self {
+        self.completed_only = completed_only;
+        self
+    }
+
+    /// Whether the workout's start date and name match, regardless of its exercises. Workouts
+    /// without a start date don't match a date range.
+    pub fn matches_workout(&self, workout: &Workout) -> bool {
+        if self.since.is_some() || self.until.is_some() {
+            let Some(date) = workout.local_date() else {
+                return false;
+            };
+            if self.since.is_some_and(|since| date < since)
+                || self.until.is_some_and(|until| date > until)
+            {
+                return false;
+            }
+        }
+        self.name_pattern.as_ref().is_none_or(|pattern| {
+            let pattern: Vec<char> = pattern.chars().collect();
+            let name: Vec<char> = workout.name.to_lowercase().chars().collect();
+            glob_match(&pattern, &name)
+        })
+    }
+
+    /// Whether the exercise's name, measurement id and tags match, regardless of its sets.
+    pub fn matches_exercise(&self, exercise: &Exercise) -> bool {
+        let names_match = self.exercises.is_empty()
+            || self.exercises.iter().any(|name| {
+                *name == exercise.canonical_name.to_lowercase()
+                    || *name == exercise.name.to_lowercase()
+            });
+        let ids_match = self.measurement_ids.is_empty()
+            || self.measurement_ids.contains(&exercise.measurement_id);
+        let tags_match = self.tags.is_empty()
+            || self
+                .measurement_tags
+                .get(&exercise.measurement_id)
+                .is_some_and(|tags| tags.iter().any(|tag| self.tags.contains(tag)));
+        names_match && ids_match && tags_match
+    }
+
+    pub fn matches_set(&self, set: &Set) -> bool {
+        let in_range = |value: Option<f32>, min: Option<f32>, max: Option<f32>| {
+            if min.is_none() && max.is_none() {
+                return true;
+            }
+            value.is_some_and(|value| {
+                min.is_none_or(|min| value >= min) && max.is_none_or(|max| value <= max)
+            })
+        };
+        (!self.completed_only || set.is_completed)
+            && in_range(set.effective_load, self.min_load, self.max_load)
+            && in_range(set.rpe, self.min_rpe, self.max_rpe)
+    }
+
+    /// The workout with only its matching exercises and sets, `None` if it doesn't match.
+    /// Exercises left without sets are dropped when sets are filtered, and workouts left
+    /// without exercises when exercises or sets are.
+    pub fn filter_workout(&self, workout: &Workout) -> Option<Workout> {
+        if !self.matches_workout(workout) {
+            return None;
+        }
+        let filters_sets = self.filters_sets();
+        let exercises: Vec<Exercise> = workout
+            .exercises
+            .iter()
+            .filter(|exercise| self.matches_exercise(exercise))
+            .filter_map(|exercise| {
+                let sets: Vec<Set> = exercise
+                    .sets
+                    .iter()
+                    .filter(|set| self.matches_set(set))
+                    .cloned()
+                    .collect();
+                (!filters_sets || !sets.is_empty()).then(|| Exercise {
+                    sets,
+                    ..exercise.clone()
+                })
+            })
+            .collect();
+        if exercises.is_empty() && (filters_sets || self.filters_exercises()) {
+            return None;
+        }
+        Some(Workout {
+            exercises,
+            ..workout.clone()
+        })
+    }
+
+    /// The matching workouts, narrowed to their matching exercises and sets, in the order of
+    /// `workouts`.
+    pub fn apply(&self, workouts: &[Workout]) -> Vec<Workout> {
+        workouts
+            .iter()
+            .filter_map(|workout| self.filter_workout(workout))
+            .collect()
+    }
+
+    fn filters_exercises(&self) -> bool {
+        !self.exercises.is_empty() || !self.measurement_ids.is_empty() || !self.tags.is_empty()
+    }
+
+    fn filters_sets(&self) -> bool {
+        self.completed_only
+            || self.min_load.is_some()
+            || self.max_load.is_some()
+            || self.min_rpe.is_some()
+            || self.max_rpe.is_some()
+    }
+}
+
+impl FromStr for WorkoutQuery {
+    type Err = InvalidQuery;
+
+    fn from_str(query: &str) -> Result<Self, Self::Err> {
+        Self::parse(query)
+    }
+}
+
+/// A term of a query string that couldn't be parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidQuery {
+    pub term: String,
+    pub reason: String,
+}
+
+impl fmt::Display for InvalidQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid query term '{}': {}", self.term, self.reason)
+    }
+}
+
+impl std::error::Error for InvalidQuery {}
+
+/// Splits a query at whitespace outside of double quotes.
+fn terms(query: &str) -> Result<Vec<String>, InvalidQuery> {
+    let mut terms = Vec::new();
+    let mut term = String::new();
+    let mut quoted = false;
+    for character in query.chars() {
+        match character {
+            '"' => {
+                quoted = !quoted;
+                term.push(character);
+            }
+            character if character.is_whitespace() && !quoted => {
+                if !term.is_empty() {
+                    terms.push(std::mem::take(&mut term));
+                }
+            }
+            character => term.push(character),
+        }
+    }
+    if quoted {
+        return Err(InvalidQuery {
+            term,
+            reason: "unclosed quote".to_string(),
+        });
+    }
+    if !term.is_empty() {
+        terms.push(term);
+    }
+    Ok(terms)
+}
+
+fn parse_date(value: &str) -> Option<Date> {
+    Date::parse(value, format_description!("[year]-[month]-[day]")).ok()
+}
+
+/// Parses `min..max`, `min..`, `..max` or a single number.
+fn parse_range(value: &str) -> Option<(Option<f32>, Option<f32>)> {
+    let bound = |bound: &str| -> Option<Option<f32>> {
+        if bound.is_empty() {
+            Some(None)
+        } else {
+            bound.parse().ok().map(Some)
+        }
+    };
+    match value.split_once("..") {
+        Some((min, max)) => match (bound(min)?, bound(max)?) {
+            (None, None) => None,
+            range => Some(range),
+        },
+        None => {
+            let exact = value.parse().ok()?;
+            Some((Some(exact), Some(exact)))
+        }
+    }
+}
+
+/// Matches `text` against a pattern with `*` for any text and `?` for any character.
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text position it was tried at.
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            // Let the last `*` take one more character.
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|character| *character == '*')
+}
@@ -1,7 +1,7 @@
 use crate::models::auth::LoginResponse;
 use crate::models::error::ApiErrorResponse;
 use crate::models::measurement::MeasurementsResponse;
-use crate::models::workout::UserResponse;
+use crate::models::workout::{Log, UserResponse};
 use reqwest::{
     Client, Url,
     header::{HeaderMap, HeaderName, HeaderValue},
@@ -279,4 +279,37 @@ impl StrongApi {
 
         Ok(response_text)
     }
+
+    /// Creates a template, e.g. one built with `DataTransformer::workout_to_template`, and
+    /// returns it as stored by the backend.
+    pub async fn create_template(&self, template: &Log) -> Result<Log, Box<dyn std::error::Error>> {
+        let user_id = self
+            .user_id
+            .as_ref()
+            .ok_or("Missing user id. Use `login` before calling `create_template`")?;
+        let url = self
+            .url
+            .join(&format!("api/users/{user_id}/templates"))
+            .expect("joining api/users/{user_id}/templates has failed, check the base URL");
+
+        let response = self
+            .client
+            .post(url)
+            .bearer_auth(self.access_token.as_ref().ok_or("Missing access token")?)
+            .headers(self.headers.clone())
+            .json(template)
+            .send()
+            .await?;
+
+        let status = response.status();
+        let response_text = response.text().await.expect("failed to read response body");
+
+        if !status.is_success() {
+            let api_error: ApiErrorResponse = serde_json::from_str(&response_text)?;
+            return Err(Box::new(api_error));
+        }
+
+        let parsed: Log = serde_json::from_str(&response_text)?;
+        Ok(parsed)
+    }
 }
@@ -0,0 +1,187 @@
+use strong_api_lib::analytics::adherence::{
+    DEFAULT_MIN_SCORE, MatchMethod, match_template, template_adherence,
+};
+use strong_api_lib::data_transformer::{DataTransformer, Workout};
+use strong_api_lib::models::workout::UserResponse;
+
+mod common;
+use common::{
+    SetExt, WorkoutExt, bodyweight_set, exercise, load_fixture, measurements_from_fixture, set,
+    target_set, template, workout,
+};
+
+fn templates() -> Vec<Workout> {
+    // Three sets of 10 of every exercise.
+    let planned = |id: &str| exercise(id, &id.to_uppercase(), vec![target_set(10); 3]);
+    vec![
+        template(
+            "push",
+            "Push",
+            vec![planned("bench"), planned("press"), planned("dips")],
+        ),
+        template(
+            "pull",
+            "Pull",
+            vec![planned("row"), planned("chin"), planned("curl")],
+        ),
+    ]
+}
+
+// ---------------------------------------------------------------------------
+// Matching
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_linked_template_wins() {
+    let mut done = workout(
+        "w1",
+        "2024-05-01T10:00:00Z",
+        vec![exercise("row", "ROW", vec![bodyweight_set(10)])],
+    )
+    .with_name("Pull");
+    done.template_id = Some("push".to_string());
+
+    let matched = match_template(&done, &templates(), DEFAULT_MIN_SCORE).unwrap();
+    assert_eq!(matched.template_id, "push");
+    assert_eq!(matched.method, MatchMethod::Link);
+    assert_eq!(matched.score, 1.0);
+}
+
+#[test]
+fn test_similar_name_and_sequence_is_matched() {
+    let done = workout(
+        "w1",
+        "2024-05-01T10:00:00Z",
+        vec![
+            exercise("row", "ROW", vec![bodyweight_set(10)]),
+            exercise("curl", "CURL", vec![bodyweight_set(10)]),
+        ],
+    )
+    .with_name("pull ");
+
+    let matched = match_template(&done, &templates(), DEFAULT_MIN_SCORE).unwrap();
+    assert_eq!(matched.template_id, "pull");
+    assert_eq!(matched.method, MatchMethod::Similarity);
+    // Same name, and 2 of 3 exercises in the same order.
+    assert!((matched.score - (0.5 + 0.5 * 2.0 / 3.0)).abs() < 0.001);
+}
+
+#[test]
+fn test_unknown_link_falls_back_to_similarity() {
+    let mut done = workout(
+        "w1",
+        "2024-05-01T10:00:00Z",
+        vec![
+            exercise("bench", "BENCH", vec![bodyweight_set(10)]),
+            exercise("press", "PRESS", vec![bodyweight_set(10)]),
+            exercise("dips", "DIPS", vec![bodyweight_set(10)]),
+        ],
+    )
+    .with_name("Monday");
+    done.template_id = Some("deleted".to_string());
+
+    let matched = match_template(&done, &templates(), DEFAULT_MIN_SCORE).unwrap();
+    assert_eq!(matched.template_id, "push");
+    assert_eq!(matched.method, MatchMethod::Similarity);
+    assert_eq!(matched.score, 0.5);
+}
+
+#[test]
+fn test_dissimilar_workouts_match_no_template() {
+    let done = workout(
+        "w1",
+        "2024-05-01T10:00:00Z",
+        vec![exercise("squat", "SQUAT", vec![bodyweight_set(5)])],
+    )
+    .with_name("Legs");
+
+    assert_eq!(match_template(&done, &templates(), DEFAULT_MIN_SCORE), None);
+    assert!(template_adherence(&[done], &templates(), DEFAULT_MIN_SCORE).is_empty());
+}
+
+// ---------------------------------------------------------------------------
+// Deltas
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_deltas_skipped_and_added_exercises() {
+    let mut planned = templates();
+    planned[0].exercises[0].sets = vec![set(80.0, 5).incomplete(); 3];
+    let done = workout(
+        "w1",
+        "2024-05-01T10:00:00Z",
+        vec![
+            exercise(
+                "bench",
+                "BENCH",
+                vec![set(82.5, 5), set(82.5, 5), set(82.5, 3), set(82.5, 5)],
+            ),
+            // Only incomplete sets, counts as skipped.
+            exercise("press", "PRESS", vec![bodyweight_set(10).incomplete()]),
+            exercise("dips", "DIPS", vec![bodyweight_set(12), bodyweight_set(8)]),
+            exercise("flyes", "FLYES", vec![bodyweight_set(15)]),
+        ],
+    )
+    .with_name("Push");
+
+    let adherence = template_adherence(&[done], &planned, DEFAULT_MIN_SCORE);
+    assert_eq!(adherence.len(), 1);
+    let adherence = &adherence[0];
+    assert_eq!(adherence.template.template_id, "push");
+
+    let bench = &adherence.exercises[0];
+    assert_eq!((bench.planned_sets, bench.done_sets), (3, 4));
+    assert_eq!((bench.planned_reps, bench.done_reps), (15, 18));
+    assert_eq!(bench.set_delta(), 1);
+    assert_eq!(bench.rep_delta(), 3);
+    assert_eq!(bench.load_delta(), Some(2.5));
+
+    let dips = &adherence.exercises[1];
+    assert_eq!(dips.set_delta(), -1);
+    assert_eq!(dips.rep_delta(), -10);
+    assert_eq!(dips.load_delta(), None);
+
+    assert_eq!(adherence.skipped, vec!["PRESS"]);
+    assert_eq!(adherence.added, vec!["FLYES"]);
+    assert_eq!(
+        adherence.to_string(),
+        "Push (2024-05-01T10:00:00Z) from template 'Push' by similarity (0.88): 2 exercise(s), 1 skipped, 1 added\
+         \n  BENCH: +1 set(s), +3 rep(s), +2.5 kg\
+         \n  DIPS: -1 set(s), -10 rep(s)\
+         \n  PRESS: skipped\
+         \n  FLYES: added"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Fixtures
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_template_links_of_fixture_logs() {
+    let user: UserResponse =
+        serde_json::from_str(&load_fixture("user_response_all_includes.json")).unwrap();
+    let measurements = measurements_from_fixture();
+    let transformer = DataTransformer::new().with_measurements_response(measurements);
+
+    let workouts = transformer
+        .get_measurements_from_logs(&user.embedded.log)
+        .unwrap();
+    let linked = workouts
+        .iter()
+        .find(|workout| workout.id == "4bb4081f-a729-4016-96c1-b837ae05100f")
+        .unwrap();
+    assert_eq!(
+        linked.template_id.as_deref(),
+        Some("328042b7-335e-4d61-8f3f-eaef8597d869")
+    );
+    assert!(workouts.iter().any(|workout| workout.template_id.is_none()));
+
+    // The linked templates aren't part of the fixture, so matching falls back to similarity.
+    let templates = transformer
+        .get_measurements_from_logs(&Some(user.templates()))
+        .unwrap();
+    for adherence in template_adherence(&workouts, &templates, 0.0) {
+        assert_eq!(adherence.template.method, MatchMethod::Similarity);
+    }
+}
@@ -0,0 +1,82 @@
+use strong_api_lib::data_transformer::aliases::ExerciseAliases;
+
+const BENCH_ID: &str = "4a304d9a-9a7a-440f-bb9a-9fd5cde6b590";
+
+// ---------------------------------------------------------------------------
+// Parsing
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_aliases_from_toml() {
+    let aliases = ExerciseAliases::from_toml(&format!(
+        r#"
+        "Bench" = "Bench Press (Barbell)"
+        "{BENCH_ID}" = "Bench Press (Barbell)"
+        "#
+    ))
+    .unwrap();
+    assert_eq!(aliases.canonical_name("", "Bench"), "Bench Press (Barbell)");
+    assert_eq!(
+        aliases.canonical_name(BENCH_ID, "Flat Bench"),
+        "Bench Press (Barbell)"
+    );
+}
+
+#[test]
+fn test_aliases_from_json() {
+    let aliases = ExerciseAliases::from_json(r#"{"Bench": "Bench Press (Barbell)"}"#).unwrap();
+    assert_eq!(aliases.canonical_name("", "Bench"), "Bench Press (Barbell)");
+}
+
+#[test]
+fn test_invalid_aliases_are_rejected() {
+    assert!(ExerciseAliases::from_json(r#"{"Bench": 1}"#).is_err());
+    assert!(ExerciseAliases::from_toml("Bench = ").is_err());
+}
+
+#[test]
+fn test_aliases_from_file_by_extension() {
+    let dir = std::env::temp_dir().join(format!("aliases-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let json = dir.join("aliases.json");
+    let toml = dir.join("aliases.toml");
+    std::fs::write(&json, r#"{"Bench": "Bench Press (Barbell)"}"#).unwrap();
+    std::fs::write(&toml, r#"Squat = "Squat (Barbell)""#).unwrap();
+
+    let from_json = ExerciseAliases::from_file(&json).unwrap();
+    let from_toml = ExerciseAliases::from_file(&toml).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        from_json.canonical_name("", "Bench"),
+        "Bench Press (Barbell)"
+    );
+    assert_eq!(from_toml.canonical_name("", "Squat"), "Squat (Barbell)");
+}
+
+// ---------------------------------------------------------------------------
+// Resolution
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_names_match_ignoring_case_and_whitespace() {
+    let aliases = ExerciseAliases::new([("Bench", "Bench Press (Barbell)")]);
+    assert_eq!(
+        aliases.canonical_name("", "  bench "),
+        "Bench Press (Barbell)"
+    );
+}
+
+#[test]
+fn test_measurement_id_wins_over_name() {
+    let aliases = ExerciseAliases::new([(BENCH_ID, "Bench Press"), ("Bench", "Something Else")]);
+    assert_eq!(aliases.canonical_name(BENCH_ID, "Bench"), "Bench Press");
+}
+
+#[test]
+fn test_exercises_without_alias_keep_their_name() {
+    let aliases = ExerciseAliases::new([("Bench", "Bench Press (Barbell)")]);
+    assert_eq!(aliases.canonical_name("m1", "Deadlift"), "Deadlift");
+    assert!(ExerciseAliases::default().is_empty());
+    assert!(!aliases.is_empty());
+}
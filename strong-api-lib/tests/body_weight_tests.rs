@@ -0,0 +1,72 @@
+use serde_json::json;
+use strong_api_lib::data_transformer::body_weight::BodyWeight;
+use strong_api_lib::models::measured_value::MeasuredValue;
+use strong_api_lib::models::preferences::WeightUnit;
+use time::macros::datetime;
+
+fn measured_value(measurement_id: &str, date: &str, value: &str) -> MeasuredValue {
+    serde_json::from_value(json!({
+        "_links": {
+            "measurement": { "href": format!("/api/users/u1/measurements/{measurement_id}") }
+        },
+        "id": format!("mv-{date}"),
+        "created": "2024-01-01T00:00:00Z",
+        "date": date,
+        "cells": [{ "id": "c1", "cellType": "BODYWEIGHT", "value": value }]
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_measured_value_deserializes_leniently() {
+    let measured_value: MeasuredValue = serde_json::from_value(json!({ "id": "mv-1" })).unwrap();
+    assert_eq!(measured_value.id, "mv-1");
+    assert!(measured_value.links.measurement.is_none());
+    assert!(measured_value.cells.is_empty());
+}
+
+#[test]
+fn test_from_measured_values_filters_by_measurement() {
+    let values = vec![
+        measured_value("body-weight", "2024-03-01T08:00:00Z", "80,5"),
+        measured_value("body-fat", "2024-03-01T08:00:00Z", "15"),
+    ];
+    let body_weights =
+        BodyWeight::from_measured_values(&values, "body-weight", WeightUnit::Kilograms);
+
+    assert_eq!(
+        body_weights,
+        vec![BodyWeight {
+            measured_at: datetime!(2024-03-01 08:00:00 UTC),
+            weight: 80.5,
+        }]
+    );
+}
+
+#[test]
+fn test_from_measured_values_converts_pounds() {
+    let values = vec![measured_value("bw", "2024-03-01T08:00:00Z", "176.37")];
+    let body_weights = BodyWeight::from_measured_values(&values, "bw", WeightUnit::Pounds);
+    assert!((body_weights[0].weight - 80.0).abs() < 1e-2);
+}
+
+#[test]
+fn test_from_measured_values_falls_back_to_created_and_skips_garbage() {
+    let mut without_date = measured_value("bw", "", "82");
+    without_date.date = None;
+    let unparsable = measured_value("bw", "2024-03-02T08:00:00Z", "heavy");
+    let bad_date = measured_value("bw", "yesterday", "81");
+
+    let body_weights = BodyWeight::from_measured_values(
+        &[without_date, unparsable, bad_date],
+        "bw",
+        WeightUnit::Kilograms,
+    );
+
+    assert_eq!(body_weights.len(), 1);
+    assert_eq!(
+        body_weights[0].measured_at,
+        datetime!(2024-01-01 00:00:00 UTC)
+    );
+    assert_eq!(body_weights[0].weight, 82.0);
+}
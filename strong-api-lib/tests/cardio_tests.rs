@@ -0,0 +1,285 @@
+use serde_json::json;
+use strong_api_lib::analytics::cardio::{
+    CardioSet, StandardDistance, best_efforts, cardio_by_week, cardio_sets,
+};
+use strong_api_lib::data_transformer::DataTransformer;
+use strong_api_lib::models::preferences::DistanceUnit;
+use strong_api_lib::models::workout::Log;
+use time::Weekday;
+use time::macros::date;
+
+mod common;
+use common::{SetExt, cardio_set, exercise, set, user_from_fixture, workout};
+
+// ---------------------------------------------------------------------------
+// Pace and speed
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_pace_and_speed_in_units() {
+    let run = CardioSet {
+        exercise: "Running".to_string(),
+        distance: Some(DistanceUnit::Miles.to_kilometers(3.0)),
+        duration: Some(1800),
+        distance_unit: DistanceUnit::Miles,
+        ..Default::default()
+    };
+
+    assert!((run.pace().unwrap() - 372.823).abs() < 0.01);
+    assert!((run.speed().unwrap() - 9.656).abs() < 0.001);
+    assert!((run.pace_in(DistanceUnit::Miles).unwrap() - 600.0).abs() < 0.01);
+    assert!((run.speed_in(DistanceUnit::Miles).unwrap() - 6.0).abs() < 0.001);
+    assert_eq!(
+        run.to_string(),
+        "Running: 3.00 mi in 30:00 (10:00 /mi, 6.0 mi/h)"
+    );
+
+    let swim = CardioSet {
+        exercise: "Swimming".to_string(),
+        distance: Some(1.5),
+        duration: Some(2700),
+        distance_unit: DistanceUnit::Meters,
+        ..Default::default()
+    };
+    assert_eq!(
+        swim.to_string(),
+        "Swimming: 1500.00 m in 45:00 (30:00 /km, 2.0 km/h)"
+    );
+
+    let yoga = CardioSet {
+        exercise: "Yoga".to_string(),
+        duration: Some(3900),
+        ..Default::default()
+    };
+    assert_eq!(yoga.pace(), None);
+    assert_eq!(yoga.speed(), None);
+    assert_eq!(yoga.to_string(), "Yoga: in 1:05:00");
+}
+
+#[test]
+fn test_only_completed_sets_with_distance_or_duration_are_cardio() {
+    let incomplete = cardio_set(Some(5.0), Some(1500), DistanceUnit::Kilometers).incomplete();
+    let strength = set(100.0, 5);
+    let workouts = vec![workout(
+        "w1",
+        "2024-05-06T07:00:00Z",
+        vec![exercise(
+            "",
+            "Running",
+            vec![
+                incomplete,
+                strength,
+                cardio_set(Some(5.0), None, DistanceUnit::Kilometers),
+            ],
+        )],
+    )];
+
+    let sets = cardio_sets(&workouts);
+    assert_eq!(sets.len(), 1);
+    assert_eq!(sets[0].workout_id, "w1");
+    assert_eq!(sets[0].distance, Some(5.0));
+}
+
+// ---------------------------------------------------------------------------
+// Weeks
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_distance_and_time_per_week() {
+    let workouts = vec![
+        workout(
+            "w1",
+            "2024-05-06T07:00:00Z",
+            vec![exercise(
+                "",
+                "Running",
+                vec![
+                    cardio_set(Some(5.0), Some(1500), DistanceUnit::Kilometers),
+                    cardio_set(Some(1.0), Some(240), DistanceUnit::Kilometers),
+                ],
+            )],
+        ),
+        workout(
+            "w2",
+            "2024-05-11T07:00:00Z",
+            vec![exercise(
+                "",
+                "Yoga",
+                vec![cardio_set(None, Some(3600), DistanceUnit::Kilometers)],
+            )],
+        ),
+        workout(
+            "w3",
+            "2024-05-13T07:00:00Z",
+            vec![exercise(
+                "",
+                "Running",
+                vec![cardio_set(Some(10.0), Some(3000), DistanceUnit::Kilometers)],
+            )],
+        ),
+    ];
+
+    let weeks = cardio_by_week(&workouts, Weekday::Monday);
+    assert_eq!(weeks.len(), 2);
+    assert_eq!(weeks[0].start, date!(2024 - 05 - 06));
+    assert_eq!((weeks[0].iso_year, weeks[0].iso_week), (2024, 19));
+    assert_eq!((weeks[0].sessions, weeks[0].sets), (2, 3));
+    assert_eq!(weeks[0].distance, 6.0);
+    assert_eq!(weeks[0].duration, 5340);
+    assert_eq!(weeks[1].start, date!(2024 - 05 - 13));
+    assert!((weeks[1].distance_in(DistanceUnit::Miles) - 6.214).abs() < 0.001);
+
+    // Weeks starting on Sunday put the Saturday yoga and the Monday run in different weeks.
+    let weeks = cardio_by_week(&workouts, Weekday::Sunday);
+    assert_eq!(weeks[0].start, date!(2024 - 05 - 05));
+    assert_eq!(weeks[1].start, date!(2024 - 05 - 12));
+    assert_eq!(weeks[1].sessions, 1);
+}
+
+// ---------------------------------------------------------------------------
+// Best efforts
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_best_efforts_over_standard_distances() {
+    let workouts = vec![
+        workout(
+            "w1",
+            "2024-05-06T07:00:00Z",
+            vec![exercise(
+                "",
+                "Running",
+                vec![cardio_set(Some(5.0), Some(1500), DistanceUnit::Kilometers)],
+            )],
+        ),
+        workout(
+            "w2",
+            "2024-05-13T07:00:00Z",
+            vec![exercise(
+                "",
+                "Running",
+                vec![
+                    cardio_set(Some(10.0), Some(3300), DistanceUnit::Kilometers),
+                    cardio_set(Some(1.0), Some(240), DistanceUnit::Kilometers),
+                ],
+            )],
+        ),
+        workout(
+            "w3",
+            "2024-05-14T07:00:00Z",
+            vec![exercise(
+                "",
+                "Rowing",
+                vec![cardio_set(Some(2.0), Some(480), DistanceUnit::Meters)],
+            )],
+        ),
+    ];
+
+    let efforts = best_efforts(&workouts);
+    let summary: Vec<(&str, StandardDistance, &str, f32)> = efforts
+        .iter()
+        .map(|effort| {
+            (
+                effort.exercise.as_str(),
+                effort.distance,
+                effort.workout_id.as_str(),
+                effort.time.round(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("Rowing", StandardDistance::OneKilometer, "w3", 240.0),
+            ("Rowing", StandardDistance::OneMile, "w3", 386.0),
+            ("Running", StandardDistance::OneKilometer, "w2", 240.0),
+            ("Running", StandardDistance::OneMile, "w1", 483.0),
+            ("Running", StandardDistance::FiveKilometers, "w1", 1500.0),
+            ("Running", StandardDistance::TenKilometers, "w2", 3300.0),
+        ]
+    );
+    assert_eq!(
+        efforts[4].to_string(),
+        "Running five_kilometers: 25:00 (5:00 /km)"
+    );
+}
+
+#[test]
+fn test_a_mile_logged_in_miles_counts_as_one_mile() {
+    let workouts = vec![workout(
+        "w1",
+        "2024-05-06T07:00:00Z",
+        vec![exercise(
+            "",
+            "Running",
+            vec![cardio_set(
+                Some(DistanceUnit::Miles.to_kilometers(1.0)),
+                Some(420),
+                DistanceUnit::Miles,
+            )],
+        )],
+    )];
+
+    let efforts = best_efforts(&workouts);
+    assert_eq!(efforts.len(), 2);
+    assert_eq!(efforts[1].distance, StandardDistance::OneMile);
+    assert!((efforts[1].time - 420.0).abs() < 0.01);
+}
+
+// ---------------------------------------------------------------------------
+// Fixtures
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_cardio_sets_of_fixture_measurements() {
+    let user = user_from_fixture();
+    let swimming = "a8225200-79fb-4131-8ea2-c4df95f56c77";
+    let yoga = "6de1b274-f023-49f2-94ec-add08373b7e4";
+    let mut preferences = user.preferences.clone();
+    preferences
+        .distance_unit
+        .insert(swimming.to_string(), DistanceUnit::Meters);
+    let group = |id: &str, measurement_id: &str, cells: serde_json::Value| {
+        json!({
+            "id": id,
+            "_links": { "measurement": { "href": format!("/api/measurements/{measurement_id}") } },
+            "_embedded": {},
+            "cellSets": [{ "id": format!("{id}-set"), "isCompleted": true, "cells": cells }]
+        })
+    };
+    let log: Log = serde_json::from_value(json!({
+        "id": "log-cardio",
+        "_links": {},
+        "_embedded": { "cellSetGroup": [
+            group("swim", swimming, json!([
+                { "id": "c1", "cellType": "DISTANCE", "value": "1500" },
+                { "id": "c2", "cellType": "DURATION", "value": "30:00" }
+            ])),
+            group("yoga", yoga, json!([{ "id": "c3", "cellType": "DURATION", "value": "3600" }]))
+        ] },
+        "created": "2024-05-06T07:00:00Z",
+        "lastChanged": "2024-05-06T07:00:00Z",
+        "access": "private",
+        "startDate": "2024-05-06T07:00:00Z",
+        "endDate": "2024-05-06T08:30:00Z",
+        "logType": "WORKOUT"
+    }))
+    .unwrap();
+    let workouts = DataTransformer::new()
+        .with_preferences(&user.id, preferences)
+        .get_measurements_from_logs(&Some(vec![log]))
+        .unwrap();
+
+    let sets = cardio_sets(&workouts);
+    assert_eq!(sets.len(), 2);
+    assert_eq!(sets[0].measurement_id, swimming);
+    assert_eq!(sets[0].distance, Some(1.5));
+    assert_eq!(sets[0].distance_unit, DistanceUnit::Meters);
+    assert!((sets[0].pace().unwrap() - 1200.0).abs() < 0.01);
+    assert_eq!((sets[1].distance, sets[1].duration), (None, Some(3600)));
+
+    let weeks = cardio_by_week(&workouts, user.first_weekday());
+    assert_eq!(weeks.len(), 1);
+    assert_eq!(weeks[0].duration, 5400);
+    assert!((weeks[0].distance_in(DistanceUnit::Meters) - 1500.0).abs() < 0.01);
+}
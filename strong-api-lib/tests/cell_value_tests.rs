@@ -0,0 +1,138 @@
+use proptest::prelude::*;
+use strong_api_lib::data_transformer::cell_value::{parse_count, parse_decimal, parse_duration};
+
+// ---------------------------------------------------------------------------
+// parse_decimal — formats seen in fixtures and from comma-locale devices
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_parse_decimal_plain_values() {
+    assert_eq!(parse_decimal("60"), Some(60.0));
+    assert_eq!(parse_decimal("15.5"), Some(15.5));
+    assert_eq!(parse_decimal("-10"), Some(-10.0));
+}
+
+#[test]
+fn test_parse_decimal_comma_and_whitespace() {
+    assert_eq!(parse_decimal("62,5"), Some(62.5));
+    assert_eq!(parse_decimal(" 10 "), Some(10.0));
+    assert_eq!(parse_decimal("\t2,25\n"), Some(2.25));
+}
+
+#[test]
+fn test_parse_decimal_thousands_separators() {
+    assert_eq!(parse_decimal("1,234.5"), Some(1234.5));
+    assert_eq!(parse_decimal("1.234,5"), Some(1234.5));
+    assert_eq!(parse_decimal("1,000,000"), Some(1_000_000.0));
+}
+
+#[test]
+fn test_parse_decimal_scientific_notation() {
+    assert_eq!(parse_decimal("1e2"), Some(100.0));
+    assert_eq!(parse_decimal("2.5E1"), Some(25.0));
+}
+
+#[test]
+fn test_parse_decimal_rejects_garbage() {
+    assert_eq!(parse_decimal(""), None);
+    assert_eq!(parse_decimal("   "), None);
+    assert_eq!(parse_decimal("heavy"), None);
+    assert_eq!(parse_decimal("NaN"), None);
+    assert_eq!(parse_decimal("inf"), None);
+    assert_eq!(parse_decimal("1,2,3.4,5"), None);
+}
+
+// ---------------------------------------------------------------------------
+// parse_count
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_parse_count_float_formatted_integers() {
+    assert_eq!(parse_count("12"), Some(12));
+    assert_eq!(parse_count("12.0"), Some(12));
+    assert_eq!(parse_count("12,0"), Some(12));
+    assert_eq!(parse_count(" 10 "), Some(10));
+    assert_eq!(parse_count("1e2"), Some(100));
+}
+
+#[test]
+fn test_parse_count_rejects_fractions_and_negatives() {
+    assert_eq!(parse_count("12.5"), None);
+    assert_eq!(parse_count("-1"), None);
+    assert_eq!(parse_count("five"), None);
+}
+
+// ---------------------------------------------------------------------------
+// parse_duration
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_parse_duration_seconds() {
+    assert_eq!(parse_duration("90"), Some(90));
+    assert_eq!(parse_duration("90.4"), Some(90));
+    assert_eq!(parse_duration("90,6"), Some(91));
+    assert_eq!(parse_duration(" 3600 "), Some(3600));
+}
+
+#[test]
+fn test_parse_duration_clock_notation() {
+    assert_eq!(parse_duration("1:30"), Some(90));
+    assert_eq!(parse_duration("01:02:03"), Some(3723));
+    assert_eq!(parse_duration("90:00"), Some(5400));
+    assert_eq!(parse_duration("0:00:05.5"), Some(6));
+}
+
+#[test]
+fn test_parse_duration_rejects_invalid() {
+    assert_eq!(parse_duration("-5"), None);
+    assert_eq!(parse_duration("1:60"), None);
+    assert_eq!(parse_duration("1:60:00"), None);
+    assert_eq!(parse_duration("1:2:3:4"), None);
+    assert_eq!(parse_duration("a:30"), None);
+    assert_eq!(parse_duration(""), None);
+}
+
+// ---------------------------------------------------------------------------
+// Property tests
+// ---------------------------------------------------------------------------
+
+proptest! {
+    #[test]
+    fn prop_integers_round_trip_as_counts(n in 0u32..1_000_000) {
+        prop_assert_eq!(parse_count(&n.to_string()), Some(n));
+        prop_assert_eq!(parse_count(&format!("{n}.0")), Some(n));
+        prop_assert_eq!(parse_count(&format!("  {n}  ")), Some(n));
+    }
+
+    #[test]
+    fn prop_decimal_comma_equals_decimal_point(whole in 0u32..10_000, hundredths in 0u32..100) {
+        let with_point = format!("{whole}.{hundredths:02}");
+        let with_comma = format!("{whole},{hundredths:02}");
+        let expected = with_point.parse::<f32>().unwrap();
+        prop_assert_eq!(parse_decimal(&with_point), Some(expected));
+        prop_assert_eq!(parse_decimal(&with_comma), Some(expected));
+        prop_assert_eq!(parse_decimal(&format!(" {with_comma}\t")), Some(expected));
+    }
+
+    #[test]
+    fn prop_weights_never_disappear(weight in 0.0f32..1000.0) {
+        // Whatever way a weight is printed, it has to come back as a value.
+        let printed = weight.to_string();
+        prop_assert_eq!(parse_decimal(&printed), Some(weight));
+        prop_assert!(parse_decimal(&printed.replace('.', ",")).is_some());
+    }
+
+    #[test]
+    fn prop_clock_durations(hours in 0u32..24, minutes in 0u32..60, seconds in 0u32..60) {
+        let total = hours * 3600 + minutes * 60 + seconds;
+        prop_assert_eq!(
+            parse_duration(&format!("{hours:02}:{minutes:02}:{seconds:02}")),
+            Some(total)
+        );
+        prop_assert_eq!(parse_duration(&total.to_string()), Some(total));
+        prop_assert_eq!(
+            parse_duration(&format!("{}:{seconds:02}", hours * 60 + minutes)),
+            Some(total)
+        );
+    }
+}
@@ -0,0 +1,172 @@
+//! Fixtures and factories shared by the integration tests. Every test file compiles this module
+//! on its own and uses only part of it.
+#![allow(dead_code)]
+
+use strong_api_lib::data_transformer::{DataTransformer, Exercise, LoadType, Set, Workout};
+use strong_api_lib::models::measurement::MeasurementsResponse;
+use strong_api_lib::models::preferences::DistanceUnit;
+use strong_api_lib::models::workout::{Log, UserResponse};
+
+pub fn fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+pub fn load_fixture(name: &str) -> String {
+    std::fs::read_to_string(fixture_path(name))
+        .unwrap_or_else(|_| panic!("fixture '{name}' not found"))
+}
+
+pub fn measurements_from_fixture() -> MeasurementsResponse {
+    serde_json::from_str(&load_fixture("measurements_response.json")).unwrap()
+}
+
+pub fn user_from_fixture() -> UserResponse {
+    serde_json::from_str(&load_fixture("user_response.json")).unwrap()
+}
+
+pub fn logs_from_fixture() -> Vec<Log> {
+    user_from_fixture().embedded.log.unwrap()
+}
+
+/// The logs of the user fixture, transformed with the measurements fixture.
+pub fn workouts_from_fixture() -> Vec<Workout> {
+    DataTransformer::new()
+        .with_measurements_response(measurements_from_fixture())
+        .get_measurements_from_logs(&user_from_fixture().embedded.log)
+        .unwrap()
+}
+
+/// A completed set of `reps` with an external load of `load` kg.
+pub fn set(load: f32, reps: u32) -> Set {
+    Set {
+        id: format!("set-{load}-{reps}"),
+        weight: Some(load),
+        load_type: LoadType::External,
+        total_load: Some(load),
+        effective_load: Some(load),
+        reps,
+        is_completed: true,
+        ..Default::default()
+    }
+}
+
+pub fn set_at_rpe(load: f32, reps: u32, rpe: f32) -> Set {
+    Set {
+        rpe: Some(rpe),
+        ..set(load, reps)
+    }
+}
+
+/// A completed set of `reps` without a weight.
+pub fn bodyweight_set(reps: u32) -> Set {
+    Set {
+        id: format!("set-{reps}"),
+        reps,
+        is_completed: true,
+        ..Default::default()
+    }
+}
+
+/// A set of `reps` planned in a template, its weight is left to the planner.
+pub fn target_set(reps: u32) -> Set {
+    Set {
+        load_type: LoadType::External,
+        reps,
+        ..Default::default()
+    }
+}
+
+/// A completed cardio set, `distance` in kilometers and logged in `unit`.
+pub fn cardio_set(distance: Option<f32>, duration: Option<u32>, unit: DistanceUnit) -> Set {
+    Set {
+        id: format!("set-{distance:?}-{duration:?}"),
+        distance,
+        duration,
+        distance_unit: unit,
+        is_completed: true,
+        ..Default::default()
+    }
+}
+
+/// Optional fields of the sets built above.
+pub trait SetExt {
+    fn with_id(self, id: &str) -> Set;
+    fn incomplete(self) -> Set;
+}
+
+impl SetExt for Set {
+    fn with_id(self, id: &str) -> Set {
+        Set {
+            id: id.to_string(),
+            ..self
+        }
+    }
+
+    fn incomplete(self) -> Set {
+        Set {
+            is_completed: false,
+            ..self
+        }
+    }
+}
+
+pub fn exercise(measurement_id: &str, name: &str, sets: Vec<Set>) -> Exercise {
+    Exercise {
+        measurement_id: measurement_id.to_string(),
+        name: name.to_string(),
+        canonical_name: name.to_string(),
+        sets,
+        ..Default::default()
+    }
+}
+
+/// A workout in Berlin starting at `start_date`, an RFC 3339 timestamp.
+pub fn workout(id: &str, start_date: &str, exercises: Vec<Exercise>) -> Workout {
+    Workout {
+        id: id.to_string(),
+        timezone: Some("Europe/Berlin".to_string()),
+        start_date: Some(start_date.to_string()),
+        exercises,
+        ..Default::default()
+    }
+}
+
+/// A template named `name`, a workout without a start date.
+pub fn template(id: &str, name: &str, exercises: Vec<Exercise>) -> Workout {
+    Workout {
+        id: id.to_string(),
+        name: name.to_string(),
+        exercises,
+        ..Default::default()
+    }
+}
+
+/// Optional fields of the workouts built above.
+pub trait WorkoutExt {
+    fn with_name(self, name: &str) -> Workout;
+    fn with_end_date(self, end_date: &str) -> Workout;
+    fn with_body_weight(self, body_weight: Option<f32>) -> Workout;
+}
+
+impl WorkoutExt for Workout {
+    fn with_name(self, name: &str) -> Workout {
+        Workout {
+            name: name.to_string(),
+            ..self
+        }
+    }
+
+    fn with_end_date(self, end_date: &str) -> Workout {
+        Workout {
+            end_date: Some(end_date.to_string()),
+            ..self
+        }
+    }
+
+    fn with_body_weight(self, body_weight: Option<f32>) -> Workout {
+        Workout {
+            body_weight,
+            ..self
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use strong_api_lib::analytics::comparison::{Delta, Period, compare_periods};
+use strong_api_lib::analytics::one_rep_max::OneRepMaxFormula;
+use strong_api_lib::analytics::personal_records::RecordKind;
+use strong_api_lib::data_transformer::Workout;
+use time::Weekday;
+use time::macros::date;
+
+mod common;
+use common::{exercise, measurements_from_fixture, set, workout, workouts_from_fixture};
+
+const LEGS_ID: &str = "57f573f8-f797-4483-bc1f-5911a70463a6";
+const ARMS_ID: &str = "0973c5dd-5b62-4e9e-a15e-114cb94f440d";
+
+/// April has one leg day, May two leg days and an arm day.
+fn history() -> Vec<Workout> {
+    vec![
+        workout(
+            "w1",
+            "2024-04-10T17:00:00Z",
+            vec![exercise(
+                LEGS_ID,
+                "Squat",
+                vec![set(100.0, 5), set(100.0, 5)],
+            )],
+        ),
+        workout(
+            "w2",
+            "2024-05-02T17:00:00Z",
+            vec![exercise(LEGS_ID, "Squat", vec![set(100.0, 5)])],
+        ),
+        workout(
+            "w3",
+            "2024-05-09T17:00:00Z",
+            vec![exercise(LEGS_ID, "Squat", vec![set(110.0, 5)])],
+        ),
+        // Starts on May 1st in Berlin.
+        workout(
+            "w4",
+            "2024-04-30T22:30:00Z",
+            vec![exercise(ARMS_ID, "Curl", vec![set(20.0, 10)])],
+        ),
+        workout(
+            "w5",
+            "2024-06-01T10:00:00Z",
+            vec![exercise(LEGS_ID, "Squat", vec![set(200.0, 1)])],
+        ),
+    ]
+}
+
+// ---------------------------------------------------------------------------
+// Periods
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_periods() {
+    let may = Period::month_of(date!(2024 - 05 - 17));
+    assert_eq!(
+        may,
+        Period::new(date!(2024 - 05 - 01), date!(2024 - 05 - 31))
+    );
+    assert_eq!(may.days(), 31);
+    assert_eq!(
+        may.previous_month(),
+        Period::new(date!(2024 - 04 - 01), date!(2024 - 04 - 30))
+    );
+    assert_eq!(
+        may.preceding(),
+        Period::new(date!(2024 - 03 - 31), date!(2024 - 04 - 30))
+    );
+    assert_eq!(
+        Period::month_of(date!(2024 - 02 - 10)).end,
+        date!(2024 - 02 - 29)
+    );
+
+    let week = Period::week_of(date!(2024 - 05 - 17), Weekday::Sunday);
+    assert_eq!(
+        week,
+        Period::new(date!(2024 - 05 - 12), date!(2024 - 05 - 18))
+    );
+    assert!(week.contains(date!(2024 - 05 - 18)));
+    assert!(!week.contains(date!(2024 - 05 - 19)));
+
+    let block = Period::days_ending(date!(2024 - 05 - 28), 28);
+    assert_eq!(block.start, date!(2024 - 05 - 01));
+    assert_eq!(block.preceding().end, date!(2024 - 04 - 30));
+    assert_eq!(block.to_string(), "2024-05-01 to 2024-05-28");
+}
+
+#[test]
+fn test_delta_percent() {
+    let delta = Delta::new(4.0, 5.0);
+    assert_eq!(delta.change, 1.0);
+    assert_eq!(delta.percent, Some(25.0));
+    assert_eq!(delta.to_string(), "4 -> 5 (+25.0%)");
+    assert_eq!(Delta::new(0.0, 3.0).percent, None);
+    assert_eq!(Delta::new(0.0, 3.0).to_string(), "0 -> 3 (new)");
+    assert_eq!(Delta::new(0.0, 0.0).to_string(), "0 -> 0");
+}
+
+// ---------------------------------------------------------------------------
+// Comparison
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_month_over_month() {
+    let may = Period::month_of(date!(2024 - 05 - 01));
+    let comparison = compare_periods(
+        &history(),
+        &measurements_from_fixture(),
+        may,
+        may.previous_month(),
+        OneRepMaxFormula::Epley,
+    );
+
+    assert_eq!(comparison.workouts, Delta::new(1.0, 3.0));
+    assert_eq!(comparison.sets, Delta::new(2.0, 3.0));
+    assert_eq!(comparison.tonnage, Delta::new(1000.0, 1250.0));
+    assert_eq!(comparison.tonnage.percent, Some(25.0));
+
+    let tags: Vec<(&str, f32, f32)> = comparison
+        .sets_by_tag
+        .iter()
+        .map(|tag| (tag.tag.as_str(), tag.sets.previous, tag.sets.current))
+        .collect();
+    assert_eq!(tags, vec![("arms", 0.0, 1.0), ("legs", 2.0, 2.0)]);
+
+    let curl = &comparison.one_rep_maxes[0];
+    assert_eq!(
+        (curl.exercise.as_str(), curl.previous, curl.percent),
+        ("Curl", None, None)
+    );
+    let squat = &comparison.one_rep_maxes[1];
+    assert!((squat.percent.unwrap() - 10.0).abs() < 0.001);
+
+    // The 110 kg squat beats the heaviest weight, e1RM and set volume of April, the first curls
+    // set no records.
+    assert_eq!(comparison.personal_record_count, Delta::new(0.0, 3.0));
+    assert!(
+        comparison
+            .personal_records
+            .iter()
+            .all(|record| record.workout_id == "w3")
+    );
+    assert!(
+        comparison
+            .personal_records
+            .iter()
+            .any(|record| record.kind == RecordKind::BestOneRepMax)
+    );
+
+    assert_eq!(
+        comparison.to_string(),
+        "2024-05-01 to 2024-05-31 compared to 2024-04-01 to 2024-04-30\
+         \n  Workouts: 1 -> 3 (+200.0%)\
+         \n  Sets: 2 -> 3 (+50.0%)\
+         \n  Tonnage (kg): 1000 -> 1250 (+25.0%)\
+         \n  Personal records: 0 -> 3 (new)\
+         \n  Sets of arms: 0 -> 1 (new)\
+         \n  Sets of legs: 2 -> 2 (+0.0%)\
+         \n  Curl e1RM: 26.7 kg (new)\
+         \n  Squat e1RM: 116.7 -> 128.3 kg (+10.0%)"
+    );
+}
+
+#[test]
+fn test_comparison_serializes_to_json() {
+    let may = Period::month_of(date!(2024 - 05 - 01));
+    let comparison = compare_periods(
+        &history(),
+        &measurements_from_fixture(),
+        may,
+        may.previous_month(),
+        OneRepMaxFormula::Epley,
+    );
+
+    let value = serde_json::to_value(&comparison).unwrap();
+    assert_eq!(value["current"]["start"], "2024-05-01");
+    assert_eq!(value["formula"], "epley");
+    assert_eq!(value["tonnage"]["percent"], 25.0);
+    assert_eq!(
+        value["sets_by_tag"][0]["sets"]["percent"],
+        serde_json::Value::Null
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Fixtures
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_block_over_block_of_fixture() {
+    let workouts = workouts_from_fixture();
+
+    // The fixture's five workouts run from November 20th to December 11th 2024.
+    let block = Period::days_ending(date!(2024 - 12 - 11), 14);
+    let comparison = compare_periods(
+        &workouts,
+        &measurements_from_fixture(),
+        block,
+        block.preceding(),
+        OneRepMaxFormula::Epley,
+    );
+
+    assert_eq!(
+        comparison.workouts.current + comparison.workouts.previous,
+        5.0
+    );
+    assert!(comparison.tonnage.current > 0.0);
+    assert!(!comparison.one_rep_maxes.is_empty());
+}
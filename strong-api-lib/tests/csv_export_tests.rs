@@ -0,0 +1,213 @@
+use strong_api_lib::data_transformer::csv_export::{HEADER, workouts_to_csv};
+use strong_api_lib::data_transformer::{DataTransformer, Exercise, LoadType, Set, Workout};
+use strong_api_lib::models::preferences::{DistanceUnit, WeightUnit};
+
+mod common;
+use common::{load_fixture, measurements_from_fixture, user_from_fixture};
+
+fn transformer() -> DataTransformer {
+    let measurements = measurements_from_fixture();
+    DataTransformer::new()
+        .with_measurements_response(measurements)
+        .with_default_body_weight(80.0)
+}
+
+fn export(workouts: &[Workout]) -> String {
+    let mut csv = Vec::new();
+    workouts_to_csv(workouts, &mut csv).unwrap();
+    String::from_utf8(csv).unwrap()
+}
+
+fn workout() -> Workout {
+    Workout {
+        id: "w1".to_string(),
+        name: "Push Day".to_string(),
+        timezone: Some("Europe/Berlin".to_string()),
+        start_date: Some("2024-11-20T16:05:14Z".to_string()),
+        end_date: Some("2024-11-20T17:10:44Z".to_string()),
+        notes: Some("Good, \"easy\" session".to_string()),
+        exercises: vec![
+            Exercise {
+                name: "Bench Press (Barbell)".to_string(),
+                notes: Some("Pause the reps".to_string()),
+                sets: vec![
+                    Set {
+                        weight: Some(100.0),
+                        weight_unit: WeightUnit::Pounds,
+                        load_type: LoadType::External,
+                        reps: 5,
+                        rpe: Some(8.5),
+                        is_completed: true,
+                        notes: Some("Felt fast".to_string()),
+                        ..Default::default()
+                    },
+                    Set {
+                        weight: Some(110.0),
+                        reps: 3,
+                        is_completed: false,
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+            Exercise {
+                name: "Running".to_string(),
+                sets: vec![Set {
+                    distance: Some(3.218_688),
+                    distance_unit: DistanceUnit::Miles,
+                    duration: Some(1200),
+                    is_completed: true,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Layout
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_export_layout() {
+    let csv = export(&[workout()]);
+    let lines: Vec<_> = csv.lines().collect();
+    assert_eq!(lines[0], HEADER.join(","));
+    assert_eq!(
+        lines[0],
+        "Date,Workout Name,Duration,Exercise Name,Set Order,Weight,Reps,Distance,Seconds,Notes,Workout Notes,RPE"
+    );
+    // Dates are local, weights and distances in the unit of their set, incomplete sets are
+    // left out and exercise notes follow the sets.
+    assert_eq!(
+        &lines[1..],
+        [
+            r#"2024-11-20 17:05:14,Push Day,1h 5m,Bench Press (Barbell),1,220.46,5,0.0,0,Felt fast,"Good, ""easy"" session",8.5"#,
+            r#"2024-11-20 17:05:14,Push Day,1h 5m,Bench Press (Barbell),Note,,,,,Pause the reps,"Good, ""easy"" session","#,
+            r#"2024-11-20 17:05:14,Push Day,1h 5m,Running,1,0.0,0,2.0,1200,,"Good, ""easy"" session","#,
+        ]
+    );
+}
+
+#[test]
+fn test_export_skips_workouts_without_start_date() {
+    let mut without_dates = workout();
+    without_dates.start_date = None;
+    assert_eq!(export(&[without_dates.clone()]).lines().count(), 1);
+    let written = workouts_to_csv(&[workout(), without_dates], std::io::sink()).unwrap();
+    assert_eq!(written, 1);
+
+    // Without an end date the duration is left empty, without a timezone dates are in UTC.
+    let mut without_end = workout();
+    without_end.end_date = None;
+    without_end.timezone = None;
+    let csv = export(&[without_end]);
+    assert!(
+        csv.lines()
+            .nth(1)
+            .unwrap()
+            .starts_with("2024-11-20 16:05:14,Push Day,,Bench Press (Barbell),1,")
+    );
+}
+
+#[test]
+fn test_export_counts_only_workouts_with_rows() {
+    let mut incomplete = workout();
+    incomplete.notes = None;
+    for exercise in &mut incomplete.exercises {
+        exercise.notes = None;
+        exercise.sets.retain(|set| !set.is_completed);
+    }
+    assert_eq!(export(&[incomplete.clone()]).lines().count(), 1);
+    let written = workouts_to_csv(&[workout(), incomplete], std::io::sink()).unwrap();
+    assert_eq!(written, 1);
+}
+
+#[test]
+fn test_export_keeps_notes_of_workouts_without_sets() {
+    let mut notes_only = workout();
+    notes_only.exercises.clear();
+    let written = workouts_to_csv(&[notes_only.clone()], std::io::sink()).unwrap();
+    assert_eq!(written, 1);
+    let csv = export(&[notes_only]);
+    assert_eq!(
+        csv.lines().nth(1).unwrap(),
+        r#"2024-11-20 17:05:14,Push Day,1h 5m,,,,,,,,"Good, ""easy"" session","#
+    );
+
+    let reimported = transformer()
+        .workouts_from_csv(csv.as_bytes(), Some("Europe/Berlin"))
+        .unwrap();
+    assert_eq!(reimported.len(), 1);
+    assert!(reimported[0].exercises.is_empty());
+    assert_eq!(
+        reimported[0].notes.as_deref(),
+        Some("Good, \"easy\" session")
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Round trips
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_export_round_trips_through_import() {
+    let transformer = transformer();
+    let imported = transformer
+        .workouts_from_csv(
+            load_fixture("strong_export.csv").as_bytes(),
+            Some("Europe/Berlin"),
+        )
+        .unwrap();
+    let csv = export(&imported);
+    let reimported = transformer
+        .workouts_from_csv(csv.as_bytes(), Some("Europe/Berlin"))
+        .unwrap();
+    assert_eq!(reimported, imported);
+}
+
+#[test]
+fn test_export_fixture_workouts() {
+    let measurements = measurements_from_fixture();
+    let user = user_from_fixture();
+    let transformer = DataTransformer::new().with_measurements_response(measurements);
+    let workouts = transformer
+        .get_measurements_from_logs(&user.embedded.log)
+        .unwrap();
+
+    let csv = export(&workouts);
+    let completed_sets = workouts
+        .iter()
+        .flat_map(|workout| &workout.exercises)
+        .flat_map(|exercise| &exercise.sets)
+        .filter(|set| set.is_completed)
+        .count();
+    let exercise_notes = workouts
+        .iter()
+        .flat_map(|workout| &workout.exercises)
+        .filter(|exercise| exercise.notes.is_some())
+        .count();
+    assert_eq!(csv.lines().count(), 1 + completed_sets + exercise_notes);
+
+    // API data reads back with the same exercises and reps.
+    let reimported = transformer.workouts_from_csv(csv.as_bytes(), None).unwrap();
+    let summary = |workouts: &[Workout]| -> Vec<(String, Vec<u32>)> {
+        workouts
+            .iter()
+            .flat_map(|workout| &workout.exercises)
+            .map(|exercise| {
+                let reps: Vec<u32> = exercise
+                    .sets
+                    .iter()
+                    .filter(|set| set.is_completed)
+                    .map(|set| set.reps)
+                    .collect();
+                (exercise.name.clone(), reps)
+            })
+            .filter(|(_, reps)| !reps.is_empty())
+            .collect()
+    };
+    assert_eq!(summary(&reimported), summary(&workouts));
+}
@@ -0,0 +1,278 @@
+use strong_api_lib::data_transformer::csv_import::CsvImportError;
+use strong_api_lib::data_transformer::{DataTransformer, LoadType, Workout};
+use strong_api_lib::models::preferences::{DistanceUnit, Preferences, WeightUnit};
+
+mod common;
+use common::{load_fixture, measurements_from_fixture};
+
+const TRAP_BAR_DEADLIFT_ID: &str = "57f573f8-f797-4483-bc1f-5911a70463a6";
+const PULL_UP_ID: &str = "adb75e5a-c873-4f57-bdf0-0043710ba90d";
+const RUNNING_ID: &str = "a35d3d7c-6093-47b9-b4dc-939c4d799169";
+
+fn transformer() -> DataTransformer {
+    let measurements = measurements_from_fixture();
+    DataTransformer::new()
+        .with_measurements_response(measurements)
+        .with_default_body_weight(80.0)
+}
+
+fn import(transformer: &DataTransformer, csv: &str) -> Vec<Workout> {
+    transformer
+        .workouts_from_csv(csv.as_bytes(), Some("Europe/Berlin"))
+        .unwrap()
+}
+
+fn preferences(json: &str) -> Preferences {
+    serde_json::from_str(json).unwrap()
+}
+
+// ---------------------------------------------------------------------------
+// Export layout
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_import_fixture_export() {
+    let (workouts, diagnostics) = transformer()
+        .workouts_from_csv_with_diagnostics(
+            load_fixture("strong_export.csv").as_bytes(),
+            Some("Europe/Berlin"),
+        )
+        .unwrap();
+    assert_eq!(workouts.len(), 2);
+
+    let pull_day = &workouts[0];
+    assert_eq!(pull_day.name, "Pull Day");
+    assert_eq!(pull_day.timezone.as_deref(), Some("Europe/Berlin"));
+    assert_eq!(pull_day.start_date.as_deref(), Some("2024-11-20T16:05:14Z"));
+    assert_eq!(pull_day.end_date.as_deref(), Some("2024-11-20T17:10:14Z"));
+    assert_eq!(pull_day.notes.as_deref(), Some("Felt strong"));
+    assert_eq!(pull_day.body_weight, Some(80.0));
+    let names: Vec<_> = pull_day
+        .exercises
+        .iter()
+        .map(|exercise| exercise.name.as_str())
+        .collect();
+    assert_eq!(
+        names,
+        vec!["Trap Bar Deadlift", "Pull Up", "Plank", "Zottman Curl"]
+    );
+
+    // The rest timer row is skipped and the note row becomes the exercise's notes.
+    let deadlift = &pull_day.exercises[0];
+    assert_eq!(deadlift.measurement_id, TRAP_BAR_DEADLIFT_ID);
+    assert_eq!(deadlift.notes.as_deref(), Some("Use straps next time"));
+    assert_eq!(deadlift.sets.len(), 2);
+    let set = &deadlift.sets[1];
+    assert_eq!(set.weight, Some(120.0));
+    assert_eq!(set.weight_unit, WeightUnit::Kilograms);
+    assert_eq!(set.load_type, LoadType::External);
+    assert_eq!(set.weight_cell_type.as_deref(), Some("BARBELL_WEIGHT"));
+    assert_eq!(set.reps, 3);
+    assert_eq!(set.rpe, Some(8.5));
+    assert_eq!(set.notes.as_deref(), Some("Grip slipped"));
+    assert_eq!((set.distance, set.duration), (None, None));
+    assert!(set.is_completed);
+
+    // Bodyweight exercises get their load from the body weight.
+    let pull_up = &pull_day.exercises[1];
+    assert_eq!(pull_up.measurement_id, PULL_UP_ID);
+    assert_eq!(pull_up.sets[0].load_type, LoadType::WeightedBodyweight);
+    assert_eq!(pull_up.sets[0].effective_load, Some(90.0));
+    assert_eq!(pull_up.sets[1].effective_load, Some(80.0));
+
+    let plank = &pull_day.exercises[2];
+    assert_eq!(plank.sets[0].load_type, LoadType::Unweighted);
+    assert_eq!(plank.sets[0].weight, None);
+    assert_eq!(plank.sets[0].duration, Some(60));
+
+    // Exercises missing from the measurements keep their name and are reported.
+    let curl = &pull_day.exercises[3];
+    assert_eq!(curl.measurement_id, "");
+    assert_eq!(curl.canonical_name, "Zottman Curl");
+    assert_eq!(curl.sets[0].load_type, LoadType::External);
+    assert_eq!(diagnostics.unmatched_exercises.len(), 1);
+    let unmatched = &diagnostics.unmatched_exercises[0];
+    assert_eq!(unmatched.name, "Zottman Curl");
+    assert_eq!(
+        (unmatched.log_id.as_str(), unmatched.exercise_id.as_str()),
+        (pull_day.id.as_str(), curl.id.as_str())
+    );
+    assert!(
+        diagnostics
+            .to_string()
+            .contains("unmatched exercise 'Zottman Curl'")
+    );
+    assert_eq!(diagnostics.len(), 1);
+
+    // A set order starting over starts a new exercise.
+    let run = &workouts[1];
+    assert_eq!(run.start_date.as_deref(), Some("2024-11-22T06:30:00Z"));
+    assert_eq!(run.end_date.as_deref(), Some("2024-11-22T07:02:00Z"));
+    assert_eq!(run.exercises.len(), 2);
+    assert_eq!(run.exercises[0].measurement_id, RUNNING_ID);
+    let set = &run.exercises[0].sets[0];
+    assert_eq!(set.distance, Some(5.2));
+    assert_eq!(set.distance_unit, DistanceUnit::Kilometers);
+    assert_eq!(set.duration, Some(1800));
+    assert_eq!(run.exercises[1].sets[0].notes.as_deref(), Some("Cool down"));
+}
+
+#[test]
+fn test_ids_are_deterministic() {
+    let csv = load_fixture("strong_export.csv");
+    let first = import(&transformer(), &csv);
+    let second = import(&DataTransformer::new(), &csv);
+    let ids = |workouts: &[Workout]| -> Vec<String> {
+        workouts
+            .iter()
+            .flat_map(|workout| {
+                std::iter::once(workout.id.clone()).chain(workout.exercises.iter().flat_map(
+                    |exercise| {
+                        std::iter::once(exercise.id.clone())
+                            .chain(exercise.sets.iter().map(|set| set.id.clone()))
+                    },
+                ))
+            })
+            .collect()
+    };
+    assert_eq!(ids(&first), ids(&second));
+
+    let mut unique = ids(&first);
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), ids(&first).len());
+    assert!(
+        first
+            .iter()
+            .all(|workout| uuid::Uuid::parse_str(&workout.id).is_ok())
+    );
+}
+
+#[test]
+fn test_workouts_sharing_date_and_name_get_different_ids() {
+    let csv = "Date,Workout Name,Exercise Name,Set Order,Weight,Reps\n\
+               2024-11-20 17:00:00,Lift,Trap Bar Deadlift,1,100,5\n\
+               2024-11-21 17:00:00,Lift,Trap Bar Deadlift,1,100,5\n\
+               2024-11-20 17:00:00,Lift,Trap Bar Deadlift,1,100,5\n";
+    let workouts = import(&transformer(), csv);
+    assert_eq!(workouts.len(), 3);
+    assert_ne!(workouts[0].id, workouts[2].id);
+    assert_ne!(
+        workouts[0].exercises[0].sets[0].id,
+        workouts[2].exercises[0].sets[0].id
+    );
+
+    // The first of them keeps the id it has without the repetition.
+    let single = import(&transformer(), &csv[..csv.rfind("2024-11-20").unwrap()]);
+    assert_eq!(single[0].id, workouts[0].id);
+}
+
+// ---------------------------------------------------------------------------
+// Units
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_units_from_preferences() {
+    let transformer = transformer().with_preferences(
+        "user",
+        preferences(r#"{"weightUnit": {"user": "POUNDS"}, "distanceUnit": {"user": "MILES"}}"#),
+    );
+    let csv = "Date,Workout Name,Duration,Exercise Name,Set Order,Weight,Reps,Distance,Seconds\n\
+               2024-11-20 17:00:00,Lift,45m,Trap Bar Deadlift,1,220.462,5,0,0\n\
+               2024-11-20 17:00:00,Lift,45m,Running,1,0,0,1,600\n";
+    let workouts = import(&transformer, csv);
+    let exercises = &workouts[0].exercises;
+    assert_eq!(exercises[0].sets[0].weight_unit, WeightUnit::Pounds);
+    assert!((exercises[0].sets[0].weight.unwrap() - 100.0).abs() < 0.01);
+    assert_eq!(exercises[1].sets[0].distance_unit, DistanceUnit::Miles);
+    assert!((exercises[1].sets[0].distance.unwrap() - 1.609).abs() < 0.01);
+}
+
+#[test]
+fn test_units_from_columns_and_headers() {
+    let with_columns = "Date;Workout Name;Workout Duration;Exercise Name;Set Order;Weight;Weight Unit;Reps;Distance;Distance Unit;Seconds;Notes;Workout Notes;RPE\n\
+                        2024-11-20 17:00:00;Lift;45m;Trap Bar Deadlift;1;220,462;lbs;5;;;;;;\n\
+                        2024-11-20 17:00:00;Lift;45m;Running;1;;;;2;mi;600;;;\n";
+    let exercises = import(&transformer(), with_columns)[0].exercises.clone();
+    assert_eq!(exercises[0].sets[0].weight_unit, WeightUnit::Pounds);
+    assert!((exercises[0].sets[0].weight.unwrap() - 100.0).abs() < 0.01);
+    assert_eq!(exercises[1].sets[0].distance_unit, DistanceUnit::Miles);
+    assert!((exercises[1].sets[0].distance.unwrap() - 3.219).abs() < 0.01);
+
+    // Columns and headers win over the preferences.
+    let with_headers = "Date,Workout Name,Duration,Exercise Name,Set Order,Weight (kg),Reps,Distance (km),Seconds\n\
+                        2024-11-20 17:00:00,Lift,45m,Trap Bar Deadlift,1,100,5,0,0\n";
+    let transformer = transformer()
+        .with_preferences("user", preferences(r#"{"weightUnit": {"user": "POUNDS"}}"#));
+    let set = &import(&transformer, with_headers)[0].exercises[0].sets[0];
+    assert_eq!(set.weight_unit, WeightUnit::Kilograms);
+    assert_eq!(set.weight, Some(100.0));
+}
+
+// ---------------------------------------------------------------------------
+// Errors and diagnostics
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_invalid_exports() {
+    let error = transformer()
+        .workouts_from_csv("Date,Workout Name,Set Order\n".as_bytes(), None)
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        CsvImportError::MissingColumn("Exercise Name")
+    ));
+    assert_eq!(error.to_string(), "CSV has no 'Exercise Name' column");
+
+    let csv = "Date,Workout Name,Exercise Name,Set Order\n20.11.2024,Lift,Plank,1\n";
+    let error = transformer()
+        .workouts_from_csv(csv.as_bytes(), None)
+        .unwrap_err();
+    assert_eq!(error.to_string(), "invalid date '20.11.2024' on line 2");
+}
+
+#[test]
+fn test_unparsable_values_are_reported() {
+    let csv = "Date,Workout Name,Duration,Exercise Name,Set Order,Weight,Reps\n\
+               2024-11-20 17:00,Lift,soon,Trap Bar Deadlift,1,heavy,5\n";
+    let (workouts, diagnostics) = transformer()
+        .workouts_from_csv_with_diagnostics(csv.as_bytes(), None)
+        .unwrap();
+    // Without a timezone dates are taken as UTC.
+    assert_eq!(
+        workouts[0].start_date.as_deref(),
+        Some("2024-11-20T17:00:00Z")
+    );
+    assert_eq!(workouts[0].timezone, None);
+    assert_eq!(workouts[0].end_date, None);
+    let set = &workouts[0].exercises[0].sets[0];
+    assert_eq!((set.weight, set.reps), (None, 5));
+
+    let unparsable: Vec<_> = diagnostics
+        .unparsable_values
+        .iter()
+        .map(|value| {
+            (
+                value.cell_id.as_str(),
+                value.cell_type.as_str(),
+                value.value.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        unparsable,
+        vec![
+            (workouts[0].id.as_str(), "Duration", "soon"),
+            (set.id.as_str(), "Weight", "heavy"),
+        ]
+    );
+
+    // Durations overflowing the seconds are unparsable too.
+    let csv = "Date,Workout Name,Duration,Exercise Name,Set Order\n\
+               2024-11-20 17:00,Lift,9999999h 5m,Plank,1\n";
+    let (workouts, diagnostics) = transformer()
+        .workouts_from_csv_with_diagnostics(csv.as_bytes(), None)
+        .unwrap();
+    assert_eq!(workouts[0].end_date, None);
+    assert_eq!(diagnostics.unparsable_values[0].value, "9999999h 5m");
+}
@@ -106,6 +106,39 @@ fn test_sets_have_weight_and_reps() {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Note cell sets become the notes of their exercise, note groups those of the workout
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_note_cell_sets_become_notes() {
+    let transformer = DataTransformer::new();
+    let workouts = transformer
+        .get_measurements_from_logs(&logs_from_fixture())
+        .unwrap();
+
+    let exercise_notes: Vec<&str> = workouts
+        .iter()
+        .flat_map(|workout| &workout.exercises)
+        .filter_map(|exercise| exercise.notes.as_deref())
+        .collect();
+    assert_eq!(exercise_notes, vec!["Just another note"]);
+
+    let mut workout_notes: Vec<&str> = workouts
+        .iter()
+        .filter_map(|workout| workout.notes.as_deref())
+        .collect();
+    workout_notes.sort();
+    assert_eq!(
+        workout_notes,
+        vec![
+            "Just a note",
+            "Just another note with an äöü",
+            "yet another note"
+        ]
+    );
+}
+
 // ---------------------------------------------------------------------------
 // Workout metadata is preserved
 // ---------------------------------------------------------------------------
@@ -357,3 +390,675 @@ fn test_data_transformer_default_equals_new() {
     let workouts = by_default.get_measurements_from_logs(&None).unwrap();
     assert!(workouts.is_empty());
 }
+
+// ---------------------------------------------------------------------------
+// Diagnostics — everything dropped or defaulted is reported
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_diagnostics_empty_for_clean_log_with_measurements() {
+    let measurements = measurements_from_fixture();
+    let known_id = measurements.embedded.measurements[0].id.clone();
+    let href = format!("/api/users/00000000-0000-0000-0000-000000000001/measurements/{known_id}");
+    let logs = make_log_with_measurement_link(Some(&href));
+
+    let transformer = DataTransformer::new().with_measurements_response(measurements);
+    let (workouts, diagnostics) = transformer
+        .get_workouts_with_diagnostics(&Some(logs))
+        .unwrap();
+
+    assert_eq!(workouts.len(), 1);
+    assert!(diagnostics.is_empty(), "unexpected issues: {diagnostics}");
+}
+
+#[test]
+fn test_diagnostics_reports_unresolved_measurement() {
+    let href = "/api/users/00000000-0000-0000-0000-000000000001/measurements/does-not-exist";
+    let logs = make_log_with_measurement_link(Some(href));
+
+    let transformer =
+        DataTransformer::new().with_measurements_response(measurements_from_fixture());
+    let (_, diagnostics) = transformer
+        .get_workouts_with_diagnostics(&Some(logs))
+        .unwrap();
+
+    assert_eq!(diagnostics.unresolved_measurements.len(), 1);
+    let unresolved = &diagnostics.unresolved_measurements[0];
+    assert_eq!(unresolved.measurement_id, "does-not-exist");
+    assert_eq!(unresolved.log_id, "log-link-test");
+    assert_eq!(unresolved.cell_set_group_id, "csg-link-test");
+}
+
+#[test]
+fn test_diagnostics_reports_missing_measurement_link_as_unresolved() {
+    let logs = make_log_with_measurement_link(None);
+    let transformer =
+        DataTransformer::new().with_measurements_response(measurements_from_fixture());
+    let (_, diagnostics) = transformer
+        .get_workouts_with_diagnostics(&Some(logs))
+        .unwrap();
+
+    assert_eq!(diagnostics.unresolved_measurements.len(), 1);
+    assert_eq!(diagnostics.unresolved_measurements[0].measurement_id, "");
+}
+
+#[test]
+fn test_diagnostics_reports_unparsable_values() {
+    let logs = make_log_with_cells(vec![
+        ("BARBELL_WEIGHT".to_string(), Some("heavy".to_string())),
+        ("REPS".to_string(), Some("five".to_string())),
+        ("RPE".to_string(), Some("8".to_string())),
+    ]);
+    let transformer = DataTransformer::new();
+    let (workouts, diagnostics) = transformer
+        .get_workouts_with_diagnostics(&Some(logs))
+        .unwrap();
+
+    let set = &workouts[0].exercises[0].sets[0];
+    assert_eq!(set.weight, None);
+    assert_eq!(set.reps, 0);
+    assert_eq!(set.rpe, Some(8.0));
+
+    let unparsable: Vec<(&str, &str, &str)> = diagnostics
+        .unparsable_values
+        .iter()
+        .map(|v| (v.cell_id.as_str(), v.cell_type.as_str(), v.value.as_str()))
+        .collect();
+    assert_eq!(
+        unparsable,
+        vec![("c0", "BARBELL_WEIGHT", "heavy"), ("c1", "REPS", "five")]
+    );
+}
+
+#[test]
+fn test_diagnostics_ignores_missing_values() {
+    let logs = make_log_with_cells(vec![
+        ("BARBELL_WEIGHT".to_string(), None),
+        ("REPS".to_string(), None),
+    ]);
+    let transformer = DataTransformer::new();
+    let (_, diagnostics) = transformer
+        .get_workouts_with_diagnostics(&Some(logs))
+        .unwrap();
+    assert!(diagnostics.unparsable_values.is_empty());
+}
+
+#[test]
+fn test_diagnostics_reports_unknown_cell_types() {
+    let logs = make_log_with_cells(vec![
+        ("REPS".to_string(), Some("5".to_string())),
+        ("HEART_RATE".to_string(), Some("150".to_string())),
+    ]);
+    let transformer = DataTransformer::new();
+    let (_, diagnostics) = transformer
+        .get_workouts_with_diagnostics(&Some(logs))
+        .unwrap();
+
+    assert_eq!(diagnostics.unknown_cell_types.len(), 1);
+    assert_eq!(diagnostics.unknown_cell_types[0].cell_id, "c1");
+    assert_eq!(diagnostics.unknown_cell_types[0].cell_type, "HEART_RATE");
+}
+
+#[test]
+fn test_diagnostics_reports_empty_groups() {
+    let mut logs = make_log_with_cells(vec![("REST_TIMER".to_string(), Some("90".to_string()))]);
+    logs[0].embedded.cell_set_group[0].links.measurement =
+        Some(strong_api_lib::models::common::Link {
+            href: "/api/users/u1/measurements/m1".to_string(),
+        });
+    let transformer = DataTransformer::new();
+    let (workouts, diagnostics) = transformer
+        .get_workouts_with_diagnostics(&Some(logs))
+        .unwrap();
+
+    assert!(workouts[0].exercises.is_empty());
+    assert_eq!(diagnostics.empty_groups.len(), 1);
+    assert_eq!(
+        diagnostics.empty_groups[0].cell_set_group_id,
+        "csg-cell-test"
+    );
+    assert!(diagnostics.unresolved_measurements.is_empty());
+}
+
+#[test]
+fn test_note_groups_are_no_empty_groups() {
+    let logs = make_log_with_cells(vec![("NOTE".to_string(), Some("only a note".to_string()))]);
+    let transformer = DataTransformer::new();
+    let (workouts, diagnostics) = transformer
+        .get_workouts_with_diagnostics(&Some(logs))
+        .unwrap();
+
+    assert!(workouts[0].exercises.is_empty());
+    assert_eq!(workouts[0].notes.as_deref(), Some("only a note"));
+    assert!(diagnostics.is_empty());
+}
+
+#[test]
+fn test_diagnostics_for_none_logs_is_empty() {
+    let transformer = DataTransformer::new();
+    let (workouts, diagnostics) = transformer.get_workouts_with_diagnostics(&None).unwrap();
+    assert!(workouts.is_empty());
+    assert!(diagnostics.is_empty());
+}
+
+#[test]
+fn test_fixture_logs_resolve_without_diagnostics() {
+    let transformer =
+        DataTransformer::new().with_measurements_response(measurements_from_fixture());
+    let (_, diagnostics) = transformer
+        .get_workouts_with_diagnostics(&logs_from_fixture())
+        .unwrap();
+    assert!(diagnostics.unparsable_values.is_empty());
+    assert!(diagnostics.unknown_cell_types.is_empty());
+}
+
+#[test]
+fn test_fixture_note_groups_are_no_empty_groups() {
+    let transformer =
+        DataTransformer::new().with_measurements_response(measurements_from_fixture());
+    let (workouts, diagnostics) = transformer
+        .get_workouts_with_diagnostics(&logs_from_fixture())
+        .unwrap();
+    let notes: Vec<&str> = workouts
+        .iter()
+        .filter_map(|workout| workout.notes.as_deref())
+        .collect();
+    assert!(notes.contains(&"Just a note"));
+    assert!(notes.contains(&"yet another note"));
+    assert!(diagnostics.empty_groups.is_empty());
+}
+
+// ---------------------------------------------------------------------------
+// Locale-robust cell values
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_decimal_comma_weight_is_kept() {
+    let logs = make_log_with_cells(vec![
+        ("BARBELL_WEIGHT".to_string(), Some("62,5".to_string())),
+        ("REPS".to_string(), Some(" 10 ".to_string())),
+        ("RPE".to_string(), Some("8,5".to_string())),
+    ]);
+    let transformer = DataTransformer::new();
+    let (workouts, diagnostics) = transformer
+        .get_workouts_with_diagnostics(&Some(logs))
+        .unwrap();
+
+    let set = &workouts[0].exercises[0].sets[0];
+    assert_eq!(set.weight, Some(62.5));
+    assert_eq!(set.reps, 10);
+    assert_eq!(set.rpe, Some(8.5));
+    assert!(diagnostics.unparsable_values.is_empty());
+}
+
+#[test]
+fn test_float_formatted_reps_are_kept() {
+    let logs = make_log_with_cells(vec![
+        ("OTHER_WEIGHT".to_string(), Some("1e2".to_string())),
+        ("REPS".to_string(), Some("12.0".to_string())),
+    ]);
+    let transformer = DataTransformer::new();
+    let workouts = transformer.get_measurements_from_logs(&Some(logs)).unwrap();
+
+    let set = &workouts[0].exercises[0].sets[0];
+    assert_eq!(set.weight, Some(100.0));
+    assert_eq!(set.reps, 12);
+}
+
+#[test]
+fn test_blank_values_are_treated_as_missing() {
+    let logs = make_log_with_cells(vec![
+        ("BARBELL_WEIGHT".to_string(), Some("  ".to_string())),
+        ("REPS".to_string(), Some("".to_string())),
+    ]);
+    let transformer = DataTransformer::new();
+    let (workouts, diagnostics) = transformer
+        .get_workouts_with_diagnostics(&Some(logs))
+        .unwrap();
+
+    let set = &workouts[0].exercises[0].sets[0];
+    assert_eq!(set.weight, None);
+    assert_eq!(set.reps, 0);
+    assert!(diagnostics.unparsable_values.is_empty());
+}
+
+// ---------------------------------------------------------------------------
+// Units — weights and distances are normalized using the user's preferences
+// ---------------------------------------------------------------------------
+
+fn preferences(json: &str) -> strong_api_lib::models::preferences::Preferences {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn test_weights_default_to_kilograms() {
+    use strong_api_lib::models::preferences::WeightUnit;
+
+    let logs = make_log_with_cells(vec![
+        ("BARBELL_WEIGHT".to_string(), Some("100".to_string())),
+        ("REPS".to_string(), Some("5".to_string())),
+    ]);
+    let workouts = DataTransformer::new()
+        .get_measurements_from_logs(&Some(logs))
+        .unwrap();
+
+    let set = &workouts[0].exercises[0].sets[0];
+    assert_eq!(set.weight, Some(100.0));
+    assert_eq!(set.weight_unit, WeightUnit::Kilograms);
+}
+
+#[test]
+fn test_pound_weights_are_converted_to_kilograms() {
+    use strong_api_lib::models::preferences::WeightUnit;
+
+    let logs = make_log_with_cells(vec![
+        ("BARBELL_WEIGHT".to_string(), Some("225".to_string())),
+        ("REPS".to_string(), Some("5".to_string())),
+    ]);
+    let workouts = DataTransformer::new()
+        .with_preferences("user", preferences(r#"{"weightUnit": {"user": "POUNDS"}}"#))
+        .get_measurements_from_logs(&Some(logs))
+        .unwrap();
+
+    let set = &workouts[0].exercises[0].sets[0];
+    assert!((set.weight.unwrap() - 102.058).abs() < 1e-3);
+    assert_eq!(set.weight_unit, WeightUnit::Pounds);
+}
+
+#[test]
+fn test_measurement_specific_weight_unit_is_used() {
+    use strong_api_lib::models::preferences::WeightUnit;
+
+    let href = "/api/users/user/measurements/m-lb";
+    let logs = make_log_with_measurement_link(Some(href));
+    let workouts = DataTransformer::new()
+        .with_preferences(
+            "user",
+            preferences(r#"{"weightUnit": {"user": "KILOGRAMS", "m-lb": "POUNDS"}}"#),
+        )
+        .get_measurements_from_logs(&Some(logs))
+        .unwrap();
+
+    let set = &workouts[0].exercises[0].sets[0];
+    assert_eq!(set.weight_unit, WeightUnit::Pounds);
+    assert!((set.weight.unwrap() - 36.287).abs() < 1e-3);
+}
+
+#[test]
+fn test_distance_is_converted_to_kilometers() {
+    use strong_api_lib::models::preferences::DistanceUnit;
+
+    let logs = make_log_with_cells(vec![
+        ("DISTANCE".to_string(), Some("3,1".to_string())),
+        ("DURATION".to_string(), Some("25:00".to_string())),
+    ]);
+    let workouts = DataTransformer::new()
+        .with_preferences(
+            "user",
+            preferences(r#"{"distanceUnit": {"user": "MILES"}}"#),
+        )
+        .get_measurements_from_logs(&Some(logs))
+        .unwrap();
+
+    let set = &workouts[0].exercises[0].sets[0];
+    assert!((set.distance.unwrap() - 4.989).abs() < 1e-3);
+    assert_eq!(set.distance_unit, DistanceUnit::Miles);
+    assert_eq!(set.weight, None);
+}
+
+// ---------------------------------------------------------------------------
+// Effective load — bodyweight, weighted and assisted exercises
+// ---------------------------------------------------------------------------
+
+fn make_dated_log_with_cells(
+    start_date: &str,
+    cells: Vec<(String, Option<String>)>,
+) -> Vec<strong_api_lib::models::workout::Log> {
+    let mut logs = make_log_with_cells(cells);
+    logs[0].start_date = Some(start_date.to_string());
+    logs
+}
+
+fn body_weight(
+    date: time::OffsetDateTime,
+    weight: f32,
+) -> strong_api_lib::data_transformer::body_weight::BodyWeight {
+    strong_api_lib::data_transformer::body_weight::BodyWeight {
+        measured_at: date,
+        weight,
+    }
+}
+
+#[test]
+fn test_external_load_is_the_logged_weight() {
+    use strong_api_lib::data_transformer::LoadType;
+
+    let logs = make_log_with_cells(vec![
+        ("BARBELL_WEIGHT".to_string(), Some("100".to_string())),
+        ("REPS".to_string(), Some("5".to_string())),
+    ]);
+    let workouts = DataTransformer::new()
+        .with_default_body_weight(80.0)
+        .get_measurements_from_logs(&Some(logs))
+        .unwrap();
+
+    let set = &workouts[0].exercises[0].sets[0];
+    assert_eq!(set.load_type, LoadType::External);
+    assert_eq!(set.effective_load, Some(100.0));
+}
+
+#[test]
+fn test_weighted_bodyweight_adds_body_weight() {
+    use strong_api_lib::data_transformer::LoadType;
+
+    let logs = make_log_with_cells(vec![
+        ("WEIGHTED_BODYWEIGHT".to_string(), Some("10".to_string())),
+        ("REPS".to_string(), Some("5".to_string())),
+    ]);
+    let workouts = DataTransformer::new()
+        .with_default_body_weight(80.0)
+        .get_measurements_from_logs(&Some(logs))
+        .unwrap();
+
+    let set = &workouts[0].exercises[0].sets[0];
+    assert_eq!(set.weight, Some(10.0));
+    assert_eq!(set.load_type, LoadType::WeightedBodyweight);
+    assert_eq!(set.effective_load, Some(90.0));
+    assert_eq!(workouts[0].body_weight, Some(80.0));
+}
+
+#[test]
+fn test_empty_weighted_bodyweight_cell_is_plain_body_weight() {
+    let logs = make_log_with_cells(vec![
+        ("WEIGHTED_BODYWEIGHT".to_string(), None),
+        ("REPS".to_string(), Some("8".to_string())),
+    ]);
+    let workouts = DataTransformer::new()
+        .with_default_body_weight(75.0)
+        .get_measurements_from_logs(&Some(logs))
+        .unwrap();
+    assert_eq!(workouts[0].exercises[0].sets[0].effective_load, Some(75.0));
+}
+
+#[test]
+fn test_assisted_bodyweight_subtracts_assistance() {
+    use strong_api_lib::data_transformer::LoadType;
+
+    let logs = make_log_with_cells(vec![
+        ("ASSISTED_BODYWEIGHT".to_string(), Some("30".to_string())),
+        ("REPS".to_string(), Some("8".to_string())),
+    ]);
+    let workouts = DataTransformer::new()
+        .with_default_body_weight(80.0)
+        .get_measurements_from_logs(&Some(logs))
+        .unwrap();
+
+    let set = &workouts[0].exercises[0].sets[0];
+    assert_eq!(set.weight, Some(30.0));
+    assert_eq!(set.load_type, LoadType::AssistedBodyweight);
+    assert_eq!(set.effective_load, Some(50.0));
+}
+
+#[test]
+fn test_assistance_uses_resistance_weight_unit() {
+    use strong_api_lib::models::preferences::WeightUnit;
+
+    let logs = make_log_with_cells(vec![
+        ("ASSISTED_BODYWEIGHT".to_string(), Some("50".to_string())),
+        ("REPS".to_string(), Some("8".to_string())),
+    ]);
+    let workouts = DataTransformer::new()
+        .with_preferences(
+            "user",
+            preferences(r#"{"weightUnit": {"user": "KILOGRAMS"}, "resistanceWeightUnit": {"user": "POUNDS"}}"#),
+        )
+        .get_measurements_from_logs(&Some(logs))
+        .unwrap();
+
+    let set = &workouts[0].exercises[0].sets[0];
+    assert_eq!(set.weight_unit, WeightUnit::Pounds);
+    assert!((set.weight.unwrap() - 22.68).abs() < 1e-2);
+}
+
+#[test]
+fn test_bodyweight_set_without_body_weight_is_reported() {
+    let logs = make_log_with_cells(vec![
+        ("WEIGHTED_BODYWEIGHT".to_string(), Some("10".to_string())),
+        ("REPS".to_string(), Some("5".to_string())),
+    ]);
+    let (workouts, diagnostics) = DataTransformer::new()
+        .get_workouts_with_diagnostics(&Some(logs))
+        .unwrap();
+
+    assert_eq!(workouts[0].exercises[0].sets[0].effective_load, None);
+    assert_eq!(diagnostics.missing_body_weights.len(), 1);
+    assert_eq!(diagnostics.missing_body_weights[0].set_id, "cs-cell-test");
+}
+
+#[test]
+fn test_unweighted_set_has_no_effective_load() {
+    use strong_api_lib::data_transformer::LoadType;
+
+    let logs = make_log_with_cells(vec![("REPS".to_string(), Some("20".to_string()))]);
+    let (workouts, diagnostics) = DataTransformer::new()
+        .with_default_body_weight(80.0)
+        .get_workouts_with_diagnostics(&Some(logs))
+        .unwrap();
+
+    let set = &workouts[0].exercises[0].sets[0];
+    assert_eq!(set.load_type, LoadType::Unweighted);
+    assert_eq!(set.effective_load, None);
+    assert!(diagnostics.missing_body_weights.is_empty());
+}
+
+#[test]
+fn test_body_weight_measured_before_the_workout_is_used() {
+    use time::macros::datetime;
+
+    let cells = vec![
+        ("WEIGHTED_BODYWEIGHT".to_string(), Some("20".to_string())),
+        ("REPS".to_string(), Some("5".to_string())),
+    ];
+    let transformer = DataTransformer::new()
+        .with_default_body_weight(70.0)
+        .with_body_weights(vec![
+            body_weight(datetime!(2024-06-01 08:00 UTC), 84.0),
+            body_weight(datetime!(2024-01-01 08:00 UTC), 80.0),
+        ]);
+
+    let in_march = transformer
+        .get_measurements_from_logs(&Some(make_dated_log_with_cells(
+            "2024-03-10T17:00:00Z",
+            cells.clone(),
+        )))
+        .unwrap();
+    assert_eq!(in_march[0].body_weight, Some(80.0));
+    assert_eq!(in_march[0].exercises[0].sets[0].effective_load, Some(100.0));
+
+    let in_july = transformer
+        .get_measurements_from_logs(&Some(make_dated_log_with_cells(
+            "2024-07-10T17:00:00+02:00",
+            cells.clone(),
+        )))
+        .unwrap();
+    assert_eq!(in_july[0].body_weight, Some(84.0));
+
+    // Before the first measurement the configured default is used
+    let in_2023 = transformer
+        .get_measurements_from_logs(&Some(make_dated_log_with_cells(
+            "2023-12-24T10:00:00Z",
+            cells,
+        )))
+        .unwrap();
+    assert_eq!(in_2023[0].body_weight, Some(70.0));
+}
+
+#[test]
+fn test_earliest_body_weight_is_used_without_default() {
+    use time::macros::datetime;
+
+    let logs = make_dated_log_with_cells(
+        "2023-12-24T10:00:00Z",
+        vec![("ASSISTED_BODYWEIGHT".to_string(), Some("10".to_string()))],
+    );
+    let workouts = DataTransformer::new()
+        .with_body_weights(vec![body_weight(datetime!(2024-01-01 08:00 UTC), 80.0)])
+        .get_measurements_from_logs(&Some(logs))
+        .unwrap();
+    assert_eq!(workouts[0].body_weight, Some(80.0));
+    assert_eq!(workouts[0].exercises[0].sets[0].effective_load, Some(70.0));
+}
+
+#[test]
+fn test_workout_start_and_end_time_are_parsed() {
+    let transformer = DataTransformer::new();
+    let workouts = transformer
+        .get_measurements_from_logs(&logs_from_fixture())
+        .unwrap();
+    let workout = &workouts[0];
+    assert!(workout.start_time().unwrap() < workout.end_time().unwrap());
+}
+
+// ---------------------------------------------------------------------------
+// Bar weight — barType, baseWeight and custom bars
+// ---------------------------------------------------------------------------
+
+fn custom_bar() -> strong_api_lib::models::preferences::CustomBarType {
+    strong_api_lib::models::preferences::CustomBarType {
+        id: "sz".to_string(),
+        name: "SZ-Stange".to_string(),
+        weight_in_kilograms: 10.0,
+        weight_in_pounds: 22.05,
+    }
+}
+
+#[test]
+fn test_logged_weight_includes_bar_by_default() {
+    let logs = make_log_with_measurement_link(Some("/api/users/user/measurements/m1"));
+    let workouts = DataTransformer::new()
+        .with_preferences("user", preferences(r#"{"barType": {"m1": "OLYMPIC_BAR"}}"#))
+        .get_measurements_from_logs(&Some(logs))
+        .unwrap();
+
+    let set = &workouts[0].exercises[0].sets[0];
+    assert_eq!(set.bar_weight, Some(20.0));
+    assert_eq!(set.total_load, Some(80.0));
+    assert_eq!(set.effective_load, Some(80.0));
+}
+
+#[test]
+fn test_bar_and_base_weight_are_added_when_excluded() {
+    let logs = make_log_with_measurement_link(Some("/api/users/user/measurements/m1"));
+    let workouts = DataTransformer::new()
+        .with_preferences(
+            "user",
+            preferences(r#"{"barType": {"m1": "sz"}, "baseWeight": {"m1": 5}}"#),
+        )
+        .with_custom_bar_types(vec![custom_bar()])
+        .with_logged_weight_includes_bar(false)
+        .get_measurements_from_logs(&Some(logs))
+        .unwrap();
+
+    let set = &workouts[0].exercises[0].sets[0];
+    assert_eq!(set.weight, Some(80.0));
+    assert_eq!(set.bar_weight, Some(15.0));
+    assert_eq!(set.total_load, Some(95.0));
+    assert_eq!(set.effective_load, Some(95.0));
+}
+
+#[test]
+fn test_without_bar_type_total_load_is_the_weight() {
+    let logs = make_log_with_measurement_link(Some("/api/users/user/measurements/m1"));
+    let workouts = DataTransformer::new()
+        .with_logged_weight_includes_bar(false)
+        .get_measurements_from_logs(&Some(logs))
+        .unwrap();
+
+    let set = &workouts[0].exercises[0].sets[0];
+    assert_eq!(set.bar_weight, None);
+    assert_eq!(set.total_load, Some(80.0));
+}
+
+#[test]
+fn test_bodyweight_sets_have_no_bar_weight() {
+    let logs = make_log_with_cells(vec![
+        ("WEIGHTED_BODYWEIGHT".to_string(), Some("10".to_string())),
+        ("REPS".to_string(), Some("5".to_string())),
+    ]);
+    let workouts = DataTransformer::new()
+        .with_preferences(
+            "user",
+            preferences(r#"{"barType": {"user": "OLYMPIC_BAR"}}"#),
+        )
+        .with_logged_weight_includes_bar(false)
+        .with_default_body_weight(80.0)
+        .get_measurements_from_logs(&Some(logs))
+        .unwrap();
+
+    let set = &workouts[0].exercises[0].sets[0];
+    assert_eq!(set.bar_weight, None);
+    assert_eq!(set.effective_load, Some(90.0));
+}
+
+// ---------------------------------------------------------------------------
+// Exercise aliases
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_aliases_set_the_canonical_name() {
+    use strong_api_lib::data_transformer::aliases::ExerciseAliases;
+
+    let measurements = measurements_from_fixture();
+    let known = &measurements.embedded.measurements[0];
+    let href = format!("/api/users/user/measurements/{}", known.id);
+    let mut logs = make_log_with_measurement_link(Some(&href));
+    logs.extend(make_log_with_measurement_link(Some(
+        "/api/users/user/measurements/other",
+    )));
+
+    let aliases = ExerciseAliases::new([(known.id.as_str(), "Canonical")]);
+    let workouts = DataTransformer::new()
+        .with_measurements_response(measurements.clone())
+        .with_exercise_aliases(aliases)
+        .get_measurements_from_logs(&Some(logs))
+        .unwrap();
+
+    let aliased = &workouts[0].exercises[0];
+    assert_eq!(aliased.name, known.name.to_string());
+    assert_eq!(aliased.canonical_name, "Canonical");
+    assert_eq!(aliased.measurement_id, known.id);
+    // Without an alias the canonical name is the name.
+    let plain = &workouts[1].exercises[0];
+    assert_eq!(plain.canonical_name, plain.name);
+}
+
+// ---------------------------------------------------------------------------
+// Locales
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_names_follow_the_locale_chain() {
+    let mut measurements = measurements_from_fixture();
+    let known = &mut measurements.embedded.measurements[0];
+    known
+        .name
+        .locales
+        .insert("de".to_string(), "Deutscher Name".to_string());
+    let href = format!("/api/users/user/measurements/{}", known.id);
+    let logs = make_log_with_measurement_link(Some(&href));
+
+    let german = DataTransformer::new()
+        .with_measurements_response(measurements.clone())
+        .with_locales(["de-AT", "en"])
+        .get_measurements_from_logs(&Some(logs.clone()))
+        .unwrap();
+    assert_eq!(german[0].exercises[0].name, "Deutscher Name");
+    assert_eq!(german[0].exercises[0].name_locale, "de");
+
+    let default = DataTransformer::new()
+        .with_measurements_response(measurements)
+        .get_measurements_from_logs(&Some(logs))
+        .unwrap();
+    assert_eq!(default[0].exercises[0].name_locale, "en");
+    assert_ne!(default[0].exercises[0].name, "Deutscher Name");
+}
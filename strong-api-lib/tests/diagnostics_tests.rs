@@ -0,0 +1,81 @@
+use strong_api_lib::data_transformer::diagnostics::{
+    Diagnostics, EmptyGroup, MissingBodyWeight, UnknownCellType, UnmatchedExercise,
+    UnparsableValue, UnresolvedMeasurement,
+};
+
+fn diagnostics_with_one_of_each() -> Diagnostics {
+    Diagnostics {
+        unresolved_measurements: vec![UnresolvedMeasurement {
+            log_id: "log-1".to_string(),
+            cell_set_group_id: "csg-1".to_string(),
+            measurement_id: "m-1".to_string(),
+        }],
+        unmatched_exercises: vec![UnmatchedExercise {
+            log_id: "log-1".to_string(),
+            exercise_id: "e-1".to_string(),
+            name: "Zottman Curl".to_string(),
+        }],
+        unparsable_values: vec![UnparsableValue {
+            cell_id: "c-1".to_string(),
+            cell_type: "REPS".to_string(),
+            value: "five".to_string(),
+        }],
+        unknown_cell_types: vec![UnknownCellType {
+            cell_id: "c-2".to_string(),
+            cell_type: "HEART_RATE".to_string(),
+        }],
+        empty_groups: vec![EmptyGroup {
+            log_id: "log-1".to_string(),
+            cell_set_group_id: "csg-2".to_string(),
+        }],
+        missing_body_weights: vec![MissingBodyWeight {
+            log_id: "log-1".to_string(),
+            set_id: "cs-1".to_string(),
+        }],
+    }
+}
+
+#[test]
+fn test_default_diagnostics_is_empty() {
+    let diagnostics = Diagnostics::default();
+    assert!(diagnostics.is_empty());
+    assert_eq!(diagnostics.len(), 0);
+    assert_eq!(diagnostics.to_string(), "Diagnostics: 0 issue(s)");
+}
+
+#[test]
+fn test_len_counts_all_categories() {
+    let diagnostics = diagnostics_with_one_of_each();
+    assert!(!diagnostics.is_empty());
+    assert_eq!(diagnostics.len(), 6);
+}
+
+#[test]
+fn test_merge_appends_issues() {
+    let mut diagnostics = diagnostics_with_one_of_each();
+    diagnostics.merge(diagnostics_with_one_of_each());
+    assert_eq!(diagnostics.len(), 12);
+    assert_eq!(diagnostics.empty_groups.len(), 2);
+}
+
+#[test]
+fn test_display_lists_every_issue() {
+    let text = diagnostics_with_one_of_each().to_string();
+    assert_eq!(
+        text,
+        "Diagnostics: 6 issue(s)\n  \
+         unresolved measurement 'm-1' (log log-1, group csg-1)\n  \
+         unmatched exercise 'Zottman Curl' (log log-1, exercise e-1)\n  \
+         unparsable REPS value 'five' in cell c-1\n  \
+         unknown cell type 'HEART_RATE' in cell c-2\n  \
+         empty group csg-2 in log log-1\n  \
+         missing body weight for set cs-1 in log log-1"
+    );
+}
+
+#[test]
+fn test_serializes_to_json() {
+    let json = serde_json::to_value(diagnostics_with_one_of_each()).unwrap();
+    assert_eq!(json["unparsable_values"][0]["value"], "five");
+    assert_eq!(json["empty_groups"][0]["cell_set_group_id"], "csg-2");
+}
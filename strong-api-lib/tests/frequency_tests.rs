@@ -0,0 +1,203 @@
+use strong_api_lib::analytics::frequency::{RestDays, Streak, training_frequency};
+use strong_api_lib::data_transformer::Workout;
+use time::{Weekday, macros::date};
+
+mod common;
+use common::{user_from_fixture, workout, workouts_from_fixture};
+
+/// Workouts on Monday to Wednesday of the week of 2 December 2024, twice on Tuesday, on
+/// Monday of the week after and on Friday of the week after that.
+fn history() -> Vec<Workout> {
+    vec![
+        workout("w1", "2024-12-02T17:00:00Z", Vec::new()),
+        workout("w2", "2024-12-03T07:00:00Z", Vec::new()),
+        workout("w3", "2024-12-03T18:00:00Z", Vec::new()),
+        workout("w4", "2024-12-04T17:00:00Z", Vec::new()),
+        workout("w5", "2024-12-09T17:00:00Z", Vec::new()),
+        workout("w6", "2024-12-20T17:00:00Z", Vec::new()),
+    ]
+}
+
+// ---------------------------------------------------------------------------
+// Weeks and goal
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_weeks_against_goal() {
+    let frequency = training_frequency(&history(), Weekday::Monday, Some(3), date!(2024 - 12 - 20));
+
+    let weeks: Vec<_> = frequency
+        .weeks
+        .iter()
+        .map(|week| (week.start, week.workouts, week.goal_met))
+        .collect();
+    assert_eq!(
+        weeks,
+        vec![
+            (date!(2024 - 12 - 02), 4, true),
+            (date!(2024 - 12 - 09), 1, false),
+            (date!(2024 - 12 - 16), 1, false),
+        ]
+    );
+    assert_eq!(frequency.goal, 3);
+    assert_eq!(frequency.workouts, 6);
+    assert_eq!(frequency.training_days, 5);
+    assert_eq!(frequency.weeks_goal_met, 1);
+    assert_eq!(frequency.average_workouts_per_week, 2.0);
+}
+
+#[test]
+fn test_weeks_without_workouts_up_to_today_are_listed() {
+    let frequency = training_frequency(&history(), Weekday::Monday, Some(3), date!(2025 - 01 - 01));
+    assert_eq!(frequency.weeks.len(), 5);
+    assert_eq!(frequency.weeks[4].start, date!(2024 - 12 - 30));
+    assert_eq!(frequency.weeks[4].workouts, 0);
+    assert_eq!(frequency.weeks[4].iso_week, 1);
+}
+
+#[test]
+fn test_without_goal_a_workout_meets_the_week() {
+    let frequency = training_frequency(&history(), Weekday::Monday, None, date!(2024 - 12 - 20));
+    assert_eq!(frequency.goal, 1);
+    assert_eq!(frequency.weeks_goal_met, 3);
+    assert_eq!(
+        frequency.longest_weekly_streak,
+        Some(Streak {
+            start: date!(2024 - 12 - 02),
+            end: date!(2024 - 12 - 16),
+            length: 3,
+        })
+    );
+    assert_eq!(
+        frequency.current_weekly_streak,
+        frequency.longest_weekly_streak
+    );
+}
+
+#[test]
+fn test_weeks_start_on_first_weekday() {
+    // With weeks starting on Wednesday, Monday and Tuesday are in the week before.
+    let frequency = training_frequency(
+        &history(),
+        Weekday::Wednesday,
+        Some(3),
+        date!(2024 - 12 - 20),
+    );
+    assert_eq!(frequency.weeks[0].start, date!(2024 - 11 - 27));
+    assert_eq!(frequency.weeks[0].workouts, 3);
+    assert_eq!(frequency.weeks_goal_met, 1);
+}
+
+#[test]
+fn test_days_are_local() {
+    // 23:30 UTC on Sunday is Monday in Berlin, so this week has two workouts.
+    let workouts = vec![
+        workout("w1", "2024-12-08T23:30:00Z", Vec::new()),
+        workout("w2", "2024-12-10T17:00:00Z", Vec::new()),
+    ];
+    let frequency = training_frequency(&workouts, Weekday::Monday, Some(2), date!(2024 - 12 - 10));
+    assert_eq!(frequency.weeks.len(), 1);
+    assert_eq!(frequency.weeks[0].start, date!(2024 - 12 - 09));
+    assert!(frequency.weeks[0].goal_met);
+}
+
+// ---------------------------------------------------------------------------
+// Streaks and rest days
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_daily_streaks() {
+    let frequency = training_frequency(&history(), Weekday::Monday, Some(3), date!(2024 - 12 - 21));
+    assert_eq!(
+        frequency.longest_daily_streak,
+        Some(Streak {
+            start: date!(2024 - 12 - 02),
+            end: date!(2024 - 12 - 04),
+            length: 3,
+        })
+    );
+    // The streak of Friday is still current on Saturday, but not on Sunday.
+    assert_eq!(
+        frequency.current_daily_streak.map(|streak| streak.length),
+        Some(1)
+    );
+    let frequency = training_frequency(&history(), Weekday::Monday, Some(3), date!(2024 - 12 - 22));
+    assert_eq!(frequency.current_daily_streak, None);
+}
+
+#[test]
+fn test_current_weekly_streak_survives_the_running_week() {
+    let workouts = vec![
+        workout("w1", "2024-12-02T17:00:00Z", Vec::new()),
+        workout("w2", "2024-12-09T17:00:00Z", Vec::new()),
+    ];
+    // Nothing trained yet in the week of 16 December, but it isn't over.
+    let frequency = training_frequency(&workouts, Weekday::Monday, None, date!(2024 - 12 - 17));
+    assert_eq!(
+        frequency.current_weekly_streak.map(|streak| streak.length),
+        Some(2)
+    );
+    let frequency = training_frequency(&workouts, Weekday::Monday, None, date!(2024 - 12 - 23));
+    assert_eq!(frequency.current_weekly_streak, None);
+}
+
+#[test]
+fn test_rest_day_distribution() {
+    let frequency = training_frequency(&history(), Weekday::Monday, Some(3), date!(2024 - 12 - 20));
+    assert_eq!(
+        frequency.rest_days,
+        vec![
+            RestDays { days: 0, count: 2 },
+            RestDays { days: 4, count: 1 },
+            RestDays { days: 10, count: 1 },
+        ]
+    );
+}
+
+#[test]
+fn test_no_workouts() {
+    let frequency = training_frequency(&[], Weekday::Monday, Some(3), date!(2024 - 12 - 20));
+    assert_eq!(frequency.goal, 3);
+    assert!(frequency.weeks.is_empty());
+    assert_eq!(frequency.longest_daily_streak, None);
+}
+
+#[test]
+fn test_display() {
+    let frequency = training_frequency(&history(), Weekday::Monday, Some(3), date!(2024 - 12 - 20));
+    assert_eq!(
+        frequency.to_string(),
+        "Training frequency: 6 workout(s) in 3 week(s), 2.0 per week (goal 3)\
+         \n  weeks goal met: 1 of 3\
+         \n  current streak: 1 day(s), 0 week(s)\
+         \n  longest streak: 3 day(s), 1 week(s)\
+         \n  rest days: 0d 2x 4d 1x 10d 1x"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Fixture
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_goal_and_first_weekday_of_user() {
+    let user = user_from_fixture();
+    assert_eq!(user.workouts_per_week_goal(), Some(3));
+
+    let workouts = workouts_from_fixture();
+    let frequency = training_frequency(
+        &workouts,
+        user.first_weekday(),
+        user.workouts_per_week_goal(),
+        date!(2024 - 12 - 11),
+    );
+    assert_eq!(frequency.workouts, 5);
+    assert_eq!(
+        frequency
+            .weeks
+            .iter()
+            .map(|week| week.workouts)
+            .sum::<u32>(),
+        5
+    );
+}
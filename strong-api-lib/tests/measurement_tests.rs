@@ -25,6 +25,7 @@ fn test_name_display_en() {
     let name = Name {
         en: Some("Bench Press".to_string()),
         custom: None,
+        ..Default::default()
     };
     assert_eq!(name.to_string(), "Bench Press");
 }
@@ -34,6 +35,7 @@ fn test_name_display_custom_fallback() {
     let name = Name {
         en: None,
         custom: Some("My Exercise".to_string()),
+        ..Default::default()
     };
     assert_eq!(name.to_string(), "My Exercise");
 }
@@ -43,6 +45,7 @@ fn test_name_display_unknown_fallback() {
     let name = Name {
         en: None,
         custom: None,
+        ..Default::default()
     };
     assert_eq!(name.to_string(), "Unknown");
 }
@@ -52,6 +55,7 @@ fn test_name_from_name_en() {
     let name = Name {
         en: Some("Squat".to_string()),
         custom: None,
+        ..Default::default()
     };
     assert_eq!(String::from(name), "Squat");
 }
@@ -61,6 +65,7 @@ fn test_name_from_name_custom_fallback() {
     let name = Name {
         en: None,
         custom: Some("Custom".to_string()),
+        ..Default::default()
     };
     assert_eq!(String::from(name), "Custom");
 }
@@ -70,6 +75,7 @@ fn test_name_from_name_unknown_fallback() {
     let name = Name {
         en: None,
         custom: None,
+        ..Default::default()
     };
     assert_eq!(String::from(name), "Unknown");
 }
@@ -81,6 +87,87 @@ fn test_name_from_string() {
     assert!(name.custom.is_none());
 }
 
+// ---------------------------------------------------------------------------
+// Name — locales
+// ---------------------------------------------------------------------------
+
+fn multi_locale_name() -> Name {
+    serde_json::from_str(r#"{"en": "Bench Press", "de": "Bankdrücken", "fr": "Développé couché"}"#)
+        .unwrap()
+}
+
+#[test]
+fn test_name_captures_all_locales() {
+    let name = multi_locale_name();
+    assert_eq!(name.en.as_deref(), Some("Bench Press"));
+    assert_eq!(name.locales.len(), 2);
+    assert_eq!(name.get("de"), Some("Bankdrücken"));
+    assert_eq!(name.get("it"), None);
+
+    let value = serde_json::to_value(&name).unwrap();
+    assert_eq!(value["de"], "Bankdrücken");
+    assert!(value.get("locales").is_none());
+}
+
+#[test]
+fn test_name_skips_locales_without_a_string() {
+    let name: Name = serde_json::from_str(
+        r#"{"en": "Bench Press", "de": null, "fr": 3, "es": {"text": "Press"}, "it": "Panca"}"#,
+    )
+    .unwrap();
+    assert_eq!(name.locales.len(), 1);
+    assert_eq!(name.get("de"), None);
+    assert_eq!(name.localized(&["de", "it"]), Some(("Panca", "it")));
+}
+
+#[test]
+fn test_name_region_falls_back_to_language() {
+    let name = multi_locale_name();
+    assert_eq!(name.get("de-AT"), Some("Bankdrücken"));
+    assert_eq!(name.get("en_GB"), Some("Bench Press"));
+}
+
+#[test]
+fn test_name_localized_follows_preference_chain() {
+    let name = multi_locale_name();
+    assert_eq!(
+        name.localized(&["it", "fr", "de"]),
+        Some(("Développé couché", "fr"))
+    );
+    assert_eq!(name.localized(&["de-CH"]), Some(("Bankdrücken", "de")));
+    assert_eq!(name.localized::<&str>(&[]), Some(("Bench Press", "en")));
+}
+
+#[test]
+fn test_name_localized_fallbacks() {
+    let custom = Name {
+        custom: Some("My Bench".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(custom.localized(&["de"]), Some(("My Bench", "custom")));
+
+    let german_only: Name = serde_json::from_str(r#"{"de": "Kniebeuge"}"#).unwrap();
+    assert_eq!(german_only.localized(&["fr"]), Some(("Kniebeuge", "de")));
+    assert_eq!(Name::default().localized(&["de"]), None);
+}
+
+#[test]
+fn test_name_default_conversions_ignore_other_locales() {
+    let german_only: Name = serde_json::from_str(r#"{"de": "Kniebeuge"}"#).unwrap();
+    assert_eq!(german_only.to_string(), "Unknown");
+    assert_eq!(String::from(multi_locale_name()), "Bench Press");
+}
+
+#[test]
+fn test_name_values_lists_all_locales() {
+    let name = multi_locale_name();
+    let values: Vec<&str> = name.values().collect();
+    assert_eq!(
+        values,
+        vec!["Bench Press", "Bankdrücken", "Développé couché"]
+    );
+}
+
 // ---------------------------------------------------------------------------
 // MeasurementsResponse::merge — the only hand-written logic in measurement.rs
 // ---------------------------------------------------------------------------
@@ -0,0 +1,170 @@
+use strong_api_lib::analytics::one_rep_max::{
+    OneRepMaxFormula, best_one_rep_maxes, rpe_table_load,
+};
+use strong_api_lib::data_transformer::{Exercise, Workout};
+
+mod common;
+use common::{SetExt, exercise, set, set_at_rpe, workouts_from_fixture};
+
+fn assert_close(actual: Option<f32>, expected: f32) {
+    let actual = actual.expect("expected an estimate");
+    assert!(
+        (actual - expected).abs() < 0.01,
+        "expected {expected}, got {actual}"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Formulas
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_formulas() {
+    assert_close(OneRepMaxFormula::Epley.estimate(100.0, 5, None), 116.67);
+    assert_close(OneRepMaxFormula::Brzycki.estimate(100.0, 5, None), 112.5);
+    assert_close(OneRepMaxFormula::Lombardi.estimate(100.0, 5, None), 117.46);
+}
+
+#[test]
+fn test_single_rep_is_the_one_rep_max() {
+    for formula in [
+        OneRepMaxFormula::Epley,
+        OneRepMaxFormula::Brzycki,
+        OneRepMaxFormula::Lombardi,
+    ] {
+        assert_eq!(formula.estimate(140.0, 1, None), Some(140.0));
+    }
+    assert_eq!(
+        OneRepMaxFormula::RpeTable.estimate(140.0, 1, Some(10.0)),
+        Some(140.0)
+    );
+}
+
+#[test]
+fn test_no_estimate_without_load_or_reps() {
+    for formula in OneRepMaxFormula::ALL {
+        assert_eq!(formula.estimate(0.0, 5, Some(8.0)), None);
+        assert_eq!(formula.estimate(100.0, 0, Some(8.0)), None);
+    }
+    assert_eq!(OneRepMaxFormula::Brzycki.estimate(20.0, 37, None), None);
+}
+
+#[test]
+fn test_rpe_table() {
+    // 5 reps at RPE 8 are 7 reps to failure, 81.1% of the one-rep max.
+    assert_close(
+        OneRepMaxFormula::RpeTable.estimate(81.1, 5, Some(8.0)),
+        100.0,
+    );
+    // Half RPE steps are interpolated: 3 reps at RPE 9.5 are 3.5 reps to failure.
+    assert_close(
+        OneRepMaxFormula::RpeTable.estimate(90.7, 3, Some(9.5)),
+        100.0,
+    );
+    assert_eq!(OneRepMaxFormula::RpeTable.estimate(100.0, 5, None), None);
+    assert_eq!(
+        OneRepMaxFormula::RpeTable.estimate(100.0, 5, Some(5.0)),
+        None
+    );
+    assert_eq!(
+        OneRepMaxFormula::RpeTable.estimate(100.0, 13, Some(8.0)),
+        None
+    );
+}
+
+#[test]
+fn test_rpe_table_load_is_the_inverse_of_the_estimate() {
+    assert_close(rpe_table_load(100.0, 5, 8.0), 81.1);
+    let load = rpe_table_load(140.0, 3, 9.5).unwrap();
+    assert_close(
+        OneRepMaxFormula::RpeTable.estimate(load, 3, Some(9.5)),
+        140.0,
+    );
+    assert_eq!(rpe_table_load(100.0, 0, 8.0), None);
+    assert_eq!(rpe_table_load(100.0, 13, 8.0), None);
+}
+
+#[test]
+fn test_incomplete_sets_have_no_estimate() {
+    let mut incomplete = set(100.0, 5);
+    incomplete.is_completed = false;
+    assert_eq!(OneRepMaxFormula::Epley.estimate_set(&incomplete), None);
+}
+
+#[test]
+fn test_formula_serializes_like_display() {
+    for formula in OneRepMaxFormula::ALL {
+        assert_eq!(serde_json::to_value(formula).unwrap(), formula.to_string());
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Best per exercise and workout
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_best_set_per_exercise_and_formula() {
+    let workout = Workout {
+        id: "w1".to_string(),
+        exercises: vec![exercise(
+            "",
+            "Squat",
+            vec![
+                set_at_rpe(150.0, 1, 10.0).with_id("heavy"),
+                set_at_rpe(100.0, 10, 6.0).with_id("easy"),
+            ],
+        )],
+        ..Default::default()
+    };
+
+    let best = best_one_rep_maxes(&workout);
+    let formulas: Vec<_> = best.iter().map(|entry| entry.formula).collect();
+    assert_eq!(formulas, OneRepMaxFormula::ALL);
+    let set_ids: Vec<_> = best.iter().map(|entry| entry.set_id.as_str()).collect();
+    // Only the RPE table credits the reps left in reserve of the easy set.
+    assert_eq!(set_ids, vec!["heavy", "heavy", "heavy", "easy"]);
+    assert!(best.iter().all(|entry| entry.workout_id == "w1"));
+}
+
+#[test]
+fn test_exercises_are_grouped_by_canonical_name() {
+    let alias = Exercise {
+        id: "e2".to_string(),
+        canonical_name: "Bench Press".to_string(),
+        ..exercise("", "Bench", vec![set(105.0, 3)])
+    };
+    let workout = Workout {
+        exercises: vec![
+            exercise("", "Bench Press", vec![set(100.0, 3)]),
+            exercise("", "Row", vec![set(80.0, 8)]),
+            alias,
+        ],
+        ..Default::default()
+    };
+
+    let epley: Vec<_> = best_one_rep_maxes(&workout)
+        .into_iter()
+        .filter(|entry| entry.formula == OneRepMaxFormula::Epley)
+        .collect();
+    assert_eq!(epley.len(), 2);
+    assert_eq!(epley[0].exercise, "Bench Press");
+    assert_eq!(epley[0].exercise_id, "e2");
+    assert_eq!(epley[0].set_id, "set-105-3");
+    assert_eq!(epley[1].exercise, "Row");
+}
+
+#[test]
+fn test_best_one_rep_maxes_of_fixture() {
+    for workout in workouts_from_fixture() {
+        for best in best_one_rep_maxes(&workout) {
+            assert!(best.one_rep_max >= best.effective_load);
+            assert_eq!(best.start_date, workout.start_date);
+            assert_ne!(best.formula, OneRepMaxFormula::RpeTable);
+        }
+    }
+    assert!(
+        workouts_from_fixture()
+            .iter()
+            .any(|workout| !best_one_rep_maxes(workout).is_empty())
+    );
+}
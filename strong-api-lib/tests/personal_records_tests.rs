@@ -0,0 +1,201 @@
+use strong_api_lib::analytics::one_rep_max::OneRepMaxFormula;
+use strong_api_lib::analytics::personal_records::{
+    PersonalRecord, PersonalRecords, RecordKind, personal_record_timeline,
+};
+
+mod common;
+use common::{exercise, set, workout, workouts_from_fixture};
+
+fn of_kind(records: &[PersonalRecord], kind: RecordKind) -> Vec<&PersonalRecord> {
+    records
+        .iter()
+        .filter(|record| record.kind == kind)
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Record kinds
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_first_workout_sets_all_records() {
+    let records = PersonalRecords::new(OneRepMaxFormula::Epley).add_workout(&workout(
+        "w1",
+        "2024-01-01T10:00:00Z",
+        vec![exercise("", "Squat", vec![set(100.0, 5)])],
+    ));
+
+    let kinds: Vec<_> = records.iter().map(|record| record.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            RecordKind::HeaviestWeight,
+            RecordKind::MostReps,
+            RecordKind::BestOneRepMax,
+            RecordKind::BestSetVolume,
+            RecordKind::BestSessionVolume,
+        ]
+    );
+    assert!(records.iter().all(|record| record.previous.is_none()));
+    assert!(records.iter().all(|record| record.workout_id == "w1"));
+    assert_eq!(records[0].set_id.as_deref(), Some("set-100-5"));
+    assert_eq!(records[4].set_id, None);
+}
+
+#[test]
+fn test_records_reference_the_best_set_and_previous_value() {
+    let mut records = PersonalRecords::new(OneRepMaxFormula::Epley);
+    records.add_workout(&workout(
+        "w1",
+        "2024-01-01T10:00:00Z",
+        vec![exercise("", "Squat", vec![set(100.0, 5)])],
+    ));
+    let new = records.add_workout(&workout(
+        "w2",
+        "2024-01-08T10:00:00Z",
+        vec![exercise(
+            "",
+            "Squat",
+            vec![set(102.5, 3), set(105.0, 1), set(95.0, 3)],
+        )],
+    ));
+
+    let heaviest = of_kind(&new, RecordKind::HeaviestWeight);
+    assert_eq!(heaviest.len(), 1);
+    assert_eq!(heaviest[0].set_id.as_deref(), Some("set-105-1"));
+    assert_eq!(heaviest[0].value, 105.0);
+    assert_eq!(heaviest[0].previous, Some(100.0));
+    // 3 × 102.5 kg is no e1RM record, while the workout's volume beats the 500 kg before.
+    assert!(of_kind(&new, RecordKind::BestOneRepMax).is_empty());
+    let session = of_kind(&new, RecordKind::BestSessionVolume);
+    assert_eq!(session[0].value, 697.5);
+    assert_eq!(session[0].previous, Some(500.0));
+}
+
+#[test]
+fn test_equal_values_are_no_record() {
+    let mut records = PersonalRecords::new(OneRepMaxFormula::Epley);
+    records.add_workout(&workout(
+        "w1",
+        "2024-01-01T10:00:00Z",
+        vec![exercise("", "Squat", vec![set(100.0, 5)])],
+    ));
+    let new = records.add_workout(&workout(
+        "w2",
+        "2024-01-08T10:00:00Z",
+        vec![exercise("", "Squat", vec![set(100.0, 5)])],
+    ));
+    assert!(new.is_empty());
+}
+
+#[test]
+fn test_most_reps_counts_heavier_loads() {
+    let mut records = PersonalRecords::new(OneRepMaxFormula::Epley);
+    records.add_workout(&workout(
+        "w1",
+        "2024-01-01T10:00:00Z",
+        vec![exercise("", "Squat", vec![set(100.0, 5)])],
+    ));
+    let new = records.add_workout(&workout(
+        "w2",
+        "2024-01-08T10:00:00Z",
+        vec![exercise(
+            "",
+            "Squat",
+            vec![
+                // Fewer reps than at 100 kg
+                set(90.0, 4),
+                // Beaten by s4 in the same workout
+                set(80.0, 7),
+                set(80.0, 8),
+                set(100.0, 6),
+            ],
+        )],
+    ));
+
+    let reps = of_kind(&new, RecordKind::MostReps);
+    let sets: Vec<_> = reps
+        .iter()
+        .map(|record| {
+            (
+                record.set_id.as_deref().unwrap(),
+                record.value,
+                record.previous,
+            )
+        })
+        .collect();
+    assert_eq!(
+        sets,
+        vec![("set-80-8", 8.0, Some(5.0)), ("set-100-6", 6.0, Some(5.0))]
+    );
+}
+
+#[test]
+fn test_incomplete_sets_set_no_records() {
+    let mut incomplete = set(200.0, 1);
+    incomplete.is_completed = false;
+    let records = PersonalRecords::new(OneRepMaxFormula::Epley).add_workout(&workout(
+        "w1",
+        "2024-01-01T10:00:00Z",
+        vec![exercise("", "Squat", vec![incomplete])],
+    ));
+    assert!(records.is_empty());
+}
+
+// ---------------------------------------------------------------------------
+// Timeline
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_timeline_is_chronological() {
+    let workouts = vec![
+        workout(
+            "late",
+            "2024-02-01T10:00:00Z",
+            vec![exercise("", "Squat", vec![set(110.0, 1)])],
+        ),
+        workout(
+            "early",
+            "2024-01-01T10:00:00Z",
+            vec![exercise("", "Squat", vec![set(100.0, 1)])],
+        ),
+    ];
+
+    let heaviest: Vec<_> = personal_record_timeline(&workouts, OneRepMaxFormula::Epley)
+        .into_iter()
+        .filter(|record| record.kind == RecordKind::HeaviestWeight)
+        .map(|record| (record.workout_id, record.previous))
+        .collect();
+    assert_eq!(
+        heaviest,
+        vec![
+            ("early".to_string(), None),
+            ("late".to_string(), Some(100.0))
+        ]
+    );
+}
+
+#[test]
+fn test_timeline_of_fixture() {
+    let workouts = workouts_from_fixture();
+    let timeline = personal_record_timeline(&workouts, OneRepMaxFormula::Epley);
+
+    assert!(!timeline.is_empty());
+    for record in &timeline {
+        assert!(
+            record
+                .previous
+                .is_none_or(|previous| record.value > previous)
+        );
+        let workout = workouts.iter().find(|w| w.id == record.workout_id).unwrap();
+        if let Some(set_id) = &record.set_id {
+            assert!(
+                workout
+                    .exercises
+                    .iter()
+                    .flat_map(|exercise| &exercise.sets)
+                    .any(|set| &set.id == set_id)
+            );
+        }
+    }
+}
@@ -0,0 +1,350 @@
+use strong_api_lib::data_transformer::{DataTransformer, Set, Workout};
+use strong_api_lib::models::preferences::WeightUnit;
+use strong_api_lib::models::workout::UserResponse;
+use strong_api_lib::planner::{Adjustment, Planner, ProgressionRule};
+
+mod common;
+use common::{
+    SetExt, WorkoutExt, exercise, load_fixture, measurements_from_fixture, set, set_at_rpe,
+    target_set, template, user_from_fixture, workout,
+};
+
+const SQUAT_ID: &str = "b2f5a2de-c684-4e94-a6e5-581e0695fcac";
+const BENCH_ID: &str = "ca9ee259-a69f-4839-bbf9-46ba8cf0d7d6";
+
+fn squat_template() -> Workout {
+    template(
+        "template",
+        "Workout A",
+        vec![exercise(SQUAT_ID, "Squat", vec![target_set(5); 3])],
+    )
+}
+
+fn squat_session(id: &str, date: &str, sets: Vec<Set>) -> Workout {
+    workout(
+        id,
+        &format!("{date}T10:00:00Z"),
+        vec![exercise(SQUAT_ID, "Squat", sets)],
+    )
+    .with_name("Workout A")
+}
+
+fn weights(workout: &Workout) -> Vec<Option<f32>> {
+    workout.exercises[0]
+        .sets
+        .iter()
+        .map(|set| set.weight)
+        .collect()
+}
+
+fn reps(workout: &Workout) -> Vec<u32> {
+    workout.exercises[0]
+        .sets
+        .iter()
+        .map(|set| set.reps)
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Linear progression
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_linear_progression_adds_weight_after_a_successful_session() {
+    let history = vec![squat_session("w1", "2024-05-01", vec![set(100.0, 5); 3])];
+
+    let plan =
+        Planner::new(ProgressionRule::Linear { increment: 2.5 }).plan(&squat_template(), &history);
+
+    assert_eq!(weights(&plan.workout), vec![Some(102.5); 3]);
+    assert_eq!(reps(&plan.workout), vec![5, 5, 5]);
+    assert_eq!(plan.exercises[0].adjustment, Adjustment::IncreasedWeight);
+    assert_eq!(plan.exercises[0].last_session.as_deref(), Some("w1"));
+    assert!(
+        plan.workout.exercises[0]
+            .sets
+            .iter()
+            .all(|set| !set.is_completed && set.id.is_empty())
+    );
+}
+
+#[test]
+fn test_linear_progression_repeats_the_weight_after_missed_reps() {
+    let history = vec![squat_session(
+        "w1",
+        "2024-05-01",
+        vec![set(100.0, 5), set(100.0, 5), set(100.0, 4)],
+    )];
+
+    let plan =
+        Planner::new(ProgressionRule::Linear { increment: 2.5 }).plan(&squat_template(), &history);
+
+    assert_eq!(weights(&plan.workout), vec![Some(100.0); 3]);
+    assert_eq!(plan.exercises[0].adjustment, Adjustment::RepeatedWeight);
+}
+
+#[test]
+fn test_the_latest_session_is_used_and_incomplete_sets_are_ignored() {
+    let history = vec![
+        squat_session("w2", "2024-05-08", vec![set(105.0, 5); 3]),
+        squat_session("w3", "2024-05-15", vec![set(110.0, 5).incomplete()]),
+        squat_session("w1", "2024-05-01", vec![set(100.0, 5); 3]),
+    ];
+
+    let plan =
+        Planner::new(ProgressionRule::Linear { increment: 5.0 }).plan(&squat_template(), &history);
+
+    assert_eq!(plan.exercises[0].last_session.as_deref(), Some("w2"));
+    assert_eq!(weights(&plan.workout), vec![Some(110.0); 3]);
+}
+
+// ---------------------------------------------------------------------------
+// Double progression
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_double_progression_adds_reps_then_weight() {
+    let rule = ProgressionRule::Double {
+        min_reps: 8,
+        max_reps: 12,
+        increment: 2.5,
+    };
+    let template = template(
+        "template",
+        "Workout A",
+        vec![exercise(BENCH_ID, "Bench", vec![target_set(0); 3])],
+    );
+
+    let history = vec![
+        workout(
+            "w1",
+            "2024-05-01T10:00:00Z",
+            vec![exercise(
+                BENCH_ID,
+                "Bench",
+                vec![set(60.0, 10), set(60.0, 12), set(60.0, 8)],
+            )],
+        )
+        .with_name("Workout A"),
+    ];
+    let plan = Planner::new(rule).plan(&template, &history);
+    assert_eq!(weights(&plan.workout), vec![Some(60.0); 3]);
+    assert_eq!(reps(&plan.workout), vec![11, 12, 9]);
+    assert_eq!(plan.exercises[0].adjustment, Adjustment::AddedReps);
+
+    let history = vec![
+        workout(
+            "w2",
+            "2024-05-08T10:00:00Z",
+            vec![exercise(BENCH_ID, "Bench", vec![set(60.0, 12); 3])],
+        )
+        .with_name("Workout A"),
+    ];
+    let plan = Planner::new(rule).plan(&template, &history);
+    assert_eq!(weights(&plan.workout), vec![Some(62.5); 3]);
+    assert_eq!(reps(&plan.workout), vec![8, 8, 8]);
+    assert_eq!(plan.exercises[0].adjustment, Adjustment::IncreasedWeight);
+}
+
+#[test]
+fn test_double_progression_with_an_inverted_rep_range() {
+    let error = serde_json::from_str::<ProgressionRule>(
+        r#"{"rule": "double", "min_reps": 12, "max_reps": 8, "increment": 2.5}"#,
+    )
+    .unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("invalid double rule: min_reps 12 is above max_reps 8")
+    );
+
+    // Rules built in code aren't checked, planning with them doesn't panic.
+    let rule = ProgressionRule::Double {
+        min_reps: 12,
+        max_reps: 8,
+        increment: 2.5,
+    };
+    assert!(rule.validate().is_err());
+    let template = template(
+        "template",
+        "Workout A",
+        vec![exercise(BENCH_ID, "Bench", vec![target_set(0)])],
+    );
+    let history = vec![
+        workout(
+            "w1",
+            "2024-05-01T10:00:00Z",
+            vec![exercise(BENCH_ID, "Bench", vec![set(60.0, 6)])],
+        )
+        .with_name("Workout A"),
+    ];
+    let plan = Planner::new(rule).plan(&template, &history);
+    assert_eq!(reps(&plan.workout), vec![12]);
+}
+
+// ---------------------------------------------------------------------------
+// RPE autoregulation
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_rpe_autoregulation_derives_the_weight_from_the_estimated_max() {
+    // 100 kg for 5 at RPE 8 estimates a max of 123.3 kg, 5 reps at RPE 9 are 83.7% of it.
+    let history = vec![squat_session(
+        "w1",
+        "2024-05-01",
+        vec![set_at_rpe(100.0, 5, 8.0)],
+    )];
+
+    let plan = Planner::new(ProgressionRule::RpeAutoregulation { target_rpe: 9.0 })
+        .with_rounding(2.5)
+        .plan(&squat_template(), &history);
+
+    assert_eq!(weights(&plan.workout), vec![Some(102.5); 3]);
+    assert!(
+        plan.workout.exercises[0]
+            .sets
+            .iter()
+            .all(|set| set.rpe == Some(9.0))
+    );
+    assert_eq!(plan.exercises[0].adjustment, Adjustment::Autoregulated);
+}
+
+#[test]
+fn test_rpe_autoregulation_leaves_the_bar_out_of_the_logged_weight() {
+    let mut set = set_at_rpe(80.0, 5, 8.0);
+    set.effective_load = Some(100.0);
+    let history = vec![squat_session("w1", "2024-05-01", vec![set])];
+
+    let plan = Planner::new(ProgressionRule::RpeAutoregulation { target_rpe: 9.0 })
+        .with_rounding(2.5)
+        .plan(&squat_template(), &history);
+
+    assert_eq!(weights(&plan.workout), vec![Some(82.5); 3]);
+}
+
+// ---------------------------------------------------------------------------
+// Rules, units and history
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_exercises_without_history_keep_the_template() {
+    let plan = Planner::default().plan(&squat_template(), &[]);
+
+    assert_eq!(weights(&plan.workout), vec![None; 3]);
+    assert_eq!(reps(&plan.workout), vec![5, 5, 5]);
+    assert_eq!(plan.exercises[0].adjustment, Adjustment::NoHistory);
+    assert_eq!(plan.exercises[0].last_session, None);
+}
+
+#[test]
+fn test_rules_per_measurement_and_pound_increments() {
+    let pounds = |weight: f32, reps: u32| Set {
+        weight_unit: WeightUnit::Pounds,
+        ..set(WeightUnit::Pounds.to_kilograms(weight), reps)
+    };
+    let target = Set {
+        weight_unit: WeightUnit::Pounds,
+        ..target_set(5)
+    };
+    let template = template(
+        "template",
+        "Workout A",
+        vec![
+            exercise(SQUAT_ID, "Squat", vec![target.clone()]),
+            exercise(BENCH_ID, "Bench", vec![target]),
+        ],
+    );
+    let history = vec![
+        workout(
+            "w1",
+            "2024-05-01T10:00:00Z",
+            vec![
+                exercise(SQUAT_ID, "Squat", vec![pounds(225.0, 5)]),
+                exercise(BENCH_ID, "Bench", vec![pounds(135.0, 5)]),
+            ],
+        )
+        .with_name("Workout A"),
+    ];
+
+    let plan = Planner::new(ProgressionRule::Linear { increment: 10.0 })
+        .with_rule(BENCH_ID, ProgressionRule::Linear { increment: 5.0 })
+        .with_rounding(5.0)
+        .plan(&template, &history);
+
+    let planned: Vec<f32> = plan
+        .workout
+        .exercises
+        .iter()
+        .map(|exercise| WeightUnit::Pounds.from_kilograms(exercise.sets[0].weight.unwrap()))
+        .collect();
+    assert!((planned[0] - 235.0).abs() < 0.01, "{planned:?}");
+    assert!((planned[1] - 140.0).abs() < 0.01, "{planned:?}");
+    assert_eq!(
+        plan.to_string(),
+        "Plan for Workout A: 2 exercise(s)\
+         \n  Squat: 235 lb x 5 (linear, increased_weight)\
+         \n  Bench: 140 lb x 5 (linear, increased_weight)"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Templates from the fixtures
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_plan_of_a_fixture_template_becomes_a_template_log() {
+    let user: UserResponse =
+        serde_json::from_str(&load_fixture("user_response_all_includes.json")).unwrap();
+    let history_user = user_from_fixture();
+    let transformer = DataTransformer::new()
+        .with_measurements_response(measurements_from_fixture())
+        .with_preferences(&history_user.id, history_user.preferences.clone());
+
+    let templates = user.templates();
+    assert_eq!(templates.len(), 5);
+    assert!(
+        templates
+            .iter()
+            .all(|template| template.log_type == "TEMPLATE")
+    );
+    let five_by_five = templates
+        .iter()
+        .find(|template| template.id == "4af63d92-425f-4ed2-ae12-aa1d9a758881")
+        .unwrap();
+
+    let template = &transformer
+        .get_measurements_from_logs(&Some(vec![five_by_five.clone()]))
+        .unwrap()[0];
+    let history = transformer
+        .get_measurements_from_logs(&history_user.embedded.log)
+        .unwrap();
+    let plan = Planner::new(ProgressionRule::Linear { increment: 2.5 }).plan(template, &history);
+
+    let bench = &plan.exercises[1];
+    assert_eq!(bench.measurement_id, BENCH_ID);
+    assert!(bench.last_session.is_some());
+    assert!(
+        plan.workout.exercises[1]
+            .sets
+            .iter()
+            .all(|set| set.weight.is_some() && set.reps == 5)
+    );
+
+    let log = transformer.workout_to_template(&plan.workout).unwrap();
+    assert_eq!(log.log_type, "TEMPLATE");
+    assert_eq!(log.name, five_by_five.name);
+    assert_eq!(log.start_date, None);
+    assert_ne!(log.id, five_by_five.id);
+    assert_eq!(
+        log.links["self"]["href"],
+        format!("/api/users/{}/templates/{}", history_user.id, log.id)
+    );
+    assert_eq!(log.embedded.cell_set_group.len(), 3);
+    assert!(
+        log.embedded
+            .cell_set_group
+            .iter()
+            .flat_map(|group| &group.cell_sets)
+            .all(|cell_set| cell_set.is_completed == Some(false))
+    );
+}
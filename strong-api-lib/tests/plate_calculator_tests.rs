@@ -0,0 +1,114 @@
+use strong_api_lib::models::preferences::WeightUnit;
+use strong_api_lib::plate_calculator::{Plate, plate_breakdown, round_to_plates};
+
+fn plates(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+fn per_side(breakdown: &strong_api_lib::plate_calculator::PlateBreakdown) -> Vec<(f32, u32)> {
+    breakdown
+        .plates
+        .iter()
+        .map(|count| (count.plate.weight, count.per_side))
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Plate names
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_plate_names_are_parsed() {
+    assert_eq!(
+        Plate::parse("PLATE_20KG"),
+        Some(Plate {
+            weight: 20.0,
+            unit: WeightUnit::Kilograms
+        })
+    );
+    assert_eq!(Plate::parse("PLATE_2_5KG").unwrap().weight, 2.5);
+    assert_eq!(Plate::parse("PLATE_1_25KG").unwrap().weight, 1.25);
+    assert_eq!(Plate::parse("PLATE_45LB").unwrap().unit, WeightUnit::Pounds);
+}
+
+#[test]
+fn test_invalid_plate_names_are_rejected() {
+    assert_eq!(Plate::parse("PLATE_KG"), None);
+    assert_eq!(Plate::parse("PLATE_0KG"), None);
+    assert_eq!(Plate::parse("BUMPER_20KG"), None);
+    assert_eq!(Plate::parse("PLATE_20ST"), None);
+}
+
+// ---------------------------------------------------------------------------
+// Breakdown
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_breakdown_picks_heaviest_plates_first() {
+    let available = plates(&["PLATE_5KG", "PLATE_20KG", "PLATE_2_5KG", "PLATE_10KG"]);
+    let breakdown = plate_breakdown(100.0, 20.0, &available);
+    assert_eq!(per_side(&breakdown), vec![(20.0, 2)]);
+    assert!(breakdown.is_exact());
+
+    let breakdown = plate_breakdown(115.0, 20.0, &available);
+    assert_eq!(per_side(&breakdown), vec![(20.0, 2), (5.0, 1), (2.5, 1)]);
+    assert_eq!(breakdown.loaded, 115.0);
+}
+
+#[test]
+fn test_unreachable_target_is_rounded_down() {
+    let available = plates(&["PLATE_20KG", "PLATE_2_5KG"]);
+    let breakdown = plate_breakdown(63.0, 20.0, &available);
+    assert_eq!(per_side(&breakdown), vec![(20.0, 1)]);
+    assert_eq!(breakdown.loaded, 60.0);
+    assert_eq!(breakdown.remainder(), 3.0);
+    assert!(!breakdown.is_exact());
+}
+
+#[test]
+fn test_target_below_bar_needs_no_plates() {
+    let breakdown = plate_breakdown(15.0, 20.0, &plates(&["PLATE_20KG"]));
+    assert!(breakdown.plates.is_empty());
+    assert_eq!(breakdown.loaded, 20.0);
+    assert_eq!(breakdown.remainder(), -5.0);
+}
+
+#[test]
+fn test_fractional_plates_add_up_exactly() {
+    let available = plates(&["PLATE_1_25KG", "PLATE_0_5KG"]);
+    let breakdown = plate_breakdown(23.5, 20.0, &available);
+    assert_eq!(per_side(&breakdown), vec![(1.25, 1), (0.5, 1)]);
+    assert!(breakdown.is_exact());
+}
+
+// ---------------------------------------------------------------------------
+// Rounding
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_round_to_plates_picks_the_closest_loadable_weight() {
+    let available = plates(&["PLATE_20KG", "PLATE_10KG", "PLATE_2_5KG", "PLATE_1_25KG"]);
+    assert_eq!(round_to_plates(76.4, 20.0, &available), 77.5);
+    assert_eq!(round_to_plates(76.1, 20.0, &available), 75.0);
+    // Halfway, the lighter weight wins.
+    assert_eq!(round_to_plates(76.25, 20.0, &available), 75.0);
+    assert_eq!(round_to_plates(12.0, 20.0, &available), 20.0);
+}
+
+#[test]
+fn test_round_to_plates_combines_plates_of_uneven_sets() {
+    // 35 kg per side can't be loaded heaviest first, but as 20 and 15 kg.
+    let available = plates(&["PLATE_25KG", "PLATE_20KG", "PLATE_15KG"]);
+    assert_eq!(round_to_plates(90.0, 20.0, &available), 90.0);
+    assert_eq!(round_to_plates(84.0, 20.0, &available), 80.0);
+    assert_eq!(round_to_plates(87.0, 20.0, &available), 90.0);
+    // Nothing loads 5 or 10 kg per side, the next weight is 15 kg per side.
+    assert_eq!(round_to_plates(34.0, 20.0, &available), 20.0);
+    assert_eq!(round_to_plates(40.0, 20.0, &available), 50.0);
+}
+
+#[test]
+fn test_round_to_plates_without_plates_keeps_the_target() {
+    assert_eq!(round_to_plates(76.4, 20.0, &[]), 76.4);
+    assert_eq!(round_to_plates(76.4, 20.0, &plates(&["BUMPER"])), 76.4);
+}
@@ -0,0 +1,198 @@
+use strong_api_lib::models::preferences::{CustomBarType, DistanceUnit, Preferences, WeightUnit};
+use strong_api_lib::models::workout::UserResponse;
+
+mod common;
+use common::load_fixture;
+
+const USER_ID: &str = "0f518518-5180-4518-b518-51b51851b518";
+
+fn preferences_from_fixture() -> Preferences {
+    let json = load_fixture("user_response_all_includes.json");
+    let user: UserResponse = serde_json::from_str(&json).unwrap();
+    user.preferences
+}
+
+// ---------------------------------------------------------------------------
+// Deserialization
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_fixture_preferences_are_typed() {
+    let preferences = preferences_from_fixture();
+    assert_eq!(preferences.weight_unit[USER_ID], WeightUnit::Kilograms);
+    assert_eq!(preferences.distance_unit[USER_ID], DistanceUnit::Kilometers);
+    assert_eq!(preferences.other["lengthUnit"][USER_ID], "CENTIMETERS");
+    assert_eq!(
+        preferences.resistance_weight_unit[USER_ID],
+        WeightUnit::Kilograms
+    );
+    assert_eq!(
+        preferences.bar_type["ca9ee259-a69f-4839-bbf9-46ba8cf0d7d6"],
+        "OLYMPIC_BAR"
+    );
+    assert!(preferences.base_weight.is_empty());
+    assert_eq!(preferences.available_plates(USER_ID).len(), 5);
+    // Everything else is kept untyped
+    assert!(preferences.other.contains_key("restTimer"));
+    assert!(!preferences.other.contains_key("barType"));
+}
+
+#[test]
+fn test_fixture_custom_bar_types_are_parsed() {
+    let json = load_fixture("user_response_all_includes.json");
+    let user: UserResponse = serde_json::from_str(&json).unwrap();
+    assert_eq!(user.legacy_custom_bar_types.len(), 1);
+    assert_eq!(user.legacy_custom_bar_types[0].name, "SZ-Stange");
+    assert_eq!(user.legacy_custom_bar_types[0].weight_in_kilograms, 10.0);
+}
+
+#[test]
+fn test_null_custom_bar_types_are_empty() {
+    let mut user: serde_json::Value =
+        serde_json::from_str(&load_fixture("user_response_all_includes.json")).unwrap();
+    user["legacyCustomBarTypes"] = serde_json::Value::Null;
+    let parsed: UserResponse = serde_json::from_value(user).unwrap();
+    assert!(parsed.legacy_custom_bar_types.is_empty());
+}
+
+#[test]
+fn test_empty_preferences_deserialize_to_defaults() {
+    let preferences: Preferences = serde_json::from_str("{}").unwrap();
+    assert_eq!(preferences, Preferences::default());
+}
+
+#[test]
+fn test_null_or_missing_user_preferences_are_defaults() {
+    let mut user: serde_json::Value =
+        serde_json::from_str(&load_fixture("user_response_all_includes.json")).unwrap();
+    user["preferences"] = serde_json::Value::Null;
+    let parsed: UserResponse = serde_json::from_value(user.clone()).unwrap();
+    assert_eq!(parsed.preferences, Preferences::default());
+
+    user.as_object_mut().unwrap().remove("preferences");
+    let parsed: UserResponse = serde_json::from_value(user).unwrap();
+    assert_eq!(parsed.preferences, Preferences::default());
+}
+
+#[test]
+fn test_unknown_units_are_dropped() {
+    let json = r#"{"weightUnit": {"user": "STONES", "m1": "POUNDS"}, "distanceUnit": null}"#;
+    let preferences: Preferences = serde_json::from_str(json).unwrap();
+    assert_eq!(preferences.weight_unit.len(), 1);
+    assert_eq!(preferences.weight_unit["m1"], WeightUnit::Pounds);
+    assert!(preferences.distance_unit.is_empty());
+}
+
+#[test]
+fn test_preferences_serialize_with_backend_names() {
+    let json = r#"{"weightUnit": {"user": "POUNDS"}, "restTimer": {"user": 90}}"#;
+    let preferences: Preferences = serde_json::from_str(json).unwrap();
+    let value = serde_json::to_value(&preferences).unwrap();
+    assert_eq!(value["weightUnit"]["user"], "POUNDS");
+    assert_eq!(value["restTimer"]["user"], 90);
+}
+
+// ---------------------------------------------------------------------------
+// Resolution: measurement override > user-wide setting > metric default
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_measurement_override_wins_over_user_setting() {
+    let json = r#"{"weightUnit": {"user": "KILOGRAMS", "m1": "POUNDS"}}"#;
+    let preferences: Preferences = serde_json::from_str(json).unwrap();
+    assert_eq!(preferences.weight_unit("user", "m1"), WeightUnit::Pounds);
+    assert_eq!(preferences.weight_unit("user", "m2"), WeightUnit::Kilograms);
+}
+
+#[test]
+fn test_missing_settings_fall_back_to_metric() {
+    let preferences = Preferences::default();
+    assert_eq!(preferences.weight_unit("u", "m"), WeightUnit::Kilograms);
+    assert_eq!(
+        preferences.distance_unit("u", "m"),
+        DistanceUnit::Kilometers
+    );
+    assert_eq!(
+        preferences.resistance_weight_unit("u", "m"),
+        WeightUnit::Kilograms
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Bar weight: bar type plus base weight, in kilograms
+// ---------------------------------------------------------------------------
+
+fn custom_bars() -> Vec<CustomBarType> {
+    vec![CustomBarType {
+        id: "sz".to_string(),
+        name: "SZ-Stange".to_string(),
+        weight_in_kilograms: 10.0,
+        weight_in_pounds: 22.05,
+    }]
+}
+
+#[test]
+fn test_bar_weight_of_known_bar_types() {
+    let json = r#"{"barType": {"m1": "OLYMPIC_BAR", "m2": "NONE", "m3": "sz", "m4": "gone"}}"#;
+    let preferences: Preferences = serde_json::from_str(json).unwrap();
+    assert_eq!(
+        preferences.bar_weight("u", "m1", &custom_bars()),
+        Some(20.0)
+    );
+    assert_eq!(preferences.bar_weight("u", "m2", &custom_bars()), Some(0.0));
+    assert_eq!(
+        preferences.bar_weight("u", "m3", &custom_bars()),
+        Some(10.0)
+    );
+    assert_eq!(preferences.bar_weight("u", "m4", &custom_bars()), None);
+    assert_eq!(preferences.bar_weight("u", "m5", &custom_bars()), None);
+}
+
+#[test]
+fn test_bar_weight_uses_the_weight_unit() {
+    let json = r#"{"weightUnit": {"u": "POUNDS"}, "barType": {"m1": "OLYMPIC_BAR", "m2": "sz"}}"#;
+    let preferences: Preferences = serde_json::from_str(json).unwrap();
+    assert_close(
+        preferences.bar_weight("u", "m1", &custom_bars()).unwrap(),
+        20.412,
+    );
+    assert_close(
+        preferences.bar_weight("u", "m2", &custom_bars()).unwrap(),
+        10.002,
+    );
+}
+
+#[test]
+fn test_base_weight_is_added_to_the_bar() {
+    let json = r#"{"barType": {"m1": "OLYMPIC_BAR"}, "baseWeight": {"m1": 2.5, "m2": 30}}"#;
+    let preferences: Preferences = serde_json::from_str(json).unwrap();
+    assert_eq!(preferences.bar_weight("u", "m1", &[]), Some(22.5));
+    assert_eq!(preferences.bar_weight("u", "m2", &[]), Some(30.0));
+}
+
+// ---------------------------------------------------------------------------
+// Unit conversions
+// ---------------------------------------------------------------------------
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 1e-3,
+        "expected {expected}, got {actual}"
+    );
+}
+
+#[test]
+fn test_weight_conversions() {
+    assert_close(WeightUnit::Pounds.to_kilograms(225.0), 102.058);
+    assert_close(WeightUnit::Pounds.from_kilograms(100.0), 220.462);
+    assert_close(WeightUnit::Kilograms.to_kilograms(80.0), 80.0);
+    assert_eq!(WeightUnit::Pounds.to_string(), "lb");
+}
+
+#[test]
+fn test_distance_conversions() {
+    assert_close(DistanceUnit::Miles.to_kilometers(1.0), 1.609);
+    assert_close(DistanceUnit::Meters.to_kilometers(400.0), 0.4);
+    assert_close(DistanceUnit::Miles.from_kilometers(42.195), 26.219);
+    assert_eq!(DistanceUnit::Miles.to_string(), "mi");
+}
@@ -0,0 +1,271 @@
+use strong_api_lib::analytics::one_rep_max::OneRepMaxFormula;
+use strong_api_lib::data_transformer::aliases::ExerciseAliases;
+use strong_api_lib::data_transformer::{DataTransformer, Exercise, LoadType};
+use strong_api_lib::models::common::Name;
+use strong_api_lib::models::measurement::{CellTypeConfig, Measurement, MeasurementsResponse};
+use strong_api_lib::models::preferences::WeightUnit;
+use strong_api_lib::models::workout::Log;
+use strong_api_lib::program::{
+    MissingTrainingMax, ProgramDefinition, ProgramGenerator, TrainingMaxes,
+};
+
+mod common;
+use common::{exercise, fixture_path, set, user_from_fixture, workout};
+
+const BENCH_ID: &str = "ca9ee259-a69f-4839-bbf9-46ba8cf0d7d6";
+const SQUAT_ID: &str = "b2f5a2de-c684-4e94-a6e5-581e0695fcac";
+
+fn five_three_one() -> ProgramDefinition {
+    ProgramDefinition::from_file(fixture_path("program_531.toml")).unwrap()
+}
+
+fn measurement(id: &str, name: Name, cell_types: &[&str]) -> Measurement {
+    Measurement {
+        id: id.to_string(),
+        name,
+        cell_type_configs: cell_types
+            .iter()
+            .map(|cell_type| CellTypeConfig {
+                cell_type: cell_type.to_string(),
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+fn weights(exercise: &Exercise) -> Vec<f32> {
+    exercise
+        .sets
+        .iter()
+        .map(|set| set.weight.unwrap())
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Definitions
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_definition_from_toml_file() {
+    let definition = five_three_one();
+
+    assert_eq!(definition.name, "5/3/1");
+    assert_eq!(definition.weeks(), 4);
+    assert_eq!(definition.days.len(), 2);
+    let squat = &definition.days[1].exercises[0];
+    assert_eq!(squat.training_max, Some(140.0));
+    assert_eq!(squat.weeks[0][0].sets, 1);
+    assert_eq!(squat.weeks[0][2].rpe, Some(8.0));
+    assert_eq!(definition.days[0].exercises[1].weeks[0][0].sets, 5);
+}
+
+#[test]
+fn test_definition_from_json() {
+    let definition = ProgramDefinition::from_json(
+        r#"{
+            "name": "GZCL",
+            "weight_unit": "POUNDS",
+            "days": [{
+                "name": "T1",
+                "exercises": [{
+                    "measurement_id": "squat",
+                    "training_max": 300,
+                    "weeks": [[{ "percent": 85, "reps": 3, "sets": 5 }]]
+                }]
+            }]
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(definition.weight_unit, WeightUnit::Pounds);
+    assert_eq!(definition.weeks(), 1);
+
+    // 85% of 300 lb is 255 lb, not rounded without a user.
+    let workouts = ProgramGenerator::new(TrainingMaxes::default())
+        .generate(&definition)
+        .unwrap();
+    let sets = &workouts[0].exercises[0].sets;
+    assert_eq!(sets.len(), 5);
+    assert!((WeightUnit::Pounds.from_kilograms(sets[0].weight.unwrap()) - 255.0).abs() < 0.01);
+}
+
+// ---------------------------------------------------------------------------
+// Generation
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_mesocycle_is_rounded_to_available_plates() {
+    let generator = ProgramGenerator::new(TrainingMaxes::new([(BENCH_ID, 117.5)]))
+        .with_user(&user_from_fixture());
+    let workouts = generator.generate(&five_three_one()).unwrap();
+
+    let names: Vec<&str> = workouts
+        .iter()
+        .map(|workout| workout.name.as_str())
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "5/3/1 - Week 1 - Bench",
+            "5/3/1 - Week 1 - Squat",
+            "5/3/1 - Week 2 - Bench",
+            "5/3/1 - Week 2 - Squat",
+            "5/3/1 - Week 3 - Bench",
+            "5/3/1 - Week 3 - Squat",
+            "5/3/1 - Week 4 - Bench",
+            "5/3/1 - Week 4 - Squat",
+        ]
+    );
+
+    // 76.4, 88.1 and 99.9 kg on a 20 kg bar with plates down to 1.25 kg.
+    let bench = &workouts[0].exercises[0];
+    assert_eq!(weights(bench), vec![77.5, 87.5, 100.0]);
+    assert!(bench.sets.iter().all(|set| set.reps == 5
+        && set.bar_weight == Some(20.0)
+        && set.load_type == LoadType::External
+        && !set.is_completed));
+
+    // The squat's training max comes from the definition: 91, 105 and 119 kg without a bar.
+    let squat = &workouts[1].exercises[0];
+    assert_eq!(squat.measurement_id, SQUAT_ID);
+    assert_eq!(weights(squat), vec![90.0, 105.0, 120.0]);
+    assert_eq!(squat.sets[2].rpe, Some(8.0));
+}
+
+#[test]
+fn test_exercises_with_fewer_weeks_start_over() {
+    let generator = ProgramGenerator::new(TrainingMaxes::new([(BENCH_ID, 100.0)]))
+        .with_user(&user_from_fixture());
+    let workouts = generator.generate(&five_three_one()).unwrap();
+
+    for bench_day in workouts.iter().step_by(2) {
+        let accessory = &bench_day.exercises[1];
+        assert_eq!(weights(accessory), vec![50.0; 5]);
+        assert!(accessory.sets.iter().all(|set| set.reps == 10));
+    }
+}
+
+#[test]
+fn test_exercises_are_named_and_loaded_like_their_measurement() {
+    let mut bench_name = Name::from("Bench Press (Barbell)".to_string());
+    bench_name
+        .locales
+        .insert("de".to_string(), "Bankdrücken (Langhantel)".to_string());
+    let mut measurements = MeasurementsResponse::default();
+    measurements.embedded.measurements = vec![
+        measurement(BENCH_ID, bench_name, &["BARBELL_WEIGHT", "REPS"]),
+        measurement(
+            SQUAT_ID,
+            Name::from("Squat (Bodyweight)".to_string()),
+            &["WEIGHTED_BODYWEIGHT", "REPS"],
+        ),
+    ];
+    let workouts = ProgramGenerator::new(TrainingMaxes::new([(BENCH_ID, 117.5)]))
+        .with_user(&user_from_fixture())
+        .with_measurements_response(measurements)
+        .with_exercise_aliases(ExerciseAliases::new([(BENCH_ID, "Bench Press")]))
+        .with_locales(["de"])
+        .generate(&five_three_one())
+        .unwrap();
+
+    let bench = &workouts[0].exercises[0];
+    assert_eq!(bench.name, "Bankdrücken (Langhantel)");
+    assert_eq!(bench.name_locale, "de");
+    assert_eq!(bench.canonical_name, "Bench Press");
+    assert!(
+        bench
+            .sets
+            .iter()
+            .all(|set| set.load_type == LoadType::External && set.bar_weight == Some(20.0))
+    );
+
+    // Weighted bodyweight exercises have no bar.
+    let squat = &workouts[1].exercises[0];
+    assert_eq!(squat.name, "Squat (Bodyweight)");
+    assert_eq!(squat.canonical_name, "Squat (Bodyweight)");
+    assert!(
+        squat
+            .sets
+            .iter()
+            .all(|set| set.load_type == LoadType::WeightedBodyweight && set.bar_weight.is_none())
+    );
+}
+
+#[test]
+fn test_missing_training_max_is_an_error() {
+    let result = ProgramGenerator::new(TrainingMaxes::default()).generate(&five_three_one());
+
+    let error = MissingTrainingMax {
+        day: "Bench".to_string(),
+        measurement_id: BENCH_ID.to_string(),
+    };
+    assert_eq!(result, Err(error.clone()));
+    assert_eq!(
+        error.to_string(),
+        format!("no training max for measurement {BENCH_ID} on day 'Bench'")
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Training maxes
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_training_maxes_from_history() {
+    let workouts = vec![
+        workout(
+            "w1",
+            "2024-05-01T10:00:00Z",
+            vec![exercise(
+                BENCH_ID,
+                "Bench",
+                vec![set(90.0, 5), set(100.0, 5)],
+            )],
+        ),
+        workout(
+            "w2",
+            "2024-05-08T10:00:00Z",
+            vec![exercise(BENCH_ID, "Bench", vec![set(95.0, 3)])],
+        ),
+    ];
+
+    // The best Epley estimate is 100 kg for 5, 116.7 kg.
+    let training_maxes = TrainingMaxes::from_history(&workouts, OneRepMaxFormula::Epley, 0.9)
+        .with_training_max(SQUAT_ID, 140.0);
+    assert!((training_maxes.get(BENCH_ID).unwrap() - 105.0).abs() < 0.01);
+    assert_eq!(training_maxes.get(SQUAT_ID), Some(140.0));
+    assert_eq!(training_maxes.get("deadlift"), None);
+}
+
+// ---------------------------------------------------------------------------
+// Templates
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_mesocycle_exports_as_template_logs() {
+    let user = user_from_fixture();
+    let workouts = ProgramGenerator::new(TrainingMaxes::new([(BENCH_ID, 117.5)]))
+        .with_user(&user)
+        .generate(&five_three_one())
+        .unwrap();
+    let transformer = DataTransformer::new().with_preferences(&user.id, user.preferences.clone());
+
+    let templates = transformer.workouts_to_templates(&workouts).unwrap();
+    let json = serde_json::to_string_pretty(&templates).unwrap();
+    let exported: Vec<Log> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(exported.len(), 8);
+    assert!(exported.iter().all(|log| log.log_type == "TEMPLATE"));
+    let week_one = &exported[0];
+    assert_eq!(
+        week_one.name.as_ref().unwrap().to_string(),
+        "5/3/1 - Week 1 - Bench"
+    );
+    let first_set: Vec<Option<&str>> = week_one.embedded.cell_set_group[0].cell_sets[0]
+        .cells
+        .iter()
+        .map(|cell| cell.value.as_deref())
+        .collect();
+    assert_eq!(first_set, vec![Some("77.5"), Some("5")]);
+}
@@ -0,0 +1,181 @@
+use strong_api_lib::analytics::progression::{
+    ProgressionMetric, ProgressionOptions, Trend, detect_progression,
+};
+use strong_api_lib::data_transformer::Workout;
+use time::{Date, Duration, macros::date};
+
+mod common;
+use common::{exercise, set, workout};
+
+const FIRST_DAY: Date = date!(2024 - 01 - 01);
+
+/// 10:00 UTC on the first day of `week`.
+fn start_of_week(week: i64) -> String {
+    format!("{}T10:00:00Z", FIRST_DAY + Duration::weeks(week))
+}
+
+/// Weekly singles of squat, bench and deadlift with the given loads.
+fn history(squat: &[f32], bench: &[f32], deadlift: &[f32]) -> Vec<Workout> {
+    (0..squat.len())
+        .map(|week| {
+            workout(
+                &format!("w{week}"),
+                &start_of_week(week as i64),
+                vec![
+                    exercise("", "Squat", vec![set(squat[week], 1)]),
+                    exercise("", "Bench Press", vec![set(bench[week], 1)]),
+                    exercise("", "Deadlift", vec![set(deadlift[week], 1)]),
+                ],
+            )
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Classification
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_trends_are_classified() {
+    let workouts = history(
+        &[100.0, 102.5, 105.0, 107.5],
+        &[80.0, 80.5, 80.0, 80.0],
+        &[150.0, 147.5, 145.0, 140.0],
+    );
+    let report = detect_progression(&workouts, &ProgressionOptions::new());
+
+    let trends: Vec<_> = report
+        .exercises
+        .iter()
+        .map(|exercise| (exercise.exercise.as_str(), exercise.trend))
+        .collect();
+    assert_eq!(
+        trends,
+        vec![
+            ("Bench Press", Trend::Plateaued),
+            ("Deadlift", Trend::Regressing),
+            ("Squat", Trend::Progressing),
+        ]
+    );
+    assert_eq!(report.until, Some(date!(2024 - 01 - 22)));
+}
+
+#[test]
+fn test_evidence() {
+    let workouts = history(
+        &[100.0, 102.5, 105.0, 107.5],
+        &[80.0, 80.0, 80.0, 80.0],
+        &[150.0, 150.0, 150.0, 150.0],
+    );
+    let report = detect_progression(&workouts, &ProgressionOptions::new());
+
+    let squat = &report.exercises[2];
+    assert_eq!(squat.sessions, 4);
+    assert_eq!(squat.first_session, FIRST_DAY);
+    assert_eq!(squat.last_session, date!(2024 - 01 - 22));
+    assert_eq!(squat.first_value, 100.0);
+    assert_eq!(squat.last_value, 107.5);
+    assert_eq!(squat.best_value, 107.5);
+    assert!((squat.slope_per_week - 2.5).abs() < 0.001);
+    assert!((squat.change_percent_per_week - 2.41).abs() < 0.01);
+    assert_eq!(report.exercises[0].slope_per_week, 0.0);
+}
+
+#[test]
+fn test_best_set_of_the_session_counts() {
+    let workouts: Vec<_> = (0..3)
+        .map(|week| {
+            workout(
+                &format!("w{week}"),
+                &start_of_week(week),
+                vec![exercise(
+                    "",
+                    "Squat",
+                    vec![set(60.0, 10), set(100.0 + week as f32 * 5.0, 1)],
+                )],
+            )
+        })
+        .collect();
+
+    // 60 kg for 10 reps is an e1RM of 80 kg, so the single is the best set every week.
+    let report = detect_progression(&workouts, &ProgressionOptions::new());
+    assert_eq!(report.exercises[0].first_value, 100.0);
+    assert_eq!(report.exercises[0].trend, Trend::Progressing);
+
+    let options = ProgressionOptions::new().with_metric(ProgressionMetric::TopSetWeight);
+    let report = detect_progression(&workouts, &options);
+    assert_eq!(report.metric, ProgressionMetric::TopSetWeight);
+    assert_eq!(report.exercises[0].last_value, 110.0);
+}
+
+// ---------------------------------------------------------------------------
+// Options
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_window_ends_with_the_latest_workout() {
+    let squat: Vec<f32> = (0..12).map(|week| 100.0 + week as f32 * 2.5).collect();
+    let flat = vec![80.0; 12];
+    let workouts = history(&squat, &flat, &flat);
+
+    let report = detect_progression(&workouts, &ProgressionOptions::new().with_window_weeks(4));
+    assert_eq!(report.window_weeks, 4);
+    assert_eq!(report.exercises[2].sessions, 4);
+    assert_eq!(report.exercises[2].first_value, 120.0);
+}
+
+#[test]
+fn test_exercises_need_enough_sessions() {
+    let workouts = history(&[100.0, 105.0], &[80.0, 80.0], &[150.0, 150.0]);
+    assert!(
+        detect_progression(&workouts, &ProgressionOptions::new())
+            .exercises
+            .is_empty()
+    );
+    let options = ProgressionOptions::new().with_min_sessions(2);
+    assert_eq!(detect_progression(&workouts, &options).exercises.len(), 3);
+}
+
+#[test]
+fn test_threshold() {
+    let workouts = history(
+        &[100.0, 102.5, 105.0, 107.5],
+        &[80.0, 80.0, 80.0, 80.0],
+        &[150.0, 150.0, 150.0, 150.0],
+    );
+    let options = ProgressionOptions::new().with_threshold_percent(5.0);
+    let report = detect_progression(&workouts, &options);
+    assert_eq!(report.exercises[2].trend, Trend::Plateaued);
+}
+
+// ---------------------------------------------------------------------------
+// Output
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_report_display_and_serialization() {
+    let workouts = history(
+        &[100.0, 102.5, 105.0, 107.5],
+        &[80.0, 80.0, 80.0, 80.0],
+        &[150.0, 150.0, 150.0, 150.0],
+    );
+    let report = detect_progression(&workouts, &ProgressionOptions::new());
+
+    assert_eq!(
+        report.to_string(),
+        "Progression of one_rep_max over 8 week(s): 3 exercise(s)\
+         \n  Bench Press: plateaued, 80.0 -> 80.0 kg in 4 sessions (+0.00 kg, +0.00% per week)\
+         \n  Deadlift: plateaued, 150.0 -> 150.0 kg in 4 sessions (+0.00 kg, +0.00% per week)\
+         \n  Squat: progressing, 100.0 -> 107.5 kg in 4 sessions (+2.50 kg, +2.41% per week)"
+    );
+    let value = serde_json::to_value(&report).unwrap();
+    assert_eq!(value["exercises"][2]["trend"], "progressing");
+    assert_eq!(value["until"], "2024-01-22");
+}
+
+#[test]
+fn test_no_workouts() {
+    let report = detect_progression(&[], &ProgressionOptions::new());
+    assert_eq!(report.until, None);
+    assert!(report.exercises.is_empty());
+}
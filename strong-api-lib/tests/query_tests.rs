@@ -0,0 +1,308 @@
+use strong_api_lib::analytics::comparison::Period;
+use strong_api_lib::data_transformer::Workout;
+use strong_api_lib::query::WorkoutQuery;
+use strong_api_lib::query::group::{
+    group_by_exercise, group_by_month, group_by_tag, group_by_week, group_by_workout_name,
+};
+use time::Weekday;
+use time::macros::date;
+
+mod common;
+use common::{
+    SetExt, WorkoutExt, exercise, measurements_from_fixture, set, set_at_rpe, workout,
+    workouts_from_fixture,
+};
+
+const LEGS_ID: &str = "57f573f8-f797-4483-bc1f-5911a70463a6";
+const ARMS_ID: &str = "0973c5dd-5b62-4e9e-a15e-114cb94f440d";
+
+fn history() -> Vec<Workout> {
+    vec![
+        workout(
+            "w1",
+            "2024-05-06T17:00:00Z",
+            vec![
+                exercise(
+                    LEGS_ID,
+                    "Squat",
+                    vec![
+                        set_at_rpe(100.0, 5, 7.0).with_id("s1"),
+                        set_at_rpe(120.0, 5, 9.0).with_id("s2"),
+                        set(140.0, 5).with_id("s3").incomplete(),
+                    ],
+                ),
+                exercise(
+                    ARMS_ID,
+                    "Curl",
+                    vec![set_at_rpe(20.0, 5, 8.0).with_id("c1")],
+                ),
+            ],
+        )
+        .with_name("Leg Day"),
+        workout(
+            "w2",
+            "2024-05-15T17:00:00Z",
+            vec![exercise(
+                "bench-id",
+                "Bench Press",
+                vec![set_at_rpe(80.0, 5, 8.0).with_id("b1")],
+            )],
+        )
+        .with_name("Push Day"),
+        // Starts on June 1st in Berlin.
+        workout(
+            "w3",
+            "2024-05-31T22:30:00Z",
+            vec![exercise(
+                LEGS_ID,
+                "Squat",
+                vec![set_at_rpe(125.0, 5, 8.5).with_id("s4")],
+            )],
+        )
+        .with_name("Leg Day 2"),
+    ]
+}
+
+fn ids(workouts: &[Workout]) -> Vec<&str> {
+    workouts.iter().map(|workout| workout.id.as_str()).collect()
+}
+
+fn set_ids(workouts: &[Workout]) -> Vec<&str> {
+    workouts
+        .iter()
+        .flat_map(|workout| &workout.exercises)
+        .flat_map(|exercise| &exercise.sets)
+        .map(|set| set.id.as_str())
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Predicates
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_empty_query_matches_everything() {
+    assert_eq!(WorkoutQuery::new().apply(&history()), history());
+}
+
+#[test]
+fn test_date_range_uses_local_start_dates() {
+    let may = WorkoutQuery::new().with_period(Period::month_of(date!(2024 - 05 - 01)));
+    assert_eq!(ids(&may.apply(&history())), vec!["w1", "w2"]);
+
+    let since = WorkoutQuery::new().with_since(date!(2024 - 06 - 01));
+    assert_eq!(ids(&since.apply(&history())), vec!["w3"]);
+
+    let mut without_date = history();
+    without_date[0].start_date = None;
+    let until = WorkoutQuery::new().with_until(date!(2024 - 12 - 31));
+    assert_eq!(ids(&until.apply(&without_date)), vec!["w2", "w3"]);
+}
+
+#[test]
+fn test_name_pattern() {
+    let legs = WorkoutQuery::new().with_name_pattern("leg*");
+    assert_eq!(ids(&legs.apply(&history())), vec!["w1", "w3"]);
+
+    let exact = WorkoutQuery::new().with_name_pattern("Leg Day ?");
+    assert_eq!(ids(&exact.apply(&history())), vec!["w3"]);
+
+    let inner = WorkoutQuery::new().with_name_pattern("*sh*");
+    assert_eq!(ids(&inner.apply(&history())), vec!["w2"]);
+}
+
+#[test]
+fn test_exercises_by_name_id_and_tag() {
+    let squats = WorkoutQuery::new().with_exercise("squat");
+    let result = squats.apply(&history());
+    assert_eq!(ids(&result), vec!["w1", "w3"]);
+    assert_eq!(result[0].exercises.len(), 1);
+
+    let either = WorkoutQuery::new()
+        .with_exercise("Curl")
+        .with_measurement_id("bench-id");
+    // Different predicates must all hold: no exercise is a curl and a bench press.
+    assert!(either.apply(&history()).is_empty());
+
+    let bench_or_curl = WorkoutQuery::new()
+        .with_exercise("Curl")
+        .with_exercise("Bench Press");
+    assert_eq!(ids(&bench_or_curl.apply(&history())), vec!["w1", "w2"]);
+
+    let legs = WorkoutQuery::new()
+        .with_tag("legs")
+        .with_measurements(&measurements_from_fixture());
+    assert_eq!(
+        set_ids(&legs.apply(&history())),
+        vec!["s1", "s2", "s3", "s4"]
+    );
+
+    // Tags ignore case.
+    let legs = WorkoutQuery::parse("tag:Legs")
+        .unwrap()
+        .with_measurements(&measurements_from_fixture());
+    assert_eq!(
+        set_ids(&legs.apply(&history())),
+        vec!["s1", "s2", "s3", "s4"]
+    );
+
+    // Without measurements nothing has a tag.
+    assert!(
+        WorkoutQuery::new()
+            .with_tag("legs")
+            .apply(&history())
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_set_predicates() {
+    let heavy = WorkoutQuery::new().with_load_range(Some(120.0), None);
+    assert_eq!(set_ids(&heavy.apply(&history())), vec!["s2", "s3", "s4"]);
+
+    let completed = heavy.clone().with_completed_only(true);
+    assert_eq!(set_ids(&completed.apply(&history())), vec!["s2", "s4"]);
+
+    // Sets without an RPE don't match a range.
+    let hard = WorkoutQuery::new().with_rpe_range(Some(8.0), Some(9.0));
+    assert_eq!(
+        set_ids(&hard.apply(&history())),
+        vec!["s2", "c1", "b1", "s4"]
+    );
+
+    // Exercises and workouts left without sets are dropped.
+    let light = WorkoutQuery::new().with_load_range(None, Some(50.0));
+    let result = light.apply(&history());
+    assert_eq!(ids(&result), vec!["w1"]);
+    assert_eq!(result[0].exercises[0].name, "Curl");
+}
+
+// ---------------------------------------------------------------------------
+// Parsing
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_parse_query_string() {
+    let parsed: WorkoutQuery =
+        r#"exercise:"Bench Press" since:2024-05-01 until:2024-05-31 load:70..90 rpe:8 completed"#
+            .parse()
+            .unwrap();
+    assert_eq!(
+        parsed,
+        WorkoutQuery::new()
+            .with_exercise("Bench Press")
+            .with_period(Period::month_of(date!(2024 - 05 - 01)))
+            .with_load_range(Some(70.0), Some(90.0))
+            .with_rpe_range(Some(8.0), Some(8.0))
+            .with_completed_only(true)
+    );
+    assert_eq!(set_ids(&parsed.apply(&history())), vec!["b1"]);
+
+    let legs = WorkoutQuery::parse(r#"name:"Leg*" tag:legs load:..110"#)
+        .unwrap()
+        .with_measurements(&measurements_from_fixture());
+    assert_eq!(set_ids(&legs.apply(&history())), vec!["s1"]);
+}
+
+#[test]
+fn test_invalid_query_terms() {
+    let error = WorkoutQuery::parse("since:yesterday").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid query term 'since:yesterday': not a date"
+    );
+    assert_eq!(
+        WorkoutQuery::parse("heavy").unwrap_err().reason,
+        "expected key:value"
+    );
+    assert_eq!(
+        WorkoutQuery::parse("reps:5").unwrap_err().reason,
+        "unknown key"
+    );
+    assert_eq!(
+        WorkoutQuery::parse("load:..").unwrap_err().reason,
+        "not a range"
+    );
+    assert_eq!(
+        WorkoutQuery::parse(r#"exercise:"Bench"#)
+            .unwrap_err()
+            .reason,
+        "unclosed quote"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Grouping
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_group_by_period_and_name() {
+    let weeks = group_by_week(&history(), Weekday::Monday);
+    let weeks: Vec<_> = weeks
+        .iter()
+        .map(|(start, workouts)| (*start, ids(workouts)))
+        .collect();
+    assert_eq!(
+        weeks,
+        vec![
+            (date!(2024 - 05 - 06), vec!["w1"]),
+            (date!(2024 - 05 - 13), vec!["w2"]),
+            (date!(2024 - 05 - 27), vec!["w3"]),
+        ]
+    );
+
+    let months = group_by_month(&history());
+    assert_eq!(ids(&months[&date!(2024 - 05 - 01)]), vec!["w1", "w2"]);
+    assert_eq!(ids(&months[&date!(2024 - 06 - 01)]), vec!["w3"]);
+
+    let names = group_by_workout_name(&history());
+    assert_eq!(
+        names.keys().collect::<Vec<_>>(),
+        vec!["Leg Day", "Leg Day 2", "Push Day"]
+    );
+}
+
+#[test]
+fn test_group_by_exercise_and_tag_narrow_workouts() {
+    let exercises = group_by_exercise(&history());
+    assert_eq!(
+        exercises.keys().collect::<Vec<_>>(),
+        vec!["Bench Press", "Curl", "Squat"]
+    );
+    assert_eq!(ids(&exercises["Squat"]), vec!["w1", "w3"]);
+    assert_eq!(set_ids(&exercises["Curl"]), vec!["c1"]);
+
+    let tags = group_by_tag(&history(), &measurements_from_fixture());
+    assert_eq!(tags.keys().collect::<Vec<_>>(), vec!["arms", "legs"]);
+    assert_eq!(set_ids(&tags["legs"]), vec!["s1", "s2", "s3", "s4"]);
+
+    // A query result groups like any other workouts.
+    let heavy = WorkoutQuery::new().with_load_range(Some(100.0), None);
+    let heavy_by_exercise = group_by_exercise(&heavy.apply(&history()));
+    assert_eq!(heavy_by_exercise.keys().collect::<Vec<_>>(), vec!["Squat"]);
+}
+
+// ---------------------------------------------------------------------------
+// Fixtures
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_query_fixture_workouts() {
+    let workouts = workouts_from_fixture();
+
+    // The fixture's five workouts run from November 20th to December 11th 2024.
+    let december = WorkoutQuery::parse("since:2024-12-01 completed")
+        .unwrap()
+        .apply(&workouts);
+    assert!(!december.is_empty() && december.len() < workouts.len());
+    assert!(
+        december
+            .iter()
+            .flat_map(|workout| &workout.exercises)
+            .flat_map(|exercise| &exercise.sets)
+            .all(|set| set.is_completed)
+    );
+
+    let by_month = group_by_month(&workouts);
+    assert_eq!(by_month.values().map(Vec::len).sum::<usize>(), 5);
+}
@@ -0,0 +1,245 @@
+use strong_api_lib::data_transformer::reverse::UnknownExercise;
+use strong_api_lib::data_transformer::{DataTransformer, Exercise, LoadType, Set};
+use strong_api_lib::models::preferences::{DistanceUnit, WeightUnit};
+use strong_api_lib::models::workout::CellSet;
+
+mod common;
+use common::{exercise, logs_from_fixture, measurements_from_fixture, workout};
+
+const USER_ID: &str = "0f518518-5180-4518-b518-51b51851b518";
+const RUNNING_ID: &str = "a35d3d7c-6093-47b9-b4dc-939c4d799169";
+const PULL_UP_ID: &str = "adb75e5a-c873-4f57-bdf0-0043710ba90d";
+const EXERCISE_ID: &str = "8d3f6a52-4a8e-4c1e-9d59-5b8b1f6f0c11";
+
+fn transformer() -> DataTransformer {
+    DataTransformer::new()
+        .with_measurements_response(measurements_from_fixture())
+        .with_preferences(USER_ID, Default::default())
+}
+
+fn cells_of(cell_set: &CellSet) -> Vec<(String, Option<String>)> {
+    let mut cells: Vec<_> = cell_set
+        .cells
+        .iter()
+        .map(|cell| (cell.cell_type.clone(), cell.value.clone()))
+        .collect();
+    cells.sort();
+    cells
+}
+
+// ---------------------------------------------------------------------------
+// Round trip: Log -> Workout -> Log
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_round_trip_preserves_workouts() {
+    let transformer = transformer();
+    let workouts = transformer
+        .get_measurements_from_logs(&Some(logs_from_fixture()))
+        .unwrap();
+
+    let logs = transformer.workouts_to_logs(&workouts).unwrap();
+    let again = transformer.get_measurements_from_logs(&Some(logs)).unwrap();
+
+    assert_eq!(again, workouts);
+}
+
+#[test]
+fn test_round_trip_preserves_all_sets_and_cells() {
+    let transformer = transformer();
+    let original = logs_from_fixture();
+    let workouts = transformer
+        .get_measurements_from_logs(&Some(original.clone()))
+        .unwrap();
+    let logs = transformer.workouts_to_logs(&workouts).unwrap();
+
+    // Notes are kept on the exercise and get new cell sets, see the round trip of workouts.
+    let rebuilt: Vec<&CellSet> = logs
+        .iter()
+        .flat_map(|log| &log.embedded.cell_set_group)
+        .flat_map(|group| &group.cell_sets)
+        .filter(|cell_set| !cell_set.cells.iter().any(|cell| cell.cell_type == "NOTE"))
+        .collect();
+    let original_sets: Vec<&CellSet> = original
+        .iter()
+        .flat_map(|log| &log.embedded.cell_set_group)
+        .flat_map(|group| &group.cell_sets)
+        .filter(|cell_set| !cell_set.cells.iter().any(|cell| cell.cell_type == "NOTE"))
+        .collect();
+
+    assert_eq!(rebuilt.len(), original_sets.len());
+    for original_set in original_sets {
+        let rebuilt_set = rebuilt
+            .iter()
+            .find(|cell_set| cell_set.id == original_set.id)
+            .unwrap_or_else(|| panic!("set {} is missing", original_set.id));
+        assert_eq!(cells_of(rebuilt_set), cells_of(original_set));
+        assert_eq!(rebuilt_set.is_completed, original_set.is_completed);
+    }
+}
+
+#[test]
+fn test_round_trip_preserves_log_metadata() {
+    let transformer = transformer();
+    let original = logs_from_fixture();
+    let workouts = transformer
+        .get_measurements_from_logs(&Some(original.clone()))
+        .unwrap();
+    let logs = transformer.workouts_to_logs(&workouts).unwrap();
+
+    for (log, original) in logs.iter().zip(&original) {
+        assert_eq!(log.id, original.id);
+        assert_eq!(log.name, original.name);
+        assert_eq!(log.timezone_id, original.timezone_id);
+        assert_eq!(log.start_date, original.start_date);
+        assert_eq!(log.end_date, original.end_date);
+        assert_eq!(log.log_type, "WORKOUT");
+        assert_eq!(
+            log.links["self"]["href"],
+            format!("/api/users/{USER_ID}/logs/{}", original.id)
+        );
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Cells, units and ids
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_cells_follow_the_measurement_config() {
+    let set = Set {
+        id: "s1".to_string(),
+        load_type: LoadType::WeightedBodyweight,
+        ..Default::default()
+    };
+    let log = transformer()
+        .workout_to_log(&workout(
+            "imported-1",
+            "2024-05-01T10:00:00Z",
+            vec![exercise(PULL_UP_ID, "Pull Up", vec![set])],
+        ))
+        .unwrap();
+
+    // REPS is mandatory and written without a value, the optional RPE is left out.
+    let cell_set = &log.embedded.cell_set_group[0].cell_sets[0];
+    assert_eq!(cells_of(cell_set), vec![("REPS".to_string(), None)]);
+}
+
+#[test]
+fn test_values_are_written_in_the_logged_unit() {
+    let set = Set {
+        id: "s1".to_string(),
+        duration: Some(1500),
+        distance: Some(DistanceUnit::Miles.to_kilometers(3.1)),
+        distance_unit: DistanceUnit::Miles,
+        ..Default::default()
+    };
+    let lifted = Set {
+        id: "s2".to_string(),
+        weight: Some(WeightUnit::Pounds.to_kilograms(225.0)),
+        weight_unit: WeightUnit::Pounds,
+        load_type: LoadType::External,
+        weight_cell_type: Some("BARBELL_WEIGHT".to_string()),
+        reps: 5,
+        rpe: Some(8.5),
+        ..Default::default()
+    };
+    let log = transformer()
+        .workout_to_log(&workout(
+            "imported-1",
+            "2024-05-01T10:00:00Z",
+            vec![
+                exercise(RUNNING_ID, "Running", vec![set]),
+                exercise("not-in-catalogue", "Bench", vec![lifted]),
+            ],
+        ))
+        .unwrap();
+
+    let groups = &log.embedded.cell_set_group;
+    assert_eq!(
+        cells_of(&groups[0].cell_sets[0]),
+        vec![
+            ("DISTANCE".to_string(), Some("3.1".to_string())),
+            ("DURATION".to_string(), Some("1500".to_string())),
+        ]
+    );
+    assert_eq!(
+        cells_of(&groups[1].cell_sets[0]),
+        vec![
+            ("BARBELL_WEIGHT".to_string(), Some("225".to_string())),
+            ("REPS".to_string(), Some("5".to_string())),
+            ("RPE".to_string(), Some("8.5".to_string())),
+        ]
+    );
+}
+
+#[test]
+fn test_ids_that_are_no_uuids_are_replaced() {
+    let log = transformer()
+        .workout_to_log(&workout(
+            "imported-1",
+            "2024-05-01T10:00:00Z",
+            vec![Exercise {
+                id: EXERCISE_ID.to_string(),
+                ..exercise(RUNNING_ID, "Running", vec![Set::default()])
+            }],
+        ))
+        .unwrap();
+
+    assert!(uuid::Uuid::parse_str(&log.id).is_ok());
+    assert_ne!(log.id, "imported-1");
+    let cell_set = &log.embedded.cell_set_group[0].cell_sets[0];
+    assert!(uuid::Uuid::parse_str(&cell_set.id).is_ok());
+    // The exercise id already was a UUID and is kept.
+    assert_eq!(log.embedded.cell_set_group[0].id, EXERCISE_ID);
+}
+
+// ---------------------------------------------------------------------------
+// Measurement resolution
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_exercise_without_measurement_id_is_resolved_by_name() {
+    let log = transformer()
+        .workout_to_log(&workout(
+            "imported-1",
+            "2024-05-01T10:00:00Z",
+            vec![exercise("", "running", vec![Set::default()])],
+        ))
+        .unwrap();
+
+    let href = &log.embedded.cell_set_group[0]
+        .links
+        .measurement
+        .as_ref()
+        .unwrap()
+        .href;
+    assert_eq!(
+        href,
+        &format!("/api/users/{USER_ID}/measurements/{RUNNING_ID}")
+    );
+}
+
+#[test]
+fn test_unknown_exercise_is_an_error() {
+    let result = transformer().workout_to_log(&workout(
+        "imported-1",
+        "2024-05-01T10:00:00Z",
+        vec![Exercise {
+            id: EXERCISE_ID.to_string(),
+            ..exercise("", "Underwater Basket Weaving", vec![Set::default()])
+        }],
+    ));
+
+    assert_eq!(
+        result,
+        Err(UnknownExercise {
+            exercise_id: EXERCISE_ID.to_string(),
+            name: "Underwater Basket Weaving".to_string(),
+        })
+    );
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "exercise 'Underwater Basket Weaving' (8d3f6a52-4a8e-4c1e-9d59-5b8b1f6f0c11) matches no measurement"
+    );
+}
@@ -0,0 +1,282 @@
+use serde_json::json;
+use strong_api_lib::analytics::one_rep_max::OneRepMaxFormula;
+use strong_api_lib::analytics::scores::{
+    BigThree, Lift, ScoreFormula, ScoreOptions, Sex, leaderboard, strength_scores,
+};
+use strong_api_lib::data_transformer::body_weight::BodyWeight;
+use strong_api_lib::data_transformer::{DataTransformer, Workout};
+use strong_api_lib::models::measured_value::MeasuredValue;
+use strong_api_lib::models::preferences::WeightUnit;
+
+mod common;
+use common::{SetExt, WorkoutExt, exercise, set, user_from_fixture, workout};
+
+const SQUAT: &str = "squat-id";
+const BENCH: &str = "bench-id";
+const DEADLIFT: &str = "deadlift-id";
+
+fn big_three() -> BigThree {
+    BigThree::new([
+        (SQUAT, Lift::Squat),
+        (BENCH, Lift::Bench),
+        (DEADLIFT, Lift::Deadlift),
+    ])
+}
+
+/// A 700 kg total, the deadlift set last at 100 kg body weight.
+fn history() -> Vec<Workout> {
+    vec![
+        workout(
+            "w1",
+            "2024-05-01T10:00:00Z",
+            vec![
+                exercise(SQUAT, "squat", vec![set(240.0, 1), set(200.0, 5)]),
+                exercise(BENCH, "bench", vec![set(160.0, 1)]),
+            ],
+        )
+        .with_body_weight(Some(98.0)),
+        workout(
+            "w2",
+            "2024-05-08T10:00:00Z",
+            vec![
+                exercise(DEADLIFT, "deadlift", vec![set(300.0, 1)]),
+                exercise("curl-id", "curl", vec![set(400.0, 1)]),
+            ],
+        )
+        .with_body_weight(Some(100.0)),
+    ]
+}
+
+// ---------------------------------------------------------------------------
+// Formulas
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_points_by_formula_and_sex() {
+    let close = |points: f32, expected: f32| (points - expected).abs() < 0.01;
+
+    assert!(close(
+        ScoreFormula::Dots.points(Sex::Male, 100.0, 700.0),
+        430.86
+    ));
+    assert!(close(
+        ScoreFormula::Wilks.points(Sex::Male, 100.0, 700.0),
+        426.01
+    ));
+    assert!(close(
+        ScoreFormula::IpfGl.points(Sex::Male, 100.0, 700.0),
+        88.43
+    ));
+    assert!(close(
+        ScoreFormula::Dots.points(Sex::Female, 60.0, 400.0),
+        443.42
+    ));
+    assert!(close(
+        ScoreFormula::Wilks.points(Sex::Female, 60.0, 400.0),
+        445.95
+    ));
+    assert!(close(
+        ScoreFormula::IpfGl.points(Sex::Female, 60.0, 400.0),
+        90.42
+    ));
+}
+
+#[test]
+fn test_body_weight_is_clamped_to_the_fitted_range() {
+    assert_eq!(
+        ScoreFormula::Dots.points(Sex::Male, 250.0, 800.0),
+        ScoreFormula::Dots.points(Sex::Male, 210.0, 800.0)
+    );
+    assert_eq!(
+        ScoreFormula::Wilks.points(Sex::Male, 250.0, 800.0),
+        ScoreFormula::Wilks.points(Sex::Male, 201.9, 800.0)
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Mapping
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_big_three_from_toml_and_json() {
+    let from_toml = BigThree::from_toml(
+        r#"
+        "squat-id" = "squat"
+        "bench-id" = "bench"
+        "deadlift-id" = "deadlift"
+        "#,
+    )
+    .unwrap();
+    let from_json = BigThree::from_json(
+        r#"{"squat-id": "squat", "bench-id": "bench", "deadlift-id": "deadlift"}"#,
+    )
+    .unwrap();
+
+    assert_eq!(from_toml, big_three());
+    assert_eq!(from_json, big_three());
+    assert_eq!(
+        big_three()
+            .with_measurement("ssb-id", Lift::Squat)
+            .lift("ssb-id"),
+        Some(Lift::Squat)
+    );
+    assert_eq!(big_three().lift("curl-id"), None);
+    assert!(BigThree::from_toml(r#""squat-id" = "snatch""#).is_err());
+}
+
+// ---------------------------------------------------------------------------
+// Scores
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_scores_of_the_big_three_bests() {
+    let options = ScoreOptions::new().with_big_three(big_three());
+    let scores = strength_scores("Alex", &history(), &options);
+
+    assert_eq!(scores.squat.as_ref().unwrap().set_id, "set-240-1");
+    assert_eq!(scores.deadlift.as_ref().unwrap().workout_id, "w2");
+    assert_eq!(scores.total, Some(700.0));
+    assert_eq!(scores.body_weight, Some(100.0));
+    assert!((scores.dots.unwrap() - 430.86).abs() < 0.01);
+    assert_eq!(
+        scores.to_string(),
+        "Alex (male, 100.0 kg): 700.0 kg total, DOTS 430.86, Wilks 426.01, IPF GL 88.43\
+         \n  squat: 240.0 kg (squat)\
+         \n  bench: 160.0 kg (bench)\
+         \n  deadlift: 300.0 kg (deadlift)"
+    );
+}
+
+#[test]
+fn test_estimated_bests_and_incomplete_sets() {
+    let mut workouts = history();
+    // A heavier squat that wasn't completed doesn't count.
+    workouts[0].exercises[0]
+        .sets
+        .push(set(260.0, 1).incomplete());
+
+    let options = ScoreOptions::new()
+        .with_big_three(big_three())
+        .with_sex(Sex::Female)
+        .with_estimate(OneRepMaxFormula::Epley);
+    let scores = strength_scores("Alex", &workouts, &options);
+
+    // 200 kg for 5 estimates 233.3 kg, less than the 240 kg single.
+    assert_eq!(scores.squat.as_ref().unwrap().weight, 240.0);
+    assert_eq!(scores.sex, Sex::Female);
+    assert!(
+        (scores.wilks.unwrap() - ScoreFormula::Wilks.points(Sex::Female, 100.0, 700.0)).abs()
+            < 0.01
+    );
+}
+
+#[test]
+fn test_missing_lift_or_body_weight_has_no_scores() {
+    let options = ScoreOptions::new().with_big_three(big_three());
+
+    let without_deadlift = strength_scores("A", &history()[..1], &options);
+    assert_eq!(without_deadlift.total, None);
+    assert_eq!(without_deadlift.dots, None);
+    assert_eq!(
+        without_deadlift.to_string().lines().next().unwrap(),
+        "A (male, 98.0 kg): incomplete total"
+    );
+
+    let mut workouts = history();
+    workouts[1].body_weight = None;
+    let without_body_weight = strength_scores("B", &workouts, &options);
+    assert_eq!(without_body_weight.total, Some(700.0));
+    assert_eq!(without_body_weight.ipf_gl, None);
+}
+
+// ---------------------------------------------------------------------------
+// Leaderboard
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_leaderboard_ranks_across_weight_classes() {
+    let heavy = ScoreOptions::new().with_big_three(big_three());
+    let light = ScoreOptions::new()
+        .with_big_three(big_three())
+        .with_sex(Sex::Female);
+    let mut light_history = history();
+    for workout in &mut light_history {
+        workout.body_weight = Some(60.0);
+        for set in workout.exercises.iter_mut().flat_map(|e| &mut e.sets) {
+            set.effective_load = set.effective_load.map(|load| load * 4.0 / 7.0);
+        }
+    }
+
+    let board = leaderboard(
+        vec![
+            strength_scores("Heavy", &history(), &heavy),
+            strength_scores("Incomplete", &history()[..1], &heavy),
+            strength_scores("Light", &light_history, &light),
+            strength_scores("Twin", &light_history, &light),
+        ],
+        ScoreFormula::Dots,
+    );
+
+    let ranks: Vec<(u32, &str)> = board
+        .entries
+        .iter()
+        .map(|entry| (entry.rank, entry.scores.athlete.as_str()))
+        .collect();
+    assert_eq!(ranks, vec![(1, "Light"), (1, "Twin"), (3, "Heavy")]);
+    assert_eq!(board.unranked, vec!["Incomplete"]);
+    assert_eq!(
+        board.to_string().lines().take(2).collect::<Vec<_>>(),
+        vec![
+            "Leaderboard by dots: 3 athlete(s), 1 unranked",
+            "  1. Light (female, 60.0 kg): 443.42 with 400.0 kg",
+        ]
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Fixtures
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_body_weight_from_measured_values_of_fixture_logs() {
+    let user = user_from_fixture();
+    let measured_values: Vec<MeasuredValue> = ["2024-11-01T08:00:00Z", "2024-12-05T08:00:00Z"]
+        .iter()
+        .zip(["180", "176"])
+        .map(|(date, value)| {
+            serde_json::from_value(json!({
+                "_links": { "measurement": { "href": "/api/users/u1/measurements/body-weight" } },
+                "id": format!("mv-{date}"),
+                "created": date,
+                "date": date,
+                "cells": [{ "id": "c1", "cellType": "BODYWEIGHT", "value": value }]
+            }))
+            .unwrap()
+        })
+        .collect();
+    let body_weights =
+        BodyWeight::from_measured_values(&measured_values, "body-weight", WeightUnit::Pounds);
+    let workouts = DataTransformer::new()
+        .with_preferences(&user.id, user.preferences.clone())
+        .with_body_weights(body_weights)
+        .get_measurements_from_logs(&user.embedded.log)
+        .unwrap();
+
+    let options = ScoreOptions::new().with_big_three(BigThree::new([(
+        "ca9ee259-a69f-4839-bbf9-46ba8cf0d7d6",
+        Lift::Bench,
+    )]));
+    let scores = strength_scores("Fixture", &workouts, &options);
+
+    let bench = scores.bench.as_ref().unwrap();
+    let bench_workout = workouts
+        .iter()
+        .find(|workout| workout.id == bench.workout_id)
+        .unwrap();
+    assert_eq!(scores.body_weight, bench_workout.body_weight);
+    assert!(scores.body_weight.is_some());
+    assert_eq!(
+        (scores.squat.as_ref(), scores.total, scores.dots),
+        (None, None, None)
+    );
+}
@@ -0,0 +1,119 @@
+use strong_api_lib::data_transformer::DataTransformer;
+use strong_api_lib::data_transformer::stream::read_logs;
+use strong_api_lib::models::workout::Log;
+
+mod common;
+use common::{logs_from_fixture, measurements_from_fixture};
+
+// ---------------------------------------------------------------------------
+// Workouts iterator
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_iterator_matches_batch_transformation() {
+    let transformer =
+        DataTransformer::new().with_measurements_response(measurements_from_fixture());
+    let logs = logs_from_fixture();
+    let (expected, expected_diagnostics) = transformer
+        .get_workouts_with_diagnostics(&Some(logs.clone()))
+        .unwrap();
+
+    // Borrowed logs
+    let mut workouts = transformer.workouts(&logs);
+    let borrowed: Vec<_> = workouts.by_ref().collect();
+    assert_eq!(borrowed, expected);
+    assert_eq!(workouts.into_diagnostics(), expected_diagnostics);
+
+    // Owned logs
+    let owned: Vec<_> = transformer.workouts(logs).collect();
+    assert_eq!(owned, expected);
+}
+
+#[test]
+fn test_diagnostics_are_collected_while_iterating() {
+    let transformer =
+        DataTransformer::new().with_measurements_response(measurements_from_fixture());
+    let logs = logs_from_fixture();
+    let mut workouts = transformer.workouts(&logs);
+
+    assert!(workouts.diagnostics().is_empty());
+    workouts.by_ref().for_each(drop);
+    // The fixture contains exercises missing from the measurements fixture.
+    assert!(!workouts.diagnostics().unresolved_measurements.is_empty());
+}
+
+// ---------------------------------------------------------------------------
+// Reading logs
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_read_logs_from_json_array() {
+    let logs = logs_from_fixture();
+    let json = serde_json::to_string_pretty(&logs).unwrap();
+
+    let read: Vec<Log> = read_logs(json.as_bytes())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(read, logs);
+}
+
+#[test]
+fn test_read_logs_from_newline_delimited_json() {
+    let logs = logs_from_fixture();
+    let json: String = logs
+        .iter()
+        .map(|log| serde_json::to_string(log).unwrap() + "\n")
+        .collect();
+
+    let read: Vec<Log> = read_logs(json.as_bytes())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(read, logs);
+}
+
+#[test]
+fn test_read_logs_from_empty_input() {
+    assert_eq!(read_logs(" [ ] ".as_bytes()).count(), 0);
+    assert_eq!(read_logs("".as_bytes()).count(), 0);
+}
+
+#[test]
+fn test_read_logs_stops_after_an_error() {
+    let log = serde_json::to_string(&logs_from_fixture()[0]).unwrap();
+    let json = format!("[{log} {log}]");
+
+    let read: Vec<_> = read_logs(json.as_bytes()).collect();
+    assert_eq!(read.len(), 2);
+    assert!(read[0].is_ok());
+    assert!(read[1].is_err());
+}
+
+#[test]
+fn test_streamed_logs_can_be_transformed() {
+    let transformer =
+        DataTransformer::new().with_measurements_response(measurements_from_fixture());
+    let logs = logs_from_fixture();
+    let json = serde_json::to_string(&logs).unwrap();
+
+    let streamed: Vec<_> = transformer
+        .workouts(read_logs(json.as_bytes()).map(Result::unwrap))
+        .collect();
+    assert_eq!(
+        streamed,
+        transformer.get_measurements_from_logs(&Some(logs)).unwrap()
+    );
+}
+
+#[test]
+fn test_read_logs_with_brackets_and_quotes_in_strings() {
+    let mut log = logs_from_fixture()[0].clone();
+    log.name = Some(strong_api_lib::models::common::Name::from(
+        r#"Push } ] {"day" \ 1"#.to_string(),
+    ));
+    let json = serde_json::to_string(&vec![log.clone(), log.clone()]).unwrap();
+
+    let read: Vec<Log> = read_logs(json.as_bytes())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(read, vec![log.clone(), log]);
+}
@@ -1,6 +1,7 @@
 use reqwest::Url;
+use strong_api_lib::models::workout::Log;
 use strong_api_lib::strong_api::{Includes, StrongApi};
-use wiremock::matchers::{method, path, query_param};
+use wiremock::matchers::{body_partial_json, method, path, query_param};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 async fn start_server() -> MockServer {
     MockServer::start().await
@@ -332,3 +333,65 @@ async fn test_get_logs_raw_send_failure_returns_error() {
     api.user_id = Some("00000000-0000-0000-0000-000000000001".to_string());
     assert!(api.get_logs_raw().await.is_err());
 }
+// ---------------------------------------------------------------------------
+// create_template — happy path + error paths
+// ---------------------------------------------------------------------------
+fn template() -> Log {
+    Log {
+        id: "bbbbbbbb-0000-0000-0000-000000000001".to_string(),
+        log_type: "TEMPLATE".to_string(),
+        ..Default::default()
+    }
+}
+#[tokio::test]
+async fn test_create_template_success() {
+    let server = start_server().await;
+    Mock::given(method("POST"))
+        .and(path(
+            "/api/users/00000000-0000-0000-0000-000000000001/templates",
+        ))
+        .and(body_partial_json(
+            serde_json::json!({ "logType": "TEMPLATE" }),
+        ))
+        .respond_with(ResponseTemplate::new(201).set_body_json(template()))
+        .mount(&server)
+        .await;
+    let mut api = api(&server);
+    api.access_token = Some("token".to_string());
+    api.user_id = Some("00000000-0000-0000-0000-000000000001".to_string());
+    let created = api.create_template(&template()).await.unwrap();
+    assert_eq!(created, template());
+}
+#[tokio::test]
+async fn test_create_template_without_user_id_returns_error() {
+    let mut api = refused_api();
+    api.access_token = Some("token".to_string());
+    let result = api.create_template(&template()).await;
+    assert!(result.unwrap_err().to_string().contains("Missing user id"));
+}
+#[tokio::test]
+async fn test_create_template_api_error_response() {
+    let server = start_server().await;
+    Mock::given(method("POST"))
+        .and(path(
+            "/api/users/00000000-0000-0000-0000-000000000001/templates",
+        ))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "code": "BAD_REQUEST",
+            "description": "Invalid template"
+        })))
+        .mount(&server)
+        .await;
+    let mut api = api(&server);
+    api.access_token = Some("token".to_string());
+    api.user_id = Some("00000000-0000-0000-0000-000000000001".to_string());
+    let result = api.create_template(&template()).await;
+    assert!(result.unwrap_err().to_string().contains("BAD_REQUEST"));
+}
+#[tokio::test]
+async fn test_create_template_send_failure_returns_error() {
+    let mut api = refused_api();
+    api.access_token = Some("token".to_string());
+    api.user_id = Some("00000000-0000-0000-0000-000000000001".to_string());
+    assert!(api.create_template(&template()).await.is_err());
+}
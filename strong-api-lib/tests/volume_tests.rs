@@ -0,0 +1,222 @@
+use strong_api_lib::analytics::calendar::{iso_week, month_start, week_start};
+use strong_api_lib::analytics::volume::{
+    Volume, volume_by_exercise, volume_by_month, volume_by_tag, volume_by_week, volume_by_workout,
+};
+use strong_api_lib::data_transformer::{DataTransformer, Set, Workout};
+use time::{Weekday, macros::date};
+
+mod common;
+use common::{exercise, measurements_from_fixture, set, set_at_rpe, user_from_fixture, workout};
+
+const LEGS_ID: &str = "57f573f8-f797-4483-bc1f-5911a70463a6";
+const ARMS_ID: &str = "0973c5dd-5b62-4e9e-a15e-114cb94f440d";
+
+fn history() -> Vec<Workout> {
+    vec![
+        // Sunday evening in UTC, but already Monday, 2 December in Berlin.
+        workout(
+            "w1",
+            "2024-12-01T23:30:00Z",
+            vec![
+                exercise(LEGS_ID, "Deadlift", vec![set_at_rpe(100.0, 5, 8.0)]),
+                exercise(ARMS_ID, "Triceps", vec![set(20.0, 10)]),
+            ],
+        ),
+        workout(
+            "w2",
+            "2024-12-05T17:00:00Z",
+            vec![exercise(
+                LEGS_ID,
+                "Deadlift",
+                vec![set_at_rpe(110.0, 3, 9.0)],
+            )],
+        ),
+        workout(
+            "w3",
+            "2024-11-30T10:00:00Z",
+            vec![exercise(ARMS_ID, "Triceps", vec![set_at_rpe(25.0, 8, 6.5)])],
+        ),
+    ]
+}
+
+// ---------------------------------------------------------------------------
+// Volume
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_volume_of_sets() {
+    let mut incomplete = set_at_rpe(200.0, 5, 10.0);
+    incomplete.is_completed = false;
+    let bodyweight = Set {
+        reps: 12,
+        is_completed: true,
+        ..Default::default()
+    };
+
+    let volume = Volume::of_sets(&[
+        set_at_rpe(100.0, 5, 7.0),
+        set_at_rpe(100.0, 5, 6.5),
+        incomplete,
+        bodyweight,
+    ]);
+    assert_eq!(
+        volume,
+        Volume {
+            sets: 3,
+            reps: 22,
+            tonnage: 1000.0,
+            hard_sets: 1,
+        }
+    );
+}
+
+#[test]
+fn test_volume_by_workout() {
+    let volumes = volume_by_workout(&history());
+    let tonnage: Vec<_> = volumes
+        .iter()
+        .map(|volume| (volume.workout_id.as_str(), volume.volume.tonnage))
+        .collect();
+    assert_eq!(tonnage, vec![("w1", 700.0), ("w2", 330.0), ("w3", 200.0)]);
+}
+
+#[test]
+fn test_volume_by_exercise() {
+    let volumes = volume_by_exercise(&history());
+    assert_eq!(volumes.len(), 2);
+    assert_eq!(volumes[0].exercise, "Deadlift");
+    assert_eq!(volumes[0].workouts, 2);
+    assert_eq!(volumes[0].volume.tonnage, 830.0);
+    assert_eq!(volumes[0].volume.hard_sets, 2);
+    assert_eq!(volumes[1].exercise, "Triceps");
+    assert_eq!(volumes[1].volume.reps, 18);
+}
+
+#[test]
+fn test_volume_by_week_uses_local_dates() {
+    let volumes = volume_by_week(&history(), Weekday::Monday);
+    let weeks: Vec<_> = volumes
+        .iter()
+        .map(|week| (week.start, week.iso_week, week.workouts))
+        .collect();
+    assert_eq!(
+        weeks,
+        vec![
+            (date!(2024 - 11 - 25), 48, 1),
+            (date!(2024 - 12 - 02), 49, 2)
+        ]
+    );
+}
+
+#[test]
+fn test_volume_by_week_respects_first_weekday() {
+    let volumes = volume_by_week(&history(), Weekday::Sunday);
+    let weeks: Vec<_> = volumes
+        .iter()
+        .map(|week| (week.start, week.workouts))
+        .collect();
+    assert_eq!(
+        weeks,
+        vec![(date!(2024 - 11 - 24), 1), (date!(2024 - 12 - 01), 2)]
+    );
+    // With weeks starting on Saturday, 30 November all workouts are in one week.
+    let volumes = volume_by_week(&history(), Weekday::Saturday);
+    assert_eq!(volumes[0].start, date!(2024 - 11 - 30));
+    assert_eq!(volumes[0].workouts, 3);
+}
+
+#[test]
+fn test_volume_by_month() {
+    let volumes = volume_by_month(&history());
+    let months: Vec<_> = volumes
+        .iter()
+        .map(|month| (month.start, month.workouts, month.volume.tonnage))
+        .collect();
+    assert_eq!(
+        months,
+        vec![
+            (date!(2024 - 11 - 01), 1, 200.0),
+            (date!(2024 - 12 - 01), 2, 1030.0)
+        ]
+    );
+}
+
+#[test]
+fn test_volume_by_tag() {
+    let volumes = volume_by_tag(&history(), &measurements_from_fixture());
+    let tags: Vec<_> = volumes
+        .iter()
+        .map(|tag| (tag.tag.as_str(), tag.workouts, tag.volume.sets))
+        .collect();
+    assert_eq!(tags, vec![("arms", 2, 2), ("legs", 2, 2)]);
+}
+
+#[test]
+fn test_volumes_serialize_dates_as_iso_strings() {
+    let value = serde_json::to_value(&volume_by_week(&history(), Weekday::Monday)[0]).unwrap();
+    assert_eq!(value["start"], "2024-11-25");
+    assert_eq!(value["volume"]["tonnage"], 200.0);
+}
+
+#[test]
+fn test_volumes_of_fixture() {
+    let user = user_from_fixture();
+    let workouts = DataTransformer::new()
+        .with_measurements_response(measurements_from_fixture())
+        .get_measurements_from_logs(&user.embedded.log)
+        .unwrap();
+
+    let total: f32 = volume_by_workout(&workouts)
+        .iter()
+        .map(|workout| workout.volume.tonnage)
+        .sum();
+    let weekly: f32 = volume_by_week(&workouts, user.first_weekday())
+        .iter()
+        .map(|week| week.volume.tonnage)
+        .sum();
+    assert!(total > 0.0);
+    assert!((total - weekly).abs() < 0.01);
+}
+
+// ---------------------------------------------------------------------------
+// Calendar
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_week_start() {
+    assert_eq!(
+        week_start(date!(2024 - 12 - 04), Weekday::Monday),
+        date!(2024 - 12 - 02)
+    );
+    assert_eq!(
+        week_start(date!(2024 - 12 - 04), Weekday::Sunday),
+        date!(2024 - 12 - 01)
+    );
+    assert_eq!(
+        week_start(date!(2024 - 12 - 01), Weekday::Sunday),
+        date!(2024 - 12 - 01)
+    );
+}
+
+#[test]
+fn test_iso_week_of_weeks_not_starting_on_monday() {
+    assert_eq!(iso_week(date!(2024 - 12 - 30)), (2025, 1));
+    // Sunday, 29 December 2024 to Saturday, 4 January 2025 contains Thursday, 2 January.
+    assert_eq!(iso_week(date!(2024 - 12 - 29)), (2025, 1));
+    assert_eq!(iso_week(date!(2024 - 12 - 01)), (2024, 49));
+}
+
+#[test]
+fn test_month_start() {
+    assert_eq!(month_start(date!(2024 - 02 - 29)), date!(2024 - 02 - 01));
+}
+
+#[test]
+fn test_first_weekday_of_user() {
+    let mut user = user_from_fixture();
+    assert_eq!(user.first_weekday(), Weekday::Monday);
+    user.first_week_day = "SUNDAY".to_string();
+    assert_eq!(user.first_weekday(), Weekday::Sunday);
+    user.first_week_day = String::new();
+    assert_eq!(user.first_weekday(), Weekday::Monday);
+}
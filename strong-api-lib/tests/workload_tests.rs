@@ -0,0 +1,164 @@
+use strong_api_lib::analytics::workload::{
+    AcwrFlag, WorkloadMeasure, WorkloadOptions, daily_workload,
+};
+use strong_api_lib::data_transformer::Workout;
+use time::{Date, Duration, macros::date};
+
+mod common;
+use common::{WorkoutExt, exercise, set_at_rpe, workout};
+
+const FIRST_DAY: Date = date!(2024 - 01 - 01);
+
+/// A one hour session `day` days after the first day with a tonnage of `tonnage` kg.
+fn session(day: i64, tonnage: f32) -> Workout {
+    let date = FIRST_DAY + Duration::days(day);
+    workout(
+        &format!("w{day}"),
+        &format!("{date}T10:00:00Z"),
+        vec![exercise("", "", vec![set_at_rpe(tonnage, 1, 8.0)])],
+    )
+    .with_end_date(&format!("{date}T11:00:00Z"))
+}
+
+/// Four weeks with 100 kg every day.
+fn steady() -> Vec<Workout> {
+    (0..28).map(|day| session(day, 100.0)).collect()
+}
+
+// ---------------------------------------------------------------------------
+// Workout load
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_workout_load() {
+    let mut workout = session(0, 100.0);
+    workout.exercises[0].sets.push(set_at_rpe(50.0, 10, 6.0));
+    let mut incomplete = set_at_rpe(500.0, 10, 10.0);
+    incomplete.is_completed = false;
+    workout.exercises[0].sets.push(incomplete);
+
+    assert_eq!(WorkloadMeasure::Tonnage.workout_load(&workout), 600.0);
+    // 60 minutes at a mean RPE of 7.
+    assert_eq!(WorkloadMeasure::SessionRpe.workout_load(&workout), 420.0);
+
+    workout.end_date = None;
+    assert_eq!(WorkloadMeasure::SessionRpe.workout_load(&workout), 0.0);
+}
+
+// ---------------------------------------------------------------------------
+// Daily series
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_steady_load() {
+    let series = daily_workload(
+        &steady(),
+        &WorkloadOptions::new(),
+        FIRST_DAY + Duration::days(27),
+    );
+
+    assert_eq!(series.len(), 28);
+    assert_eq!(series[0].date, FIRST_DAY);
+    assert_eq!(series[6].acute, 700.0);
+    // No ACWR before there are 28 days of history.
+    assert!(series[..27].iter().all(|day| day.acwr.is_none()));
+    let last = &series[27];
+    assert_eq!(last.acute, 700.0);
+    assert_eq!(last.chronic, 700.0);
+    assert_eq!(last.acwr, Some(1.0));
+    assert_eq!(last.flag, None);
+    // Every day had the same load.
+    assert_eq!(last.monotony, None);
+    assert_eq!(last.strain, None);
+}
+
+#[test]
+fn test_spike_is_flagged() {
+    let mut workouts = steady();
+    workouts.push(session(28, 1000.0));
+    let series = daily_workload(
+        &workouts,
+        &WorkloadOptions::new(),
+        FIRST_DAY + Duration::days(28),
+    );
+
+    let spike = series.last().unwrap();
+    assert_eq!(spike.acute, 1600.0);
+    assert_eq!(spike.chronic, 925.0);
+    assert!((spike.acwr.unwrap() - 1.7297).abs() < 0.001);
+    assert_eq!(spike.flag, Some(AcwrFlag::AboveBand));
+
+    let wide = WorkloadOptions::new().with_acwr_band(0.5, 2.0);
+    let series = daily_workload(&workouts, &wide, FIRST_DAY + Duration::days(28));
+    assert_eq!(series.last().unwrap().flag, None);
+}
+
+#[test]
+fn test_rest_days_up_to_until_are_included() {
+    let series = daily_workload(
+        &steady(),
+        &WorkloadOptions::new(),
+        FIRST_DAY + Duration::days(31),
+    );
+
+    let last = series.last().unwrap();
+    assert_eq!(last.date, date!(2024 - 02 - 01));
+    assert_eq!(last.load, 0.0);
+    assert_eq!(last.acute, 300.0);
+    assert_eq!(last.chronic, 600.0);
+    assert_eq!(last.flag, Some(AcwrFlag::BelowBand));
+}
+
+#[test]
+fn test_monotony_and_strain() {
+    let workouts: Vec<_> = (0..7).step_by(2).map(|day| session(day, 100.0)).collect();
+    let series = daily_workload(
+        &workouts,
+        &WorkloadOptions::new(),
+        FIRST_DAY + Duration::days(6),
+    );
+
+    let last = series.last().unwrap();
+    assert_eq!(last.acute, 400.0);
+    assert!((last.monotony.unwrap() - 1.1547).abs() < 0.001);
+    assert!((last.strain.unwrap() - 461.88).abs() < 0.01);
+}
+
+#[test]
+fn test_session_rpe_measure() {
+    let options = WorkloadOptions::new().with_measure(WorkloadMeasure::SessionRpe);
+    let series = daily_workload(&steady(), &options, FIRST_DAY + Duration::days(27));
+    assert_eq!(series[0].load, 480.0);
+    assert_eq!(series[0].measure, WorkloadMeasure::SessionRpe);
+    assert_eq!(series[27].acwr, Some(1.0));
+}
+
+#[test]
+fn test_no_workouts_or_until_before_first_workout() {
+    assert!(daily_workload(&[], &WorkloadOptions::new(), FIRST_DAY).is_empty());
+    assert!(
+        daily_workload(
+            &steady(),
+            &WorkloadOptions::new(),
+            FIRST_DAY - Duration::days(1)
+        )
+        .is_empty()
+    );
+}
+
+#[test]
+fn test_daily_workload_serialization() {
+    let mut workouts = steady();
+    workouts.push(session(28, 1000.0));
+    let series = daily_workload(
+        &workouts,
+        &WorkloadOptions::new(),
+        FIRST_DAY + Duration::days(28),
+    );
+
+    let value = serde_json::to_value(series.last().unwrap()).unwrap();
+    assert_eq!(value["date"], "2024-01-29");
+    assert_eq!(value["measure"], "tonnage");
+    assert_eq!(value["flag"], "above_band");
+    assert_eq!(AcwrFlag::AboveBand.to_string(), "above_band");
+}
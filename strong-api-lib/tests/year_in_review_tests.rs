@@ -0,0 +1,218 @@
+use strong_api_lib::analytics::personal_records::RecordKind;
+use strong_api_lib::analytics::year_in_review::year_in_review;
+use strong_api_lib::data_transformer::Workout;
+use time::macros::date;
+use time::{Month, Weekday};
+
+mod common;
+use common::{SetExt, WorkoutExt, exercise, set, workout, workouts_from_fixture};
+
+/// A squat day in 2023, then three days in a row and a lone Monday in 2024.
+fn history() -> Vec<Workout> {
+    vec![
+        workout(
+            "w0",
+            "2023-12-01T17:00:00Z",
+            vec![exercise("Squat-id", "Squat", vec![set(100.0, 5)])],
+        )
+        .with_end_date("2023-12-01T18:00:00Z"),
+        // Wednesday to Friday.
+        workout(
+            "w1",
+            "2024-03-06T17:00:00Z",
+            vec![
+                exercise("Squat-id", "Squat", vec![set(110.0, 5), set(110.0, 5)]),
+                exercise("Curl-id", "Curl", vec![set(20.0, 10)]),
+            ],
+        )
+        .with_end_date("2024-03-06T18:30:00Z"),
+        workout(
+            "w2",
+            "2024-03-07T17:00:00Z",
+            vec![exercise("Bench-id", "Bench", vec![set(80.0, 5)])],
+        )
+        .with_end_date("2024-03-07T18:00:00Z"),
+        workout(
+            "w3",
+            "2024-03-08T17:00:00Z",
+            vec![
+                exercise("Squat-id", "Squat", vec![set(120.0, 3)]),
+                exercise("Curl-id", "Curl", vec![set(22.5, 8)]),
+            ],
+        )
+        .with_end_date("2024-03-08T18:00:00Z"),
+        // Starts on Monday, July 1st in Berlin.
+        workout(
+            "w4",
+            "2024-06-30T22:30:00Z",
+            vec![exercise("Curl-id", "Curl", vec![set(25.0, 8)])],
+        )
+        .with_end_date("2024-06-30T23:00:00Z"),
+        // Without completed sets.
+        workout(
+            "w5",
+            "2024-08-01T17:00:00Z",
+            vec![exercise(
+                "Squat-id",
+                "Squat",
+                vec![set(200.0, 1).incomplete()],
+            )],
+        )
+        .with_end_date("2024-08-01T18:00:00Z"),
+    ]
+}
+
+// ---------------------------------------------------------------------------
+// Totals
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_totals_of_the_year() {
+    let review = year_in_review(&history(), 2024).unwrap();
+
+    assert_eq!(review.sessions, 4);
+    assert_eq!(review.training_days, 4);
+    assert_eq!(review.hours, 4.0);
+    assert_eq!(review.sets, 7);
+    assert_eq!(
+        review.tonnage,
+        1100.0 + 200.0 + 400.0 + 360.0 + 180.0 + 200.0
+    );
+
+    let favourites: Vec<(&str, u32, u32)> = review
+        .favourite_exercises
+        .iter()
+        .map(|favourite| {
+            (
+                favourite.exercise.as_str(),
+                favourite.sessions,
+                favourite.sets,
+            )
+        })
+        .collect();
+    assert_eq!(
+        favourites,
+        vec![("Curl", 3, 3), ("Squat", 2, 3), ("Bench", 1, 1)]
+    );
+}
+
+#[test]
+fn test_streaks_and_busiest_days() {
+    let review = year_in_review(&history(), 2024).unwrap();
+
+    let streak = review.longest_streak.unwrap();
+    assert_eq!((streak.start, streak.length), (date!(2024 - 03 - 06), 3));
+    assert_eq!(review.longest_weekly_streak.unwrap().length, 1);
+
+    // Wednesday, Thursday, Friday and Monday once each, so the first of the week wins.
+    assert_eq!(review.busiest_weekday, Some(Weekday::Monday));
+    assert_eq!(review.workouts_by_weekday[0], (Weekday::Monday, 1));
+    assert_eq!(review.busiest_month, Some(Month::March));
+    assert_eq!(review.workouts_by_month[6], (Month::July, 1));
+}
+
+#[test]
+fn test_heatmap_covers_every_day() {
+    let review = year_in_review(&history(), 2024).unwrap();
+
+    assert_eq!(review.heatmap.len(), 366);
+    assert_eq!(review.heatmap[0].date, date!(2024 - 01 - 01));
+    assert_eq!(review.heatmap[365].date, date!(2024 - 12 - 31));
+    let july_first = review
+        .heatmap
+        .iter()
+        .find(|day| day.date == date!(2024 - 07 - 01))
+        .unwrap();
+    assert_eq!((july_first.workouts, july_first.tonnage), (1, 200.0));
+    assert_eq!(
+        review.heatmap.iter().map(|day| day.workouts).sum::<u32>(),
+        4
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Records
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_biggest_records_grow_from_the_years_before() {
+    let review = year_in_review(&history(), 2024).unwrap();
+
+    // Squat went from 100 kg to 120 kg, the curls grew from the first curl workout and the one
+    // bench workout set no record.
+    let records: Vec<(&str, RecordKind, f32)> = review
+        .biggest_records
+        .iter()
+        .map(|record| (record.exercise.as_str(), record.kind, record.after))
+        .collect();
+    assert_eq!(
+        records,
+        vec![
+            ("Curl", RecordKind::HeaviestWeight, 25.0),
+            ("Squat", RecordKind::HeaviestWeight, 120.0),
+            ("Curl", RecordKind::BestOneRepMax, 31.666666),
+            ("Squat", RecordKind::BestOneRepMax, 132.0),
+        ]
+    );
+    assert_eq!(review.biggest_records[0].before, 20.0);
+    assert_eq!(review.biggest_records[0].workout_id, "w4");
+    assert_eq!(review.biggest_records[1].before, 100.0);
+    assert_eq!(review.biggest_records[1].workout_id, "w3");
+
+    assert_eq!(
+        review.to_string(),
+        "2024 in review: 4 session(s), 4.0 hour(s), 7 sets, 2440 kg\
+         \n  Longest streak: 3 day(s) from 2024-03-06\
+         \n  Longest weekly streak: 1 week(s) from 2024-07-01\
+         \n  Busiest weekday: Monday, busiest month: March\
+         \n  Favourite: Curl (3 session(s), 3 sets)\
+         \n  Favourite: Squat (2 session(s), 3 sets)\
+         \n  Favourite: Bench (1 session(s), 1 sets)\
+         \n  Record: Curl heaviest_weight 20.0 -> 25.0 kg (+25.0%)\
+         \n  Record: Squat heaviest_weight 100.0 -> 120.0 kg (+20.0%)\
+         \n  Record: Curl best_one_rep_max 26.7 -> 31.7 kg (+18.7%)\
+         \n  Record: Squat best_one_rep_max 116.7 -> 132.0 kg (+13.1%)"
+    );
+}
+
+#[test]
+fn test_empty_year() {
+    let review = year_in_review(&history(), 2022).unwrap();
+
+    assert_eq!(review.sessions, 0);
+    assert_eq!(review.longest_streak, None);
+    assert_eq!(review.busiest_weekday, None);
+    assert_eq!(review.heatmap.len(), 365);
+    assert!(review.biggest_records.is_empty());
+}
+
+#[test]
+fn test_years_out_of_range() {
+    assert!(year_in_review(&history(), 99999).is_none());
+    assert!(year_in_review(&history(), 9999).is_none());
+    assert!(year_in_review(&history(), -99999).is_none());
+    assert_eq!(year_in_review(&history(), 9998).unwrap().heatmap.len(), 365);
+}
+
+// ---------------------------------------------------------------------------
+// Fixtures
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_year_in_review_of_fixture() {
+    let workouts = workouts_from_fixture();
+
+    // The fixture's five workouts run from November 20th to December 11th 2024.
+    let review = year_in_review(&workouts, 2024).unwrap();
+    assert_eq!(review.sessions, 5);
+    assert!(review.hours > 0.0);
+    assert!(review.tonnage > 0.0);
+    assert!(!review.favourite_exercises.is_empty());
+    assert_eq!(
+        review.workouts_by_month[10].1 + review.workouts_by_month[11].1,
+        5
+    );
+
+    let value = serde_json::to_value(&review).unwrap();
+    assert_eq!(value["heatmap"][0]["date"], "2024-01-01");
+}